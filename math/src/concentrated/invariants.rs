@@ -0,0 +1,138 @@
+use crate::concentrated::*;
+use proptest::prelude::*;
+use sp_arithmetic::Permill;
+
+pub const ONE: Balance = 1_000_000_000_000;
+
+fn tick() -> impl Strategy<Value = Tick> {
+    MIN_TICK..=MAX_TICK
+}
+
+fn price_tick() -> impl Strategy<Value = Tick> {
+    -50_000..50_000
+}
+
+fn range_width() -> impl Strategy<Value = Tick> {
+    1..10_000
+}
+
+fn liquidity() -> impl Strategy<Value = Liquidity> {
+    1000 * ONE..10_000_000 * ONE
+}
+
+fn trade_amount() -> impl Strategy<Value = Balance> {
+    ONE..100 * ONE
+}
+
+fn trade_fee() -> impl Strategy<Value = Permill> {
+    (0u32..100_000u32).prop_map(Permill::from_parts)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn tick_invariants(tick in tick()) {
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+
+        assert_eq!(tick_at_sqrt_price(sqrt_price), Some(tick));
+
+        if tick < MAX_TICK {
+            assert!(sqrt_price_at_tick(tick + 1).unwrap() > sqrt_price);
+            assert_eq!(tick_at_sqrt_price(sqrt_price_at_tick(tick + 1).unwrap() - 1), Some(tick));
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn swap_step_invariants(tick in price_tick(),
+        width in range_width(),
+        liquidity in liquidity(),
+        amount in trade_amount(),
+        fee in trade_fee(),
+        a_to_b in any::<bool>(),
+    ) {
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+        let target_tick = if a_to_b { tick - width } else { tick + width };
+        let sqrt_price_target = sqrt_price_at_tick(target_tick).unwrap();
+
+        let step = calculate_swap_step(sqrt_price, sqrt_price_target, liquidity, amount, fee).unwrap();
+
+        assert!(step.amount_in + step.fee <= amount);
+
+        let (lower, upper) = if a_to_b {
+            (sqrt_price_target, sqrt_price)
+        } else {
+            (sqrt_price, sqrt_price_target)
+        };
+        assert!(lower <= step.sqrt_price && step.sqrt_price <= upper, "Price moved beyond target");
+
+        // Virtual reserves of the range must cover what can be withdrawn after the step
+        let (lower_tick, upper_tick) = (tick.min(target_tick) - 1, tick.max(target_tick) + 1);
+        let (range_lower, range_upper) = (sqrt_price_at_tick(lower_tick).unwrap(), sqrt_price_at_tick(upper_tick).unwrap());
+
+        let before = calculate_position_amounts(sqrt_price, range_lower, range_upper, liquidity, true).unwrap();
+        let after = calculate_position_amounts(step.sqrt_price, range_lower, range_upper, liquidity, false).unwrap();
+
+        if a_to_b {
+            assert!(after.0 <= before.0 + step.amount_in);
+            assert!(after.1 <= before.1 - step.amount_out);
+        } else {
+            assert!(after.0 <= before.0 - step.amount_out);
+            assert!(after.1 <= before.1 + step.amount_in);
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn swap_exact_out_invariants(tick in price_tick(),
+        width in range_width(),
+        liquidity in liquidity(),
+        amount in trade_amount(),
+        fee in trade_fee(),
+        a_to_b in any::<bool>(),
+    ) {
+        let (tick_lower, tick_upper) = (tick - width, tick + width);
+        let ticks = vec![(tick_lower, liquidity as i128), (tick_upper, -(liquidity as i128))];
+        let state = SwapState {
+            sqrt_price: sqrt_price_at_tick(tick).unwrap(),
+            tick,
+            liquidity,
+            fee_growth: 0,
+        };
+
+        // Buying an amount costs at least as much as selling for the same amount
+        if let Some(bought) = calculate_swap_exact_out(state, &ticks, amount, fee, Permill::zero(), a_to_b) {
+            let sold = calculate_swap(state, &ticks, bought.amount_in, fee, Permill::zero(), a_to_b).unwrap();
+
+            assert!(sold.amount_out >= amount);
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn position_invariants(tick in price_tick(),
+        lower_width in range_width(),
+        upper_width in range_width(),
+        liquidity in liquidity(),
+    ) {
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+        let sqrt_price_lower = sqrt_price_at_tick(tick - lower_width).unwrap();
+        let sqrt_price_upper = sqrt_price_at_tick(tick + upper_width).unwrap();
+
+        let (amount_a, amount_b) = calculate_position_amounts(sqrt_price, sqrt_price_lower, sqrt_price_upper, liquidity, true).unwrap();
+
+        // Liquidity provided by the amounts paid for a position is not more than its liquidity
+        // and amounts withdrawn for it are not more than those paid
+        let provided = calculate_liquidity_for_amounts(sqrt_price, sqrt_price_lower, sqrt_price_upper, amount_a, amount_b).unwrap();
+        let (withdrawn_a, withdrawn_b) = calculate_position_amounts(sqrt_price, sqrt_price_lower, sqrt_price_upper, provided, false).unwrap();
+
+        assert!(provided >= liquidity - 1);
+        assert!(withdrawn_a <= amount_a && withdrawn_b <= amount_b);
+    }
+}
@@ -0,0 +1,809 @@
+use crate::{ensure, to_balance};
+use core::convert::TryFrom;
+use primitive_types::{U256, U512};
+use sp_arithmetic::{PerThing, Permill};
+
+pub type Balance = u128;
+
+/// Liquidity of a price range, as square root of the product of virtual reserves.
+pub type Liquidity = u128;
+
+/// Square root of price of asset A denominated in asset B, in Q64.64 fixed point.
+pub type SqrtPrice = u128;
+
+/// Fee per unit of liquidity accumulated over time, in Q64.64 fixed point.
+/// Only differences of fee growths are meaningful, so it is allowed to overflow.
+pub type FeeGrowth = u128;
+
+/// Index of price 1.0001^TICK.
+pub type Tick = i32;
+
+/// Number of fractional bits of `SqrtPrice` and `FeeGrowth`.
+const RESOLUTION: usize = 64;
+
+/// Lowest tick, of which price is approximately 2^-64.
+pub const MIN_TICK: Tick = -443_636;
+
+/// Highest tick, of which price is approximately 2^64.
+pub const MAX_TICK: Tick = 443_636;
+
+/// Square root of price of `MIN_TICK`.
+pub const MIN_SQRT_PRICE: SqrtPrice = 4_295_048_017;
+
+/// Square root of price of `MAX_TICK`.
+pub const MAX_SQRT_PRICE: SqrtPrice = 79_226_673_515_401_279_992_447_579_062;
+
+/// 1 / sqrt(1.0001^(2^i)) in Q128.128 fixed point, for bits 1 to 18 of an absolute tick.
+const TICK_FACTORS: [u128; 18] = [
+    0xfff97272373d413259a46990580e213a,
+    0xfff2e50f5f656932ef12357cf3c7fdcc,
+    0xffe5caca7e10e4e61c3624eaa0941cd0,
+    0xffcb9843d60f6159c9db58835c926644,
+    0xff973b41fa98c081472e6896dfb254c0,
+    0xff2ea16466c96a3843ec78b326b52861,
+    0xfe5dee046a99a2a811c461f1969c3053,
+    0xfcbe86c7900a88aedcffc83b479aa3a4,
+    0xf987a7253ac413176f2b074cf7815e54,
+    0xf3392b0822b70005940c7a398e4b70f3,
+    0xe7159475a2c29b7443b29c7fa6e889d9,
+    0xd097f3bdfd2022b8845ad8f792aa5825,
+    0xa9f746462d870fdf8a65dc1f90e061e5,
+    0x70d869a156d2a1b890bb3df62baf32f7,
+    0x31be135f97d08fd981231505542fcfa6,
+    0x9aa508b5b7a84e1c677de54f3e99bc9,
+    0x5d6af8dedb81196699c329225ee604,
+    0x2216e584f5fa1ea926041bedfe98,
+];
+
+/// 1 / sqrt(1.0001) in Q128.128 fixed point, for bit 0 of an absolute tick.
+const TICK_FACTOR_0: u128 = 0xfffcb933bd6fad37aa2d162d1a594001;
+
+/// Result of a swap step within a range of constant liquidity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapStep {
+    /// Square root of price after the step.
+    pub sqrt_price: SqrtPrice,
+    /// Amount in, without fee.
+    pub amount_in: Balance,
+    pub amount_out: Balance,
+    /// Fee paid in asset in.
+    pub fee: Balance,
+}
+
+/// State of a pool relevant to a swap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapState {
+    pub sqrt_price: SqrtPrice,
+    /// Tick of the range containing the price, i.e. highest tick not above the price.
+    pub tick: Tick,
+    /// Liquidity of the range containing the price.
+    pub liquidity: Liquidity,
+    /// Global fee growth of the asset in.
+    pub fee_growth: FeeGrowth,
+}
+
+/// Result of a swap crossing any number of ticks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapResult {
+    /// Amount in, including fee.
+    pub amount_in: Balance,
+    pub amount_out: Balance,
+    /// Whole fee paid in asset in.
+    pub fee: Balance,
+    /// Part of the fee which is not distributed to liquidity providers.
+    pub protocol_fee: Balance,
+    /// State of the pool after the swap.
+    pub state: SwapState,
+    /// Crossed ticks in order of crossing, with global fee growth of the asset in at the time.
+    pub crossed_ticks: Vec<(Tick, FeeGrowth)>,
+}
+
+/// Dividing with rounding in given direction.
+fn div_rounding(numerator: U512, denominator: U512, round_up: bool) -> Option<U512> {
+    ensure!(!denominator.is_zero());
+
+    let (quotient, remainder) = numerator.div_mod(denominator);
+
+    if round_up && !remainder.is_zero() {
+        quotient.checked_add(U512::one())
+    } else {
+        Some(quotient)
+    }
+}
+
+/// Calculating square root of price of given tick.
+/// Formula : sqrt(1.0001^TICK) * 2^64
+///
+/// - `tick` - tick between `MIN_TICK` and `MAX_TICK`
+///
+/// Returns square root of price rounded up, or None in case of error
+pub fn sqrt_price_at_tick(tick: Tick) -> Option<SqrtPrice> {
+    ensure!((MIN_TICK..=MAX_TICK).contains(&tick));
+
+    let abs_tick = tick.unsigned_abs();
+
+    // Product of factors of set bits is 1 / sqrt(1.0001^|TICK|) in Q128.128
+    let mut ratio = if abs_tick & 1 != 0 {
+        U256::from(TICK_FACTOR_0)
+    } else {
+        U256::one() << 128
+    };
+
+    for (bit, factor) in TICK_FACTORS.iter().enumerate() {
+        if abs_tick & (1 << (bit + 1)) != 0 {
+            ratio = ratio.checked_mul(U256::from(*factor))? >> 128;
+        }
+    }
+
+    if tick > 0 {
+        ratio = U256::MAX.checked_div(ratio)?;
+    }
+
+    let (sqrt_price, remainder) = ratio.div_mod(U256::one() << RESOLUTION);
+    let sqrt_price = if remainder.is_zero() {
+        sqrt_price
+    } else {
+        sqrt_price.checked_add(U256::one())?
+    };
+
+    to_balance!(sqrt_price)
+}
+
+/// Calculating highest tick of which square root of price is not above given one.
+///
+/// - `sqrt_price` - square root of price between `MIN_SQRT_PRICE` and `MAX_SQRT_PRICE`
+///
+/// Returns None in case of error
+pub fn tick_at_sqrt_price(sqrt_price: SqrtPrice) -> Option<Tick> {
+    ensure!((MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&sqrt_price));
+
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+
+    while low < high {
+        // Rounded up, so that the range always shrinks
+        let mid = low + (high - low + 1) / 2;
+
+        if sqrt_price_at_tick(mid)? <= sqrt_price {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Some(low)
+}
+
+/// Calculating square root of price of asset B denominated in asset A.
+/// Formula : 1 / SQRT_PRICE
+///
+/// - `sqrt_price` - square root of price of asset A denominated in asset B
+///
+/// Returns None in case of error
+pub fn calculate_inverse_sqrt_price(sqrt_price: SqrtPrice) -> Option<SqrtPrice> {
+    ensure!(sqrt_price != 0);
+
+    to_balance!((U256::one() << (2 * RESOLUTION)).checked_div(U256::from(sqrt_price))?)
+}
+
+/// Calculating amount of asset A in a price range of given liquidity.
+/// Formula : LIQUIDITY * (SQRT_PRICE_UPPER - SQRT_PRICE_LOWER) / (SQRT_PRICE_UPPER * SQRT_PRICE_LOWER)
+///
+/// - `sqrt_price_a` - square root of price of one end of the range
+/// - `sqrt_price_b` - square root of price of the other end of the range
+/// - `liquidity` - liquidity of the range
+/// - `round_up` - whether the amount is rounded up, e.g. when it is paid to the pool
+///
+/// Returns None in case of error
+pub fn calculate_amount_a_delta(
+    sqrt_price_a: SqrtPrice,
+    sqrt_price_b: SqrtPrice,
+    liquidity: Liquidity,
+    round_up: bool,
+) -> Option<Balance> {
+    let (lower, upper) = if sqrt_price_a < sqrt_price_b {
+        (sqrt_price_a, sqrt_price_b)
+    } else {
+        (sqrt_price_b, sqrt_price_a)
+    };
+
+    let (lower_hp, upper_hp, liquidity_hp) =
+        (U512::from(lower), U512::from(upper), U512::from(liquidity));
+
+    let numerator = (liquidity_hp << RESOLUTION).checked_mul(upper_hp - lower_hp)?;
+    let denominator = upper_hp.checked_mul(lower_hp)?;
+
+    to_balance!(div_rounding(numerator, denominator, round_up)?)
+}
+
+/// Calculating amount of asset B in a price range of given liquidity.
+/// Formula : LIQUIDITY * (SQRT_PRICE_UPPER - SQRT_PRICE_LOWER)
+///
+/// - `sqrt_price_a` - square root of price of one end of the range
+/// - `sqrt_price_b` - square root of price of the other end of the range
+/// - `liquidity` - liquidity of the range
+/// - `round_up` - whether the amount is rounded up, e.g. when it is paid to the pool
+///
+/// Returns None in case of error
+pub fn calculate_amount_b_delta(
+    sqrt_price_a: SqrtPrice,
+    sqrt_price_b: SqrtPrice,
+    liquidity: Liquidity,
+    round_up: bool,
+) -> Option<Balance> {
+    let (lower, upper) = if sqrt_price_a < sqrt_price_b {
+        (sqrt_price_a, sqrt_price_b)
+    } else {
+        (sqrt_price_b, sqrt_price_a)
+    };
+
+    let numerator = U512::from(liquidity).checked_mul(U512::from(upper - lower))?;
+
+    to_balance!(div_rounding(
+        numerator,
+        U512::one() << RESOLUTION,
+        round_up
+    )?)
+}
+
+/// Calculating amounts of both assets of a position of given liquidity at given price.
+/// Only asset A is in a range above the price and only asset B in a range below it.
+///
+/// - `sqrt_price` - square root of current price
+/// - `sqrt_price_lower` - square root of price of lower end of the range
+/// - `sqrt_price_upper` - square root of price of upper end of the range
+/// - `liquidity` - liquidity of the position
+/// - `round_up` - whether the amounts are rounded up, e.g. when they are paid to the pool
+///
+/// Returns amounts of asset A and asset B, or None in case of error
+pub fn calculate_position_amounts(
+    sqrt_price: SqrtPrice,
+    sqrt_price_lower: SqrtPrice,
+    sqrt_price_upper: SqrtPrice,
+    liquidity: Liquidity,
+    round_up: bool,
+) -> Option<(Balance, Balance)> {
+    ensure!(sqrt_price_lower < sqrt_price_upper);
+
+    if sqrt_price <= sqrt_price_lower {
+        let amount_a =
+            calculate_amount_a_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?;
+        Some((amount_a, 0))
+    } else if sqrt_price < sqrt_price_upper {
+        let amount_a = calculate_amount_a_delta(sqrt_price, sqrt_price_upper, liquidity, round_up)?;
+        let amount_b = calculate_amount_b_delta(sqrt_price_lower, sqrt_price, liquidity, round_up)?;
+        Some((amount_a, amount_b))
+    } else {
+        let amount_b =
+            calculate_amount_b_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?;
+        Some((0, amount_b))
+    }
+}
+
+/// Calculating highest liquidity of a position which can be provided by given amounts.
+/// Formula : min(AMOUNT_A * SQRT_PRICE * SQRT_PRICE_UPPER / (SQRT_PRICE_UPPER - SQRT_PRICE), AMOUNT_B / (SQRT_PRICE - SQRT_PRICE_LOWER))
+///
+/// - `sqrt_price` - square root of current price
+/// - `sqrt_price_lower` - square root of price of lower end of the range
+/// - `sqrt_price_upper` - square root of price of upper end of the range
+/// - `amount_a` - maximum amount of asset A
+/// - `amount_b` - maximum amount of asset B
+///
+/// Returns None in case of error
+pub fn calculate_liquidity_for_amounts(
+    sqrt_price: SqrtPrice,
+    sqrt_price_lower: SqrtPrice,
+    sqrt_price_upper: SqrtPrice,
+    amount_a: Balance,
+    amount_b: Balance,
+) -> Option<Liquidity> {
+    ensure!(sqrt_price_lower < sqrt_price_upper);
+
+    let liquidity_for_a = |lower: SqrtPrice, upper: SqrtPrice| -> Option<Liquidity> {
+        let numerator = U512::from(amount_a)
+            .checked_mul(U512::from(lower))?
+            .checked_mul(U512::from(upper))?;
+        let denominator = U512::from(upper - lower) << RESOLUTION;
+        to_balance!(div_rounding(numerator, denominator, false)?)
+    };
+
+    let liquidity_for_b = |lower: SqrtPrice, upper: SqrtPrice| -> Option<Liquidity> {
+        let numerator = U512::from(amount_b) << RESOLUTION;
+        to_balance!(div_rounding(numerator, U512::from(upper - lower), false)?)
+    };
+
+    if sqrt_price <= sqrt_price_lower {
+        liquidity_for_a(sqrt_price_lower, sqrt_price_upper)
+    } else if sqrt_price < sqrt_price_upper {
+        Some(
+            liquidity_for_a(sqrt_price, sqrt_price_upper)?
+                .min(liquidity_for_b(sqrt_price_lower, sqrt_price)?),
+        )
+    } else {
+        liquidity_for_b(sqrt_price_lower, sqrt_price_upper)
+    }
+}
+
+/// Calculating square root of price after selling given amount of asset A within a range.
+/// Formula : LIQUIDITY * SQRT_PRICE / (LIQUIDITY + AMOUNT_IN * SQRT_PRICE)
+///
+/// - `sqrt_price` - square root of current price
+/// - `liquidity` - liquidity of the range
+/// - `amount_in` - amount of asset A, without fee
+///
+/// Returns square root of price rounded up, or None in case of error
+pub fn calculate_sqrt_price_after_amount_a_in(
+    sqrt_price: SqrtPrice,
+    liquidity: Liquidity,
+    amount_in: Balance,
+) -> Option<SqrtPrice> {
+    if amount_in == 0 {
+        return Some(sqrt_price);
+    }
+
+    let (sqrt_price_hp, liquidity_hp) =
+        (U512::from(sqrt_price), U512::from(liquidity) << RESOLUTION);
+
+    let numerator = liquidity_hp.checked_mul(sqrt_price_hp)?;
+    let denominator =
+        liquidity_hp.checked_add(U512::from(amount_in).checked_mul(sqrt_price_hp)?)?;
+
+    to_balance!(div_rounding(numerator, denominator, true)?)
+}
+
+/// Calculating square root of price after selling given amount of asset B within a range.
+/// Formula : SQRT_PRICE + AMOUNT_IN / LIQUIDITY
+///
+/// - `sqrt_price` - square root of current price
+/// - `liquidity` - liquidity of the range
+/// - `amount_in` - amount of asset B, without fee
+///
+/// Returns square root of price rounded down, or None in case of error
+pub fn calculate_sqrt_price_after_amount_b_in(
+    sqrt_price: SqrtPrice,
+    liquidity: Liquidity,
+    amount_in: Balance,
+) -> Option<SqrtPrice> {
+    let delta = div_rounding(
+        U512::from(amount_in) << RESOLUTION,
+        U512::from(liquidity),
+        false,
+    )?;
+
+    to_balance!(U512::from(sqrt_price).checked_add(delta)?)
+}
+
+/// Calculating square root of price after buying given amount of asset A within a range.
+/// Formula : LIQUIDITY * SQRT_PRICE / (LIQUIDITY - AMOUNT_OUT * SQRT_PRICE)
+///
+/// - `sqrt_price` - square root of current price
+/// - `liquidity` - liquidity of the range
+/// - `amount_out` - amount of asset A
+///
+/// Returns square root of price rounded up, or None in case of error
+pub fn calculate_sqrt_price_after_amount_a_out(
+    sqrt_price: SqrtPrice,
+    liquidity: Liquidity,
+    amount_out: Balance,
+) -> Option<SqrtPrice> {
+    if amount_out == 0 {
+        return Some(sqrt_price);
+    }
+
+    let (sqrt_price_hp, liquidity_hp) =
+        (U512::from(sqrt_price), U512::from(liquidity) << RESOLUTION);
+
+    let numerator = liquidity_hp.checked_mul(sqrt_price_hp)?;
+    let denominator =
+        liquidity_hp.checked_sub(U512::from(amount_out).checked_mul(sqrt_price_hp)?)?;
+
+    to_balance!(div_rounding(numerator, denominator, true)?)
+}
+
+/// Calculating square root of price after buying given amount of asset B within a range.
+/// Formula : SQRT_PRICE - AMOUNT_OUT / LIQUIDITY
+///
+/// - `sqrt_price` - square root of current price
+/// - `liquidity` - liquidity of the range
+/// - `amount_out` - amount of asset B
+///
+/// Returns square root of price rounded down, or None in case of error
+pub fn calculate_sqrt_price_after_amount_b_out(
+    sqrt_price: SqrtPrice,
+    liquidity: Liquidity,
+    amount_out: Balance,
+) -> Option<SqrtPrice> {
+    let delta = div_rounding(
+        U512::from(amount_out) << RESOLUTION,
+        U512::from(liquidity),
+        true,
+    )?;
+
+    to_balance!(U512::from(sqrt_price).checked_sub(delta)?)
+}
+
+/// Calculating a swap step which moves the price towards target price, within a range of
+/// constant liquidity. The step ends at the target price unless the amount runs out before.
+/// Fee is taken from the amount in.
+///
+/// - `sqrt_price` - square root of current price
+/// - `sqrt_price_target` - square root of price at which the range ends
+/// - `liquidity` - liquidity of the range
+/// - `amount_remaining` - amount in left to be sold, including fee
+/// - `fee` - trade fee
+///
+/// Returns None in case of error
+pub fn calculate_swap_step(
+    sqrt_price: SqrtPrice,
+    sqrt_price_target: SqrtPrice,
+    liquidity: Liquidity,
+    amount_remaining: Balance,
+    fee: Permill,
+) -> Option<SwapStep> {
+    ensure!(fee < Permill::one());
+
+    // Selling asset A moves the price down
+    let a_to_b = sqrt_price_target < sqrt_price;
+
+    let amount_in_to_target = if a_to_b {
+        calculate_amount_a_delta(sqrt_price_target, sqrt_price, liquidity, true)?
+    } else {
+        calculate_amount_b_delta(sqrt_price, sqrt_price_target, liquidity, true)?
+    };
+
+    // Fee is rounded up to prevent value leaking from the pool
+    let amount_remaining_without_fee =
+        amount_remaining.checked_sub(fee.mul_ceil(amount_remaining))?;
+
+    let (next_sqrt_price, amount_in, fee_amount) =
+        if amount_remaining_without_fee >= amount_in_to_target {
+            let numerator =
+                U512::from(amount_in_to_target).checked_mul(U512::from(fee.deconstruct()))?;
+            let denominator = U512::from(Permill::ACCURACY - fee.deconstruct());
+            let fee_amount = to_balance!(div_rounding(numerator, denominator, true)?)?;
+
+            (sqrt_price_target, amount_in_to_target, fee_amount)
+        } else {
+            let next_sqrt_price = if a_to_b {
+                calculate_sqrt_price_after_amount_a_in(
+                    sqrt_price,
+                    liquidity,
+                    amount_remaining_without_fee,
+                )?
+            } else {
+                calculate_sqrt_price_after_amount_b_in(
+                    sqrt_price,
+                    liquidity,
+                    amount_remaining_without_fee,
+                )?
+            };
+
+            let amount_in = if a_to_b {
+                calculate_amount_a_delta(next_sqrt_price, sqrt_price, liquidity, true)?
+            } else {
+                calculate_amount_b_delta(sqrt_price, next_sqrt_price, liquidity, true)?
+            };
+
+            // Whole remaining amount is used, what is not swapped is fee
+            (
+                next_sqrt_price,
+                amount_in,
+                amount_remaining.checked_sub(amount_in)?,
+            )
+        };
+
+    let amount_out = if a_to_b {
+        calculate_amount_b_delta(next_sqrt_price, sqrt_price, liquidity, false)?
+    } else {
+        calculate_amount_a_delta(sqrt_price, next_sqrt_price, liquidity, false)?
+    };
+
+    Some(SwapStep {
+        sqrt_price: next_sqrt_price,
+        amount_in,
+        amount_out,
+        fee: fee_amount,
+    })
+}
+
+/// Calculating a swap step which moves the price towards target price, within a range of
+/// constant liquidity, buying given amount. The step ends at the target price unless
+/// the amount is bought before. Fee is added to the amount in.
+///
+/// - `sqrt_price` - square root of current price
+/// - `sqrt_price_target` - square root of price at which the range ends
+/// - `liquidity` - liquidity of the range
+/// - `amount_remaining` - amount out left to be bought
+/// - `fee` - trade fee
+///
+/// Returns None in case of error
+pub fn calculate_swap_step_exact_out(
+    sqrt_price: SqrtPrice,
+    sqrt_price_target: SqrtPrice,
+    liquidity: Liquidity,
+    amount_remaining: Balance,
+    fee: Permill,
+) -> Option<SwapStep> {
+    ensure!(fee < Permill::one());
+
+    // Selling asset A moves the price down
+    let a_to_b = sqrt_price_target < sqrt_price;
+
+    let amount_out_to_target = if a_to_b {
+        calculate_amount_b_delta(sqrt_price_target, sqrt_price, liquidity, false)?
+    } else {
+        calculate_amount_a_delta(sqrt_price, sqrt_price_target, liquidity, false)?
+    };
+
+    let (next_sqrt_price, amount_out) = if amount_remaining >= amount_out_to_target {
+        (sqrt_price_target, amount_out_to_target)
+    } else {
+        let next_sqrt_price = if a_to_b {
+            calculate_sqrt_price_after_amount_b_out(sqrt_price, liquidity, amount_remaining)?
+        } else {
+            calculate_sqrt_price_after_amount_a_out(sqrt_price, liquidity, amount_remaining)?
+        };
+
+        // Price is rounded so that at least the remaining amount is bought
+        (next_sqrt_price, amount_remaining)
+    };
+
+    let amount_in = if a_to_b {
+        calculate_amount_a_delta(next_sqrt_price, sqrt_price, liquidity, true)?
+    } else {
+        calculate_amount_b_delta(sqrt_price, next_sqrt_price, liquidity, true)?
+    };
+
+    // Fee is rounded up to prevent value leaking from the pool
+    let numerator = U512::from(amount_in).checked_mul(U512::from(fee.deconstruct()))?;
+    let denominator = U512::from(Permill::ACCURACY - fee.deconstruct());
+    let fee_amount = to_balance!(div_rounding(numerator, denominator, true)?)?;
+
+    Some(SwapStep {
+        sqrt_price: next_sqrt_price,
+        amount_in,
+        amount_out,
+        fee: fee_amount,
+    })
+}
+
+/// Calculating a swap selling given amount, crossing initialized ticks until the amount
+/// is used up. Liquidity changes by net liquidity of every crossed tick.
+/// Fee without protocol fee is distributed over liquidity of each range in which it is paid.
+///
+/// - `state` - state of the pool before the swap
+/// - `ticks` - initialized ticks with their net liquidity, sorted by tick
+/// - `amount_in` - amount to sell, including fee
+/// - `fee` - trade fee
+/// - `protocol_fee` - part of the trade fee not distributed to liquidity providers
+/// - `a_to_b` - whether asset A is sold for asset B
+///
+/// Returns None in case of error, e.g. when liquidity is not sufficient for the amount
+pub fn calculate_swap(
+    state: SwapState,
+    ticks: &[(Tick, i128)],
+    amount_in: Balance,
+    fee: Permill,
+    protocol_fee: Permill,
+    a_to_b: bool,
+) -> Option<SwapResult> {
+    swap(state, ticks, amount_in, fee, protocol_fee, a_to_b, true)
+}
+
+/// Calculating a swap buying given amount, crossing initialized ticks until the amount
+/// is bought. Liquidity changes by net liquidity of every crossed tick.
+/// Fee without protocol fee is distributed over liquidity of each range in which it is paid.
+///
+/// - `state` - state of the pool before the swap
+/// - `ticks` - initialized ticks with their net liquidity, sorted by tick
+/// - `amount_out` - amount to buy
+/// - `fee` - trade fee
+/// - `protocol_fee` - part of the trade fee not distributed to liquidity providers
+/// - `a_to_b` - whether asset A is sold for asset B
+///
+/// Returns None in case of error, e.g. when liquidity is not sufficient for the amount
+pub fn calculate_swap_exact_out(
+    state: SwapState,
+    ticks: &[(Tick, i128)],
+    amount_out: Balance,
+    fee: Permill,
+    protocol_fee: Permill,
+    a_to_b: bool,
+) -> Option<SwapResult> {
+    swap(state, ticks, amount_out, fee, protocol_fee, a_to_b, false)
+}
+
+/// Swap of given amount in if `exact_in`, otherwise of given amount out.
+fn swap(
+    state: SwapState,
+    ticks: &[(Tick, i128)],
+    amount: Balance,
+    fee: Permill,
+    protocol_fee: Permill,
+    a_to_b: bool,
+    exact_in: bool,
+) -> Option<SwapResult> {
+    let mut state = state;
+    let mut amount_remaining = amount;
+    let mut amount_in: Balance = 0;
+    let mut amount_out: Balance = 0;
+    let mut fee_total: Balance = 0;
+    let mut protocol_fee_total: Balance = 0;
+    let mut crossed_ticks = Vec::new();
+
+    while amount_remaining > 0 {
+        // Ticks are sorted, so the next one is found by binary search
+        let above = ticks.partition_point(|(tick, _)| *tick <= state.tick);
+        let next_tick = if a_to_b {
+            above.checked_sub(1).and_then(|index| ticks.get(index))
+        } else {
+            ticks.get(above)
+        };
+
+        let sqrt_price_target = match next_tick {
+            Some((tick, _)) => sqrt_price_at_tick(*tick)?,
+            None if a_to_b => MIN_SQRT_PRICE,
+            None => MAX_SQRT_PRICE,
+        };
+
+        // No liquidity is left beyond the last tick
+        ensure!(next_tick.is_some() || state.sqrt_price != sqrt_price_target);
+
+        let step = if exact_in {
+            calculate_swap_step(
+                state.sqrt_price,
+                sqrt_price_target,
+                state.liquidity,
+                amount_remaining,
+                fee,
+            )?
+        } else {
+            calculate_swap_step_exact_out(
+                state.sqrt_price,
+                sqrt_price_target,
+                state.liquidity,
+                amount_remaining,
+                fee,
+            )?
+        };
+
+        amount_remaining = if exact_in {
+            amount_remaining
+                .checked_sub(step.amount_in)?
+                .checked_sub(step.fee)?
+        } else {
+            amount_remaining.checked_sub(step.amount_out)?
+        };
+        amount_in = amount_in
+            .checked_add(step.amount_in)?
+            .checked_add(step.fee)?;
+        amount_out = amount_out.checked_add(step.amount_out)?;
+
+        let step_protocol_fee = protocol_fee.mul_floor(step.fee);
+        fee_total = fee_total.checked_add(step.fee)?;
+        protocol_fee_total = protocol_fee_total.checked_add(step_protocol_fee)?;
+
+        if state.liquidity > 0 {
+            let fee_growth = div_rounding(
+                U512::from(step.fee - step_protocol_fee) << RESOLUTION,
+                U512::from(state.liquidity),
+                false,
+            )?;
+            state.fee_growth = state.fee_growth.wrapping_add(to_balance!(fee_growth)?);
+        }
+
+        match next_tick {
+            Some(&(tick, liquidity_net)) if step.sqrt_price == sqrt_price_target => {
+                // Price moving down leaves the range above the tick
+                let liquidity_net = if a_to_b {
+                    liquidity_net.checked_neg()?
+                } else {
+                    liquidity_net
+                };
+
+                state.liquidity = if liquidity_net >= 0 {
+                    state.liquidity.checked_add(liquidity_net.unsigned_abs())?
+                } else {
+                    state.liquidity.checked_sub(liquidity_net.unsigned_abs())?
+                };
+                state.tick = if a_to_b { tick - 1 } else { tick };
+
+                crossed_ticks.push((tick, state.fee_growth));
+            }
+            _ if step.sqrt_price != state.sqrt_price => {
+                state.tick = tick_at_sqrt_price(step.sqrt_price)?;
+            }
+            _ => {}
+        }
+
+        state.sqrt_price = step.sqrt_price;
+    }
+
+    Some(SwapResult {
+        amount_in,
+        amount_out,
+        fee: fee_total,
+        protocol_fee: protocol_fee_total,
+        state,
+        crossed_ticks,
+    })
+}
+
+/// Calculating fee growth inside a range of ticks, given fee growths outside of its ticks.
+/// Fee growth outside of a tick is the growth on the other side of the tick than the current price.
+/// Formula : GLOBAL - BELOW_LOWER - ABOVE_UPPER
+///
+/// - `tick` - tick of current price
+/// - `tick_lower` - lower tick of the range
+/// - `tick_upper` - upper tick of the range
+/// - `lower_outside` - fee growth outside of the lower tick
+/// - `upper_outside` - fee growth outside of the upper tick
+/// - `global` - global fee growth
+///
+/// Returns fee growth inside the range
+pub fn calculate_fee_growth_inside(
+    tick: Tick,
+    tick_lower: Tick,
+    tick_upper: Tick,
+    lower_outside: FeeGrowth,
+    upper_outside: FeeGrowth,
+    global: FeeGrowth,
+) -> FeeGrowth {
+    let below = if tick >= tick_lower {
+        lower_outside
+    } else {
+        global.wrapping_sub(lower_outside)
+    };
+
+    let above = if tick < tick_upper {
+        upper_outside
+    } else {
+        global.wrapping_sub(upper_outside)
+    };
+
+    global.wrapping_sub(below).wrapping_sub(above)
+}
+
+/// Calculating fees earned by a position since fee growth inside its range was last recorded.
+/// Formula : LIQUIDITY * (FEE_GROWTH_INSIDE - FEE_GROWTH_INSIDE_LAST)
+///
+/// - `liquidity` - liquidity of the position
+/// - `fee_growth_inside` - current fee growth inside the range of the position
+/// - `fee_growth_inside_last` - fee growth inside the range when last recorded
+///
+/// Returns None in case of error
+pub fn calculate_fees_owed(
+    liquidity: Liquidity,
+    fee_growth_inside: FeeGrowth,
+    fee_growth_inside_last: FeeGrowth,
+) -> Option<Balance> {
+    let fee_growth = fee_growth_inside.wrapping_sub(fee_growth_inside_last);
+
+    let fees = U512::from(liquidity).checked_mul(U512::from(fee_growth))? >> RESOLUTION;
+
+    to_balance!(fees)
+}
+
+/// Calculating spot price given square root of price.
+/// Formula : AMOUNT * SQRT_PRICE^2 for asset A, AMOUNT / SQRT_PRICE^2 for asset B
+///
+/// - `sqrt_price` - square root of price of asset A denominated in asset B
+/// - `amount` - amount
+/// - `a_to_b` - whether `amount` is of asset A, priced in asset B
+///
+/// Returns None in case of error
+pub fn calculate_spot_price(
+    sqrt_price: SqrtPrice,
+    amount: Balance,
+    a_to_b: bool,
+) -> Option<Balance> {
+    let price = U512::from(sqrt_price).checked_mul(U512::from(sqrt_price))?;
+    let amount_hp = U512::from(amount);
+
+    let spot_price = if a_to_b {
+        amount_hp.checked_mul(price)? >> (2 * RESOLUTION)
+    } else {
+        div_rounding(amount_hp << (2 * RESOLUTION), price, false)?
+    };
+
+    to_balance!(spot_price)
+}
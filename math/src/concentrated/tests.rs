@@ -0,0 +1,562 @@
+use crate::concentrated::*;
+use sp_arithmetic::Permill;
+use std::vec;
+
+const Q: SqrtPrice = 1 << 64;
+const ONE: Balance = 1_000_000_000_000;
+
+#[test]
+fn sqrt_price_at_tick_should_work() {
+    let cases = vec![
+        (0, Some(Q), "Price of one"),
+        (
+            1,
+            Some(18_447_666_387_855_959_851),
+            "Smallest tick above one",
+        ),
+        (
+            -1,
+            Some(18_445_821_805_675_392_312),
+            "Largest tick below one",
+        ),
+        (MIN_TICK, Some(MIN_SQRT_PRICE), "Min tick"),
+        (MAX_TICK, Some(MAX_SQRT_PRICE), "Max tick"),
+        (MIN_TICK - 1, None, "Tick below min"),
+        (MAX_TICK + 1, None, "Tick above max"),
+    ];
+
+    for case in cases {
+        assert_eq!(sqrt_price_at_tick(case.0), case.1, "{}", case.2);
+    }
+}
+
+#[test]
+fn tick_at_sqrt_price_should_work() {
+    let cases = vec![
+        (Q, Some(0), "Price of one"),
+        (Q - 1, Some(-1), "Price just below one"),
+        (18_447_666_387_855_959_851, Some(1), "Price of tick"),
+        (18_447_666_387_855_959_850, Some(0), "Price just below tick"),
+        (MIN_SQRT_PRICE, Some(MIN_TICK), "Min price"),
+        (MAX_SQRT_PRICE, Some(MAX_TICK), "Max price"),
+        (MIN_SQRT_PRICE - 1, None, "Price below min"),
+        (MAX_SQRT_PRICE + 1, None, "Price above max"),
+    ];
+
+    for case in cases {
+        assert_eq!(tick_at_sqrt_price(case.0), case.1, "{}", case.2);
+    }
+}
+
+#[test]
+fn inverse_sqrt_price_should_work() {
+    let cases = vec![
+        (Q, Some(Q), "Price of one"),
+        (2 * Q, Some(Q / 2), "Price of four"),
+        (Q / 2, Some(2 * Q), "Price of quarter"),
+        (1, None, "Overflow"),
+        (0, None, "Zero price"),
+    ];
+
+    for case in cases {
+        assert_eq!(calculate_inverse_sqrt_price(case.0), case.1, "{}", case.2);
+    }
+}
+
+#[test]
+fn amount_deltas_should_work() {
+    let cases = vec![
+        (Q, 2 * Q, 1000, false, Some(500), Some(1000), "Easy case"),
+        (
+            2 * Q,
+            Q,
+            1000,
+            false,
+            Some(500),
+            Some(1000),
+            "Reversed range",
+        ),
+        (Q, 2 * Q, 1001, false, Some(500), Some(1001), "Rounded down"),
+        (Q, 2 * Q, 1001, true, Some(501), Some(1001), "Rounded up"),
+        (Q, Q, 1000, true, Some(0), Some(0), "Empty range"),
+        (Q, 2 * Q, 0, true, Some(0), Some(0), "Zero liquidity"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_amount_a_delta(case.0, case.1, case.2, case.3),
+            case.4,
+            "{}",
+            case.6
+        );
+        assert_eq!(
+            calculate_amount_b_delta(case.0, case.1, case.2, case.3),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn position_amounts_should_work() {
+    let cases = vec![
+        (
+            3 * Q / 2,
+            Q,
+            2 * Q,
+            1200,
+            Some((200, 600)),
+            "Price in range",
+        ),
+        (Q / 2, Q, 2 * Q, 1000, Some((500, 0)), "Price below range"),
+        (Q, Q, 2 * Q, 1000, Some((500, 0)), "Price at lower tick"),
+        (3 * Q, Q, 2 * Q, 1000, Some((0, 1000)), "Price above range"),
+        (
+            2 * Q,
+            Q,
+            2 * Q,
+            1000,
+            Some((0, 1000)),
+            "Price at upper tick",
+        ),
+        (Q, 2 * Q, Q, 1000, None, "Invalid range"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_position_amounts(case.0, case.1, case.2, case.3, false),
+            case.4,
+            "{}",
+            case.5
+        );
+    }
+}
+
+#[test]
+fn liquidity_for_amounts_should_work() {
+    let cases = vec![
+        (3 * Q / 2, Q, 2 * Q, 200, 600, Some(1200), "Price in range"),
+        (
+            3 * Q / 2,
+            Q,
+            2 * Q,
+            200,
+            6000,
+            Some(1200),
+            "Limited by asset A",
+        ),
+        (
+            3 * Q / 2,
+            Q,
+            2 * Q,
+            2000,
+            600,
+            Some(1200),
+            "Limited by asset B",
+        ),
+        (Q / 2, Q, 2 * Q, 500, 1000, Some(1000), "Price below range"),
+        (3 * Q, Q, 2 * Q, 500, 2000, Some(2000), "Price above range"),
+        (Q, 2 * Q, Q, 500, 1000, None, "Invalid range"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_liquidity_for_amounts(case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn swap_step_should_work() {
+    let cases = vec![
+        (
+            Q,
+            2 * Q,
+            1000,
+            2000,
+            Permill::zero(),
+            Some(SwapStep {
+                sqrt_price: 2 * Q,
+                amount_in: 1000,
+                amount_out: 500,
+                fee: 0,
+            }),
+            "Target reached",
+        ),
+        (
+            Q,
+            2 * Q,
+            1000,
+            500,
+            Permill::zero(),
+            Some(SwapStep {
+                sqrt_price: 3 * Q / 2,
+                amount_in: 500,
+                amount_out: 333,
+                fee: 0,
+            }),
+            "Amount used up",
+        ),
+        (
+            Q,
+            2 * Q,
+            1000,
+            2000,
+            Permill::from_percent(1),
+            Some(SwapStep {
+                sqrt_price: 2 * Q,
+                amount_in: 1000,
+                amount_out: 500,
+                fee: 11,
+            }),
+            "Target reached with fee",
+        ),
+        (
+            2 * Q,
+            Q,
+            1000,
+            200,
+            Permill::from_percent(1),
+            Some(SwapStep {
+                sqrt_price: 26_428_000_105_601_076_814,
+                amount_in: 198,
+                amount_out: 567,
+                fee: 2,
+            }),
+            "Asset A sold with fee",
+        ),
+        (
+            Q,
+            2 * Q,
+            0,
+            200,
+            Permill::from_percent(1),
+            Some(SwapStep {
+                sqrt_price: 2 * Q,
+                amount_in: 0,
+                amount_out: 0,
+                fee: 0,
+            }),
+            "Range without liquidity",
+        ),
+        (
+            Q,
+            2 * Q,
+            1000,
+            200,
+            Permill::one(),
+            None,
+            "Whole amount is fee",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_swap_step(case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn swap_should_cross_ticks() {
+    let (liquidity_1, liquidity_2) = (1000 * ONE, 500 * ONE);
+    let ticks = vec![
+        (-2000, liquidity_2 as i128),
+        (-1000, liquidity_1 as i128),
+        (1000, -(liquidity_1 as i128)),
+        (2000, -(liquidity_2 as i128)),
+    ];
+    let state = SwapState {
+        sqrt_price: Q,
+        tick: 0,
+        liquidity: liquidity_1 + liquidity_2,
+        fee_growth: 0,
+    };
+
+    let fee = Permill::from_percent(1);
+    let protocol_fee = Permill::from_percent(20);
+
+    let within_range = calculate_swap(state, &ticks, 10 * ONE, fee, protocol_fee, true).unwrap();
+    assert_eq!(within_range.amount_out, 9_835_088_416_451);
+    assert_eq!(within_range.fee, 100_000_000_000);
+    assert_eq!(within_range.protocol_fee, 20_000_000_000);
+    assert_eq!(within_range.state.tick, -132);
+    assert_eq!(within_range.state.liquidity, liquidity_1 + liquidity_2);
+    assert!(within_range.crossed_ticks.is_empty());
+
+    let across_tick = calculate_swap(state, &ticks, 100 * ONE, fee, protocol_fee, true).unwrap();
+    assert_eq!(across_tick.amount_out, 92_340_211_302_834);
+    assert_eq!(across_tick.state.tick, -1824);
+    assert_eq!(across_tick.state.liquidity, liquidity_2);
+    assert_eq!(
+        across_tick.crossed_ticks,
+        vec![(-1000, 7_642_313_658_166_719)]
+    );
+
+    let other_direction =
+        calculate_swap(state, &ticks, 100 * ONE, fee, protocol_fee, false).unwrap();
+    assert_eq!(other_direction.amount_out, across_tick.amount_out);
+    assert_eq!(other_direction.state.tick, 1823);
+    assert_eq!(other_direction.state.liquidity, liquidity_2);
+    assert_eq!(
+        other_direction.crossed_ticks,
+        vec![(1000, 7_642_313_658_166_719)]
+    );
+
+    assert_eq!(
+        calculate_swap(state, &ticks, 200 * ONE, fee, protocol_fee, true),
+        None,
+        "Liquidity is not sufficient"
+    );
+}
+
+#[test]
+fn swap_step_exact_out_should_work() {
+    let cases = vec![
+        (
+            Q,
+            2 * Q,
+            1000,
+            500,
+            Permill::zero(),
+            Some(SwapStep {
+                sqrt_price: 2 * Q,
+                amount_in: 1000,
+                amount_out: 500,
+                fee: 0,
+            }),
+            "Target reached",
+        ),
+        (
+            Q,
+            2 * Q,
+            1000,
+            250,
+            Permill::zero(),
+            Some(SwapStep {
+                sqrt_price: 24_595_658_764_946_068_822,
+                amount_in: 334,
+                amount_out: 250,
+                fee: 0,
+            }),
+            "Amount bought",
+        ),
+        (
+            Q,
+            2 * Q,
+            1000,
+            500,
+            Permill::from_percent(1),
+            Some(SwapStep {
+                sqrt_price: 2 * Q,
+                amount_in: 1000,
+                amount_out: 500,
+                fee: 11,
+            }),
+            "Target reached with fee",
+        ),
+        (
+            2 * Q,
+            Q,
+            1000,
+            567,
+            Permill::from_percent(1),
+            Some(SwapStep {
+                sqrt_price: 26_434_184_257_625_787_465,
+                amount_in: 198,
+                amount_out: 567,
+                fee: 2,
+            }),
+            "Asset B bought with fee",
+        ),
+        (
+            Q,
+            2 * Q,
+            0,
+            200,
+            Permill::from_percent(1),
+            Some(SwapStep {
+                sqrt_price: 2 * Q,
+                amount_in: 0,
+                amount_out: 0,
+                fee: 0,
+            }),
+            "Range without liquidity",
+        ),
+        (
+            Q,
+            2 * Q,
+            1000,
+            200,
+            Permill::one(),
+            None,
+            "Whole amount is fee",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_swap_step_exact_out(case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn swap_exact_out_should_cross_ticks() {
+    let (liquidity_1, liquidity_2) = (1000 * ONE, 500 * ONE);
+    let ticks = vec![
+        (-2000, liquidity_2 as i128),
+        (-1000, liquidity_1 as i128),
+        (1000, -(liquidity_1 as i128)),
+        (2000, -(liquidity_2 as i128)),
+    ];
+    let state = SwapState {
+        sqrt_price: Q,
+        tick: 0,
+        liquidity: liquidity_1 + liquidity_2,
+        fee_growth: 0,
+    };
+
+    let fee = Permill::from_percent(1);
+    let protocol_fee = Permill::from_percent(20);
+
+    let within_range =
+        calculate_swap_exact_out(state, &ticks, 9_835_088_416_451, fee, protocol_fee, true)
+            .unwrap();
+    assert_eq!(within_range.amount_in, 10 * ONE);
+    assert_eq!(within_range.fee, 100_000_000_000);
+    assert_eq!(within_range.protocol_fee, 20_000_000_000);
+    assert_eq!(within_range.state.tick, -132);
+    assert!(within_range.crossed_ticks.is_empty());
+
+    let across_tick =
+        calculate_swap_exact_out(state, &ticks, 92_340_211_302_834, fee, protocol_fee, true)
+            .unwrap();
+    assert_eq!(across_tick.amount_in, 100 * ONE);
+    assert_eq!(across_tick.state.tick, -1824);
+    assert_eq!(across_tick.state.liquidity, liquidity_2);
+    assert_eq!(
+        across_tick.crossed_ticks,
+        vec![(-1000, 7_642_313_658_166_719)]
+    );
+
+    let other_direction =
+        calculate_swap_exact_out(state, &ticks, 92_340_211_302_834, fee, protocol_fee, false)
+            .unwrap();
+    assert_eq!(other_direction.amount_in, 100 * ONE);
+    assert_eq!(other_direction.state.tick, 1823);
+    assert_eq!(
+        other_direction.crossed_ticks,
+        vec![(1000, 7_642_313_658_166_719)]
+    );
+
+    assert_eq!(
+        calculate_swap_exact_out(state, &ticks, 200 * ONE, fee, protocol_fee, true),
+        None,
+        "Liquidity is not sufficient"
+    );
+}
+
+#[test]
+fn fee_growth_inside_should_work() {
+    let cases = vec![
+        (0, -10, 10, 100, 200, 1000, 700, "Price in range"),
+        (-20, -10, 10, 300, 200, 1000, 100, "Price below range"),
+        (20, -10, 10, 100, 200, 1000, 100, "Price above range"),
+        (10, -10, 10, 100, 200, 1000, 100, "Price at upper tick"),
+        (-10, -10, 10, 100, 200, 1000, 700, "Price at lower tick"),
+        (
+            0,
+            -10,
+            10,
+            800,
+            600,
+            1000,
+            u128::MAX - 399,
+            "Overflowing growth",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_fee_growth_inside(case.0, case.1, case.2, case.3, case.4, case.5),
+            case.6,
+            "{}",
+            case.7
+        );
+    }
+}
+
+#[test]
+fn fees_owed_should_work() {
+    let cases = vec![
+        (1000 * ONE, Q, 0, Some(1000 * ONE), "Easy case"),
+        (
+            1000 * ONE,
+            3 * Q / 2,
+            Q,
+            Some(500 * ONE),
+            "Growth since last record",
+        ),
+        (
+            1000 * ONE,
+            Q / 2,
+            u128::MAX - Q / 2 + 1,
+            Some(1000 * ONE),
+            "Overflowed growth",
+        ),
+        (1000 * ONE, Q, Q, Some(0), "No growth"),
+        (u128::MAX, u128::MAX, 0, None, "Overflow"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_fees_owed(case.0, case.1, case.2),
+            case.3,
+            "{}",
+            case.4
+        );
+    }
+}
+
+#[test]
+fn spot_price_should_work() {
+    let cases = vec![
+        (Q, ONE, true, Some(ONE), "Price of one"),
+        (
+            3 * Q / 2,
+            ONE,
+            true,
+            Some(2_250_000_000_000),
+            "Price of asset A",
+        ),
+        (
+            3 * Q / 2,
+            ONE,
+            false,
+            Some(444_444_444_444),
+            "Price of asset B",
+        ),
+        (Q, 0, true, Some(0), "Zero amount"),
+        (0, ONE, false, None, "Zero price"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_spot_price(case.0, case.1, case.2),
+            case.3,
+            "{}",
+            case.4
+        );
+    }
+}
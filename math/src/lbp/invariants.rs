@@ -0,0 +1,121 @@
+use crate::lbp::*;
+use primitive_types::U512;
+use proptest::prelude::*;
+
+pub const ONE: Balance = 1_000_000_000_000;
+
+/// Allowed relative increase of the weighted invariant per unit of weight, as its inverse.
+const TOLERANCE: u32 = 1_000_000_000;
+
+fn asset_reserve() -> impl Strategy<Value = Balance> {
+    1000 * ONE..10_000_000 * ONE
+}
+
+fn trade_amount() -> impl Strategy<Value = Balance> {
+    ONE..100 * ONE
+}
+
+fn asset_weight() -> impl Strategy<Value = LBPWeight> {
+    1_000_000u32..100_000_000u32
+}
+
+/// Weights of both assets as small integer parts, so that the weighted invariant can be
+/// calculated exactly, scaled to the range of weights used by pools.
+fn asset_weights() -> impl Strategy<Value = ((u32, u32), (LBPWeight, LBPWeight))> {
+    (1u32..=3, 1u32..=3, 1_000_000u32..30_000_000u32).prop_map(|(in_part, out_part, scale)| {
+        ((in_part, out_part), (in_part * scale, out_part * scale))
+    })
+}
+
+/// Weighted invariant: IN_RESERVE ^ IN_WEIGHT * OUT_RESERVE ^ OUT_WEIGHT
+fn invariant(state: (Balance, Balance), weights: (u32, u32)) -> U512 {
+    U512::from(state.0).pow(weights.0.into()) * U512::from(state.1).pow(weights.1.into())
+}
+
+fn assert_asset_invariant(
+    old_state: (Balance, Balance),
+    new_state: (Balance, Balance),
+    weights: (u32, u32),
+    desc: &str,
+) {
+    let old_s = invariant(old_state, weights);
+    let new_s = invariant(new_state, weights);
+
+    assert!(new_s >= old_s, "Invariant decreased for {}", desc);
+    assert!(
+        (new_s - old_s) * U512::from(TOLERANCE) <= old_s * U512::from(weights.0 + weights.1),
+        "Invariant increased too much for {}",
+        desc
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn sell_invariants(asset_in_reserve in asset_reserve(),
+        asset_out_reserve in asset_reserve(),
+        (parts, (in_weight, out_weight)) in asset_weights(),
+        amount in trade_amount()
+    ) {
+        let amount_out = calculate_out_given_in(asset_in_reserve, asset_out_reserve, in_weight, out_weight, amount).unwrap();
+
+        assert_asset_invariant((asset_in_reserve, asset_out_reserve),
+            (asset_in_reserve + amount, asset_out_reserve - amount_out),
+            parts,
+            "out given in"
+        );
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn buy_invariants(asset_in_reserve in asset_reserve(),
+        asset_out_reserve in asset_reserve(),
+        (parts, (in_weight, out_weight)) in asset_weights(),
+        amount in trade_amount()
+    ) {
+        let amount_in = calculate_in_given_out(asset_out_reserve, asset_in_reserve, out_weight, in_weight, amount).unwrap();
+
+        assert_asset_invariant((asset_in_reserve, asset_out_reserve),
+            (asset_in_reserve + amount_in, asset_out_reserve - amount),
+            parts,
+            "in given out"
+        );
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn sell_then_buy_should_not_be_profitable(asset_in_reserve in asset_reserve(),
+        asset_out_reserve in asset_reserve(),
+        in_weight in asset_weight(),
+        out_weight in asset_weight(),
+        amount in trade_amount()
+    ) {
+        let amount_out = calculate_out_given_in(asset_in_reserve, asset_out_reserve, in_weight, out_weight, amount).unwrap();
+        let amount_back = calculate_out_given_in(asset_out_reserve - amount_out, asset_in_reserve + amount, out_weight, in_weight, amount_out).unwrap();
+
+        assert!(amount_back <= amount);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn linear_weights_should_be_between_endpoints(start in 0u32..1_000_000u32,
+        length in 1u32..1_000_000u32,
+        start_weight in asset_weight(),
+        end_weight in asset_weight(),
+        point in 0u32..1_000_000u32
+    ) {
+        let end = start + length;
+        let at = start + point % (length + 1);
+
+        let weight = calculate_linear_weights(start, end, start_weight, end_weight, at).unwrap();
+
+        assert!(weight >= start_weight.min(end_weight));
+        assert!(weight <= start_weight.max(end_weight));
+    }
+}
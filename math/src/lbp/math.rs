@@ -0,0 +1,230 @@
+use crate::transcendental::{pow, FixedRatio};
+use crate::{ensure, round_up, to_balance, to_lbp_weight};
+use core::convert::TryFrom;
+use primitive_types::U256;
+use sp_arithmetic::traits::{AtLeast32BitUnsigned, SaturatedConversion};
+use sp_arithmetic::Permill;
+
+pub type Balance = u128;
+pub type LBPWeight = u32;
+
+const FIXED_ROUND_UP: Balance = 1;
+
+const FRAC_BITS: usize = 96;
+
+/// Bound of the error of `pow`, by which its result is rounded in favour of the pool.
+/// Error of `pow` is a few hundred units of the last place, far below 2^-64.
+const POW_ROUND_UP: FixedRatio = FixedRatio::from_bits(1 << 32);
+
+/// Converting ratio of two numbers to fixed point number.
+fn to_fixed_ratio(numerator: u128, denominator: u128) -> Option<FixedRatio> {
+    ensure!(denominator != 0);
+
+    let bits = (U256::from(numerator) << FRAC_BITS).checked_div(U256::from(denominator))?;
+
+    Some(FixedRatio::from_bits(i128::try_from(bits).ok()?))
+}
+
+/// Calculating spot price given reserves and weights of selling asset and buying asset.
+/// Formula : OUT_RESERVE * IN_WEIGHT * AMOUNT / (IN_RESERVE * OUT_WEIGHT)
+///
+/// - `in_reserve` - reserve amount of selling asset
+/// - `out_reserve` - reserve amount of buying asset
+/// - `in_weight` - weight of selling asset
+/// - `out_weight` - weight of buying asset
+/// - `amount` - amount
+///
+/// Returns None in case of error
+pub fn calculate_spot_price(
+    in_reserve: Balance,
+    out_reserve: Balance,
+    in_weight: LBPWeight,
+    out_weight: LBPWeight,
+    amount: Balance,
+) -> Option<Balance> {
+    ensure!(in_reserve != 0 && out_weight != 0);
+
+    if amount == 0 || out_reserve == 0 {
+        return to_balance!(0);
+    }
+
+    let numerator = U256::from(out_reserve)
+        .checked_mul(U256::from(in_weight))?
+        .checked_mul(U256::from(amount))?;
+    let denominator = U256::from(in_reserve).checked_mul(U256::from(out_weight))?;
+
+    to_balance!(numerator.checked_div(denominator)?)
+}
+
+/// Calculating amount to be received from the pool given the amount to be sent to the pool,
+/// both reserves and weights.
+/// Formula : OUT_RESERVE * (1 - (IN_RESERVE / (IN_RESERVE + AMOUNT_IN)) ^ (IN_WEIGHT / OUT_WEIGHT))
+///
+/// - `in_reserve` - reserve amount of selling asset
+/// - `out_reserve` - reserve amount of buying asset
+/// - `in_weight` - weight of selling asset
+/// - `out_weight` - weight of buying asset
+/// - `amount_in` - amount
+///
+/// Returns None in case of error
+pub fn calculate_out_given_in(
+    in_reserve: Balance,
+    out_reserve: Balance,
+    in_weight: LBPWeight,
+    out_weight: LBPWeight,
+    amount_in: Balance,
+) -> Option<Balance> {
+    if amount_in == 0 {
+        return Some(0);
+    }
+    ensure!(in_reserve != 0);
+
+    let base = to_fixed_ratio(in_reserve, in_reserve.checked_add(amount_in)?)?;
+    let exponent = to_fixed_ratio(in_weight.into(), out_weight.into())?;
+
+    let remaining_ratio = pow(base, exponent)?;
+    let one = FixedRatio::from_num(1);
+    ensure!(remaining_ratio <= one);
+
+    // Remaining ratio is rounded up, as truncation of `pow` would favour the trader
+    let remaining_ratio = remaining_ratio.checked_add(POW_ROUND_UP)?.min(one);
+
+    let out_ratio_bits = (one - remaining_ratio).to_bits() as u128;
+
+    // Rounded down to prevent value leaking from the pool
+    let amount_out = U256::from(out_reserve).checked_mul(U256::from(out_ratio_bits))? >> FRAC_BITS;
+
+    to_balance!(amount_out)
+}
+
+/// Calculating amount to be sent to the pool given the amount to be received from the pool,
+/// both reserves and weights.
+/// Formula : IN_RESERVE * ((OUT_RESERVE / (OUT_RESERVE - AMOUNT_OUT)) ^ (OUT_WEIGHT / IN_WEIGHT) - 1) + 1
+///
+/// - `out_reserve` - reserve amount of buying asset
+/// - `in_reserve` - reserve amount of selling asset
+/// - `out_weight` - weight of buying asset
+/// - `in_weight` - weight of selling asset
+/// - `amount_out` - buy amount
+///
+/// Returns None in case of error
+pub fn calculate_in_given_out(
+    out_reserve: Balance,
+    in_reserve: Balance,
+    out_weight: LBPWeight,
+    in_weight: LBPWeight,
+    amount_out: Balance,
+) -> Option<Balance> {
+    if amount_out == 0 {
+        return Some(0);
+    }
+    ensure!(amount_out < out_reserve);
+
+    let base = to_fixed_ratio(out_reserve, out_reserve - amount_out)?;
+    let exponent = to_fixed_ratio(out_weight.into(), in_weight.into())?;
+
+    let growth_ratio = pow(base, exponent)?;
+    let one = FixedRatio::from_num(1);
+    ensure!(growth_ratio >= one);
+
+    // Growth ratio is rounded up, as truncation of `pow` would favour the trader
+    let growth_ratio = growth_ratio.checked_add(POW_ROUND_UP)?;
+
+    let in_ratio_bits = (growth_ratio - one).to_bits() as u128;
+
+    let amount_in = U256::from(in_reserve).checked_mul(U256::from(in_ratio_bits))? >> FRAC_BITS;
+
+    // We are rounding up to prevent value leaking from the pool
+    round_up!(to_balance!(amount_in)?)
+}
+
+/// Calculating weight at given point of linear change of weight between two points.
+/// Formula : (START_Y * (END_X - AT) + END_Y * (AT - START_X)) / (END_X - START_X)
+///
+/// - `start_x` - start of the change, e.g. block number
+/// - `end_x` - end of the change
+/// - `start_y` - weight at `start_x`
+/// - `end_y` - weight at `end_x`
+/// - `at` - point of the change
+///
+/// Returns None in case of error
+pub fn calculate_linear_weights<BlockNumber: AtLeast32BitUnsigned>(
+    start_x: BlockNumber,
+    end_x: BlockNumber,
+    start_y: LBPWeight,
+    end_y: LBPWeight,
+    at: BlockNumber,
+) -> Option<LBPWeight> {
+    ensure!(start_x < end_x && start_x <= at && at <= end_x);
+
+    let to_end: u128 = (end_x.clone() - at.clone()).saturated_into();
+    let from_start: u128 = (at - start_x.clone()).saturated_into();
+    let length: u128 = (end_x - start_x).saturated_into();
+
+    let weight = U256::from(start_y)
+        .checked_mul(U256::from(to_end))?
+        .checked_add(U256::from(end_y).checked_mul(U256::from(from_start))?)?
+        .checked_div(U256::from(length))?;
+
+    to_lbp_weight!(weight)
+}
+
+/// Calculating amount to be received from the pool given the amount to be sent to the pool,
+/// both reserves, weights and trade fee.
+/// Fee is deducted from the amount out and stays in the pool.
+///
+/// - `in_reserve` - reserve amount of selling asset
+/// - `out_reserve` - reserve amount of buying asset
+/// - `in_weight` - weight of selling asset
+/// - `out_weight` - weight of buying asset
+/// - `amount_in` - amount
+/// - `fee` - trade fee
+///
+/// Returns amount out without fee and the fee amount, or None in case of error
+pub fn calculate_out_given_in_with_fee(
+    in_reserve: Balance,
+    out_reserve: Balance,
+    in_weight: LBPWeight,
+    out_weight: LBPWeight,
+    amount_in: Balance,
+    fee: Permill,
+) -> Option<(Balance, Balance)> {
+    let amount_out =
+        calculate_out_given_in(in_reserve, out_reserve, in_weight, out_weight, amount_in)?;
+
+    // Fee is rounded up to prevent value leaking from the pool
+    let fee_amount = fee.mul_ceil(amount_out);
+    let amount_out_without_fee = amount_out.checked_sub(fee_amount)?;
+
+    Some((amount_out_without_fee, fee_amount))
+}
+
+/// Calculating amount to be sent to the pool given the amount to be received from the pool,
+/// both reserves, weights and trade fee.
+/// Fee is added to the amount in and stays in the pool.
+///
+/// - `out_reserve` - reserve amount of buying asset
+/// - `in_reserve` - reserve amount of selling asset
+/// - `out_weight` - weight of buying asset
+/// - `in_weight` - weight of selling asset
+/// - `amount_out` - buy amount
+/// - `fee` - trade fee
+///
+/// Returns amount in including fee and the fee amount, or None in case of error
+pub fn calculate_in_given_out_with_fee(
+    out_reserve: Balance,
+    in_reserve: Balance,
+    out_weight: LBPWeight,
+    in_weight: LBPWeight,
+    amount_out: Balance,
+    fee: Permill,
+) -> Option<(Balance, Balance)> {
+    let amount_in =
+        calculate_in_given_out(out_reserve, in_reserve, out_weight, in_weight, amount_out)?;
+
+    // Fee is rounded up to prevent value leaking from the pool
+    let fee_amount = fee.mul_ceil(amount_in);
+    let amount_in_with_fee = amount_in.checked_add(fee_amount)?;
+
+    Some((amount_in_with_fee, fee_amount))
+}
@@ -0,0 +1,263 @@
+use crate::lbp::*;
+use crate::transcendental::{pow, FixedRatio};
+use sp_arithmetic::Permill;
+use std::vec;
+
+#[test]
+fn pow_should_work() {
+    let cases = vec![
+        (
+            FixedRatio::from_num(2),
+            FixedRatio::from_num(0),
+            FixedRatio::from_num(1),
+            "Zero exponent",
+        ),
+        (
+            FixedRatio::from_num(0),
+            FixedRatio::from_num(2),
+            FixedRatio::from_num(0),
+            "Zero base",
+        ),
+        (
+            FixedRatio::from_num(0.5),
+            FixedRatio::from_num(1),
+            FixedRatio::from_num(0.5),
+            "Exponent one",
+        ),
+        (
+            FixedRatio::from_num(2),
+            FixedRatio::from_num(10),
+            FixedRatio::from_num(1024),
+            "Integer exponent",
+        ),
+        (
+            FixedRatio::from_num(4),
+            FixedRatio::from_num(0.5),
+            FixedRatio::from_num(2),
+            "Square root",
+        ),
+        (
+            FixedRatio::from_num(0.25),
+            FixedRatio::from_num(1.5),
+            FixedRatio::from_num(0.125),
+            "Base below one",
+        ),
+    ];
+
+    let tolerance = FixedRatio::from_num(0.000_000_000_000_000_001);
+
+    for case in cases {
+        let result = pow(case.0, case.1).unwrap();
+        let diff = if result >= case.2 {
+            result - case.2
+        } else {
+            case.2 - result
+        };
+        assert!(diff <= tolerance, "{}: {} != {}", case.3, result, case.2);
+    }
+}
+
+#[test]
+fn spot_price_should_work() {
+    let cases = vec![
+        (1000, 2000, 50, 50, 500, Some(1000), "Equal weights"),
+        (1000, 2000, 80, 20, 500, Some(4000), "Heavier asset in"),
+        (1000, 2000, 20, 80, 500, Some(250), "Heavier asset out"),
+        (1, 1, 1, 1, 0, Some(0), "Zero amount"),
+        (0, 1, 1, 1, 1, None, "Zero in reserve"),
+        (1, 1, 1, 0, 1, None, "Zero out weight"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_spot_price(case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn out_given_in_should_work() {
+    let cases = vec![
+        (1000, 2000, 50, 50, 500, Some(666), "Equal weights"),
+        (1000, 2000, 80, 20, 500, Some(1604), "Heavier asset in"),
+        (1000, 2000, 20, 80, 500, Some(192), "Heavier asset out"),
+        (1000, 1000, 50, 50, 0, Some(0), "Zero amount in"),
+        (0, 1000, 50, 50, 100, None, "Zero in reserve"),
+        (1000, 1000, 50, 0, 100, None, "Zero out weight"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_out_given_in(case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn in_given_out_should_work() {
+    let cases = vec![
+        (2000, 1000, 50, 50, 500, Some(334), "Equal weights"),
+        (2000, 1000, 80, 20, 500, Some(2161), "Heavier asset out"),
+        (2000, 1000, 20, 80, 500, Some(75), "Heavier asset in"),
+        (1000, 1000, 50, 50, 0, Some(0), "Zero amount out"),
+        (
+            1000,
+            1000,
+            50,
+            50,
+            1000,
+            None,
+            "Amount out equal to reserve",
+        ),
+        (1000, 1000, 50, 0, 100, None, "Zero in weight"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_in_given_out(case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn out_given_in_and_in_given_out_should_round_in_favour_of_pool() {
+    const RESERVE: Balance = 1 << 100;
+
+    // Exact amount out is 371_286_264_631_651_162_796_992_193_736.67
+    assert_eq!(
+        calculate_out_given_in(1, RESERVE, 1, 2, 1),
+        Some(371_286_264_631_651_162_728_272_717_088)
+    );
+    // Exact amount out is 819_468_432_429_940_282_146_847_699_556.33
+    assert_eq!(
+        calculate_out_given_in(1, RESERVE, 3, 2, 1),
+        Some(819_468_432_429_940_282_078_128_222_864)
+    );
+    // Exact amount in is 744_619_296_102_181_435_617_920_527_171.86
+    assert_eq!(
+        calculate_in_given_out(RESERVE, RESERVE, 2, 3, RESERVE / 2),
+        Some(744_619_296_102_181_435_686_640_003_697)
+    );
+}
+
+#[test]
+fn out_given_in_with_fee_should_work() {
+    let cases = vec![
+        (
+            1000,
+            2000,
+            80,
+            20,
+            500,
+            Permill::zero(),
+            Some((1604, 0)),
+            "No fee",
+        ),
+        (
+            1000,
+            2000,
+            80,
+            20,
+            500,
+            Permill::from_percent(1),
+            Some((1587, 17)),
+            "Fee is rounded up",
+        ),
+        (
+            1000,
+            1000,
+            50,
+            50,
+            0,
+            Permill::from_percent(1),
+            Some((0, 0)),
+            "Zero amount in",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_out_given_in_with_fee(case.0, case.1, case.2, case.3, case.4, case.5),
+            case.6,
+            "{}",
+            case.7
+        );
+    }
+}
+
+#[test]
+fn in_given_out_with_fee_should_work() {
+    let cases = vec![
+        (
+            2000,
+            1000,
+            80,
+            20,
+            500,
+            Permill::zero(),
+            Some((2161, 0)),
+            "No fee",
+        ),
+        (
+            2000,
+            1000,
+            80,
+            20,
+            500,
+            Permill::from_percent(1),
+            Some((2183, 22)),
+            "Fee is rounded up",
+        ),
+        (
+            1000,
+            1000,
+            50,
+            50,
+            1000,
+            Permill::from_percent(1),
+            None,
+            "Amount out equal to reserve",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_in_given_out_with_fee(case.0, case.1, case.2, case.3, case.4, case.5),
+            case.6,
+            "{}",
+            case.7
+        );
+    }
+}
+
+#[test]
+fn linear_weights_should_work() {
+    let cases = vec![
+        (100u32, 200u32, 20, 80, 100u32, Some(20), "Start"),
+        (100, 200, 20, 80, 200, Some(80), "End"),
+        (100, 200, 20, 80, 150, Some(50), "Increasing weight"),
+        (100, 200, 80, 20, 125, Some(65), "Decreasing weight"),
+        (100, 200, 20, 21, 150, Some(20), "Rounded down"),
+        (100, 200, 20, 80, 99, None, "Before start"),
+        (100, 200, 20, 80, 201, None, "After end"),
+        (100, 100, 20, 80, 100, None, "Empty interval"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_linear_weights(case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
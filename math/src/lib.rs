@@ -1,3 +1,7 @@
+pub mod concentrated;
+pub mod lbp;
+pub mod stableswap;
+mod transcendental;
 pub mod xyk;
 
 #[macro_export]
@@ -0,0 +1,153 @@
+use crate::stableswap::*;
+use proptest::prelude::*;
+use sp_arithmetic::Permill;
+
+pub const ONE: Balance = 1_000_000_000_000;
+
+fn asset_reserve() -> impl Strategy<Value = Balance> {
+    1000 * ONE..10_000_000 * ONE
+}
+
+fn trade_amount() -> impl Strategy<Value = Balance> {
+    ONE..100 * ONE
+}
+
+fn amplification() -> impl Strategy<Value = Balance> {
+    1u128..10_000u128
+}
+
+fn reserves() -> impl Strategy<Value = Vec<Balance>> {
+    prop::collection::vec(asset_reserve(), 2..5)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn sell_invariants(reserves in reserves(),
+        amount in trade_amount(),
+        amplification in amplification()
+    ) {
+        let idx_out = reserves.len() - 1;
+        let amount_out = calculate_out_given_in(&reserves, 0, idx_out, amount, amplification).unwrap();
+
+        let mut new_reserves = reserves.clone();
+        new_reserves[0] += amount;
+        new_reserves[idx_out] -= amount_out;
+
+        let d = calculate_d(&reserves, amplification).unwrap();
+        let new_d = calculate_d(&new_reserves, amplification).unwrap();
+
+        assert!(new_d >= d, "Invariant decreased for out given in");
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn buy_invariants(reserves in reserves(),
+        amount in trade_amount(),
+        amplification in amplification()
+    ) {
+        let idx_out = reserves.len() - 1;
+        let amount_in = calculate_in_given_out(&reserves, 0, idx_out, amount, amplification).unwrap();
+
+        let mut new_reserves = reserves.clone();
+        new_reserves[0] += amount_in;
+        new_reserves[idx_out] -= amount;
+
+        let d = calculate_d(&reserves, amplification).unwrap();
+        let new_d = calculate_d(&new_reserves, amplification).unwrap();
+
+        assert!(new_d >= d, "Invariant decreased for in given out");
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn sell_then_buy_should_not_be_profitable(reserves in reserves(),
+        amount in trade_amount(),
+        amplification in amplification()
+    ) {
+        let idx_out = reserves.len() - 1;
+        let amount_out = calculate_out_given_in(&reserves, 0, idx_out, amount, amplification).unwrap();
+
+        let mut new_reserves = reserves.clone();
+        new_reserves[0] += amount;
+        new_reserves[idx_out] -= amount_out;
+
+        let amount_back = calculate_out_given_in(&new_reserves, idx_out, 0, amount_out, amplification).unwrap();
+
+        assert!(amount_back <= amount);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn balanced_reserves_should_have_invariant_equal_to_sum(reserve in asset_reserve(),
+        n in 2usize..9,
+        amplification in amplification()
+    ) {
+        let reserves = vec![reserve; n];
+
+        let d = calculate_d(&reserves, amplification).unwrap();
+
+        assert!(d.abs_diff(reserve * n as Balance) <= 1);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn amplification_should_be_between_endpoints(initial in amplification(),
+        final_amplification in amplification(),
+        start in 0u32..1_000_000u32,
+        length in 1u32..1_000_000u32,
+        at in 0u32..3_000_000u32
+    ) {
+        let amplification = calculate_amplification(initial, final_amplification, start, start + length, at).unwrap();
+
+        assert!(amplification >= initial.min(final_amplification));
+        assert!(amplification <= initial.max(final_amplification));
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn add_liquidity_should_not_issue_more_than_invariant_growth(reserves in reserves(),
+        amount in trade_amount(),
+        amplification in amplification(),
+        fee in (0u32..100_000u32).prop_map(Permill::from_parts)
+    ) {
+        let share_issuance = calculate_d(&reserves, amplification).unwrap();
+
+        let mut updated_reserves = reserves.clone();
+        updated_reserves[0] += amount;
+
+        let shares = calculate_shares(&reserves, &updated_reserves, amplification, share_issuance, fee).unwrap();
+        let updated_d = calculate_d(&updated_reserves, amplification).unwrap();
+
+        assert!(shares <= updated_d - share_issuance);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn remove_liquidity_should_keep_share_value(reserves in reserves(),
+        shares in trade_amount(),
+        amplification in amplification()
+    ) {
+        let share_issuance = calculate_d(&reserves, amplification).unwrap();
+
+        let amounts = calculate_liquidity_out_with_fee(&reserves, shares, share_issuance, Permill::zero()).unwrap();
+        let updated_reserves: Vec<Balance> = reserves.iter().zip(amounts.iter()).map(|(reserve, (amount, _))| reserve - amount).collect();
+
+        let updated_d = calculate_d(&updated_reserves, amplification).unwrap();
+
+        // Value of a share does not decrease
+        assert!(updated_d >= share_issuance - shares);
+    }
+}
@@ -0,0 +1,456 @@
+use crate::{ensure, round_up, to_balance, to_u256};
+use core::convert::TryFrom;
+use primitive_types::U256;
+use sp_arithmetic::traits::{AtLeast32BitUnsigned, SaturatedConversion};
+use sp_arithmetic::{PerThing, Permill};
+
+pub type Balance = u128;
+
+const FIXED_ROUND_UP: Balance = 1;
+
+/// Maximum number of Newton iterations when calculating the invariant or a reserve.
+const MAX_ITERATIONS: u8 = 255;
+
+/// Converging precision of Newton iterations.
+const PRECISION: u8 = 1;
+
+fn has_converged(value: U256, previous: U256) -> bool {
+    let diff = if value > previous {
+        value - previous
+    } else {
+        previous - value
+    };
+    diff <= U256::from(PRECISION)
+}
+
+/// Calculating `A * n^n` used in the invariant, where `n` is number of assets.
+fn calculate_ann(n: usize, amplification: Balance) -> Option<U256> {
+    let n = U256::from(n);
+    n.checked_pow(n)?.checked_mul(U256::from(amplification))
+}
+
+/// Calculating D invariant of given reserves using Newton iteration.
+/// Formula : A * n^n * sum(x_i) + D = A * n^n * D + D^(n+1) / (n^n * prod(x_i))
+///
+/// - `reserves` - reserves of all assets of the pool
+/// - `amplification` - amplification parameter A
+///
+/// Returns None in case of error
+pub fn calculate_d(reserves: &[Balance], amplification: Balance) -> Option<Balance> {
+    ensure!(reserves.len() >= 2 && amplification != 0);
+
+    let n = U256::from(reserves.len());
+    let ann = calculate_ann(reserves.len(), amplification)?;
+
+    let sum = reserves.iter().try_fold(U256::zero(), |acc, reserve| {
+        acc.checked_add(U256::from(*reserve))
+    })?;
+
+    if sum.is_zero() {
+        return Some(0);
+    }
+
+    let mut d = sum;
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut d_p = d;
+        for reserve in reserves {
+            let reserve = U256::from(*reserve);
+            ensure!(!reserve.is_zero());
+            d_p = d_p.checked_mul(d)?.checked_div(reserve.checked_mul(n)?)?;
+        }
+
+        let d_prev = d;
+
+        // D = (Ann * S + D_P * n) * D / ((Ann - 1) * D + (n + 1) * D_P)
+        let numerator = ann
+            .checked_mul(sum)?
+            .checked_add(d_p.checked_mul(n)?)?
+            .checked_mul(d)?;
+        let denominator = ann
+            .checked_sub(U256::one())?
+            .checked_mul(d)?
+            .checked_add(n.checked_add(U256::one())?.checked_mul(d_p)?)?;
+
+        d = numerator.checked_div(denominator)?;
+
+        if has_converged(d, d_prev) {
+            return to_balance!(d);
+        }
+    }
+
+    None
+}
+
+/// Calculating reserve of asset at `asset_index` which keeps D invariant given reserves of
+/// other assets, using Newton iteration.
+/// Formula : y^2 + (b - D) * y = c, where b = S' + D / Ann and c = D^(n+1) / (n^n * P' * Ann)
+///
+/// - `reserves` - reserves of all assets of the pool, reserve at `asset_index` is ignored
+/// - `asset_index` - index of the calculated asset
+/// - `d` - D invariant
+/// - `amplification` - amplification parameter A
+///
+/// Returns None in case of error
+fn calculate_y(
+    reserves: &[Balance],
+    asset_index: usize,
+    d: Balance,
+    amplification: Balance,
+) -> Option<Balance> {
+    ensure!(asset_index < reserves.len());
+
+    let n = U256::from(reserves.len());
+    let ann = calculate_ann(reserves.len(), amplification)?;
+    let d = U256::from(d);
+
+    let mut sum = U256::zero();
+    let mut c = d;
+
+    for (idx, reserve) in reserves.iter().enumerate() {
+        if idx == asset_index {
+            continue;
+        }
+        let reserve = U256::from(*reserve);
+        ensure!(!reserve.is_zero());
+
+        sum = sum.checked_add(reserve)?;
+        c = c.checked_mul(d)?.checked_div(reserve.checked_mul(n)?)?;
+    }
+
+    c = c.checked_mul(d)?.checked_div(ann.checked_mul(n)?)?;
+    let b = sum.checked_add(d.checked_div(ann)?)?;
+
+    let mut y = d;
+
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+
+        // y = (y^2 + c) / (2 * y + b - D)
+        let numerator = y.checked_mul(y)?.checked_add(c)?;
+        let denominator = y
+            .checked_mul(U256::from(2u8))?
+            .checked_add(b)?
+            .checked_sub(d)?;
+
+        y = numerator.checked_div(denominator)?;
+
+        if has_converged(y, y_prev) {
+            return to_balance!(y);
+        }
+    }
+
+    None
+}
+
+/// Calculating amount to be received from the pool given the amount to be sent to the pool
+/// and reserves of all assets.
+///
+/// - `reserves` - reserves of all assets of the pool
+/// - `idx_in` - index of selling asset
+/// - `idx_out` - index of buying asset
+/// - `amount_in` - amount
+/// - `amplification` - amplification parameter A
+///
+/// Returns None in case of error
+pub fn calculate_out_given_in(
+    reserves: &[Balance],
+    idx_in: usize,
+    idx_out: usize,
+    amount_in: Balance,
+    amplification: Balance,
+) -> Option<Balance> {
+    ensure!(idx_in != idx_out && idx_in < reserves.len() && idx_out < reserves.len());
+
+    if amount_in == 0 {
+        return Some(0);
+    }
+
+    let d = calculate_d(reserves, amplification)?;
+
+    let mut new_reserves = reserves.to_vec();
+    new_reserves[idx_in] = new_reserves[idx_in].checked_add(amount_in)?;
+
+    let new_out_reserve = calculate_y(&new_reserves, idx_out, d, amplification)?;
+
+    let amount_out = reserves[idx_out].checked_sub(new_out_reserve)?;
+
+    // Rounded down to prevent value leaking from the pool
+    Some(amount_out.saturating_sub(FIXED_ROUND_UP))
+}
+
+/// Calculating amount to be sent to the pool given the amount to be received from the pool
+/// and reserves of all assets.
+///
+/// - `reserves` - reserves of all assets of the pool
+/// - `idx_in` - index of selling asset
+/// - `idx_out` - index of buying asset
+/// - `amount_out` - buy amount
+/// - `amplification` - amplification parameter A
+///
+/// Returns None in case of error
+pub fn calculate_in_given_out(
+    reserves: &[Balance],
+    idx_in: usize,
+    idx_out: usize,
+    amount_out: Balance,
+    amplification: Balance,
+) -> Option<Balance> {
+    ensure!(idx_in != idx_out && idx_in < reserves.len() && idx_out < reserves.len());
+
+    if amount_out == 0 {
+        return Some(0);
+    }
+    ensure!(amount_out < reserves[idx_out]);
+
+    let d = calculate_d(reserves, amplification)?;
+
+    let mut new_reserves = reserves.to_vec();
+    new_reserves[idx_out] -= amount_out;
+
+    let new_in_reserve = calculate_y(&new_reserves, idx_in, d, amplification)?;
+
+    // We are rounding up to prevent value leaking from the pool
+    round_up!(new_in_reserve.checked_sub(reserves[idx_in])?)
+}
+
+/// Calculating amount to be received from the pool given the amount to be sent to the pool,
+/// reserves of all assets and trade fee.
+/// Fee is deducted from the amount out and stays in the pool.
+///
+/// Returns amount out without fee and the fee amount, or None in case of error
+pub fn calculate_out_given_in_with_fee(
+    reserves: &[Balance],
+    idx_in: usize,
+    idx_out: usize,
+    amount_in: Balance,
+    amplification: Balance,
+    fee: Permill,
+) -> Option<(Balance, Balance)> {
+    let amount_out = calculate_out_given_in(reserves, idx_in, idx_out, amount_in, amplification)?;
+
+    // Fee is rounded up to prevent value leaking from the pool
+    let fee_amount = fee.mul_ceil(amount_out);
+    let amount_out_without_fee = amount_out.checked_sub(fee_amount)?;
+
+    Some((amount_out_without_fee, fee_amount))
+}
+
+/// Calculating amount to be sent to the pool given the amount to be received from the pool,
+/// reserves of all assets and trade fee.
+/// Fee is added to the amount in and stays in the pool.
+///
+/// Returns amount in including fee and the fee amount, or None in case of error
+pub fn calculate_in_given_out_with_fee(
+    reserves: &[Balance],
+    idx_in: usize,
+    idx_out: usize,
+    amount_out: Balance,
+    amplification: Balance,
+    fee: Permill,
+) -> Option<(Balance, Balance)> {
+    let amount_in = calculate_in_given_out(reserves, idx_in, idx_out, amount_out, amplification)?;
+
+    // Fee is rounded up to prevent value leaking from the pool
+    let fee_amount = fee.mul_ceil(amount_in);
+    let amount_in_with_fee = amount_in.checked_add(fee_amount)?;
+
+    Some((amount_in_with_fee, fee_amount))
+}
+
+/// Calculating spot price of asset `idx_in` denominated in asset `idx_out`.
+/// Formula : AMOUNT * x_out * (Ann * x_in + D_P) / (x_in * (Ann * x_out + D_P)),
+/// where D_P = D^(n+1) / (n^n * prod(x_i))
+///
+/// - `reserves` - reserves of all assets of the pool
+/// - `idx_in` - index of priced asset
+/// - `idx_out` - index of asset in which the price is denominated
+/// - `amount` - amount
+/// - `amplification` - amplification parameter A
+///
+/// Returns None in case of error
+pub fn calculate_spot_price(
+    reserves: &[Balance],
+    idx_in: usize,
+    idx_out: usize,
+    amount: Balance,
+    amplification: Balance,
+) -> Option<Balance> {
+    ensure!(idx_in != idx_out && idx_in < reserves.len() && idx_out < reserves.len());
+
+    if amount == 0 {
+        return Some(0);
+    }
+
+    let n = U256::from(reserves.len());
+    let ann = calculate_ann(reserves.len(), amplification)?;
+    let d = U256::from(calculate_d(reserves, amplification)?);
+
+    let mut d_p = d;
+    for reserve in reserves {
+        d_p = d_p
+            .checked_mul(d)?
+            .checked_div(U256::from(*reserve).checked_mul(n)?)?;
+    }
+
+    let (amount, in_reserve, out_reserve) = to_u256!(amount, reserves[idx_in], reserves[idx_out]);
+
+    let numerator = amount
+        .checked_mul(out_reserve)?
+        .checked_mul(ann.checked_mul(in_reserve)?.checked_add(d_p)?)?;
+    let denominator = in_reserve.checked_mul(ann.checked_mul(out_reserve)?.checked_add(d_p)?)?;
+
+    to_balance!(numerator.checked_div(denominator)?)
+}
+
+/// Calculating shares to be issued for liquidity added to the pool, given reserves before and
+/// after the liquidity is added. Any subset of assets can be added.
+/// Formula : SHARE_ISSUANCE * (D_ADJUSTED - D_INITIAL) / D_INITIAL
+///
+/// Part of added amounts which differs from the balanced deposit is charged
+/// `FEE * n / (4 * (n - 1))` as if it was traded, D_ADJUSTED is invariant of reserves
+/// without these fees.
+///
+/// - `initial_reserves` - reserves of all assets before liquidity is added
+/// - `updated_reserves` - reserves of all assets after liquidity is added
+/// - `amplification` - amplification parameter A
+/// - `share_issuance` - total issuance of shares of the pool
+/// - `fee` - trade fee
+///
+/// Returns None in case of error
+pub fn calculate_shares(
+    initial_reserves: &[Balance],
+    updated_reserves: &[Balance],
+    amplification: Balance,
+    share_issuance: Balance,
+    fee: Permill,
+) -> Option<Balance> {
+    ensure!(initial_reserves.len() == updated_reserves.len());
+
+    let initial_d = calculate_d(initial_reserves, amplification)?;
+    let updated_d = calculate_d(updated_reserves, amplification)?;
+    ensure!(initial_d != 0 && updated_d > initial_d);
+
+    let n = U256::from(initial_reserves.len());
+    let fee_numerator = U256::from(fee.deconstruct()).checked_mul(n)?;
+    let fee_denominator = U256::from(Permill::ACCURACY)
+        .checked_mul(U256::from(4u8))?
+        .checked_mul(n.checked_sub(U256::one())?)?;
+
+    let adjusted_reserves = initial_reserves
+        .iter()
+        .zip(updated_reserves.iter())
+        .map(|(initial, updated)| {
+            let (initial, updated, initial_d, updated_d) =
+                to_u256!(*initial, *updated, initial_d, updated_d);
+
+            let ideal = updated_d.checked_mul(initial)?.checked_div(initial_d)?;
+            let diff = if updated > ideal {
+                updated - ideal
+            } else {
+                ideal - updated
+            };
+
+            // Fee is rounded up to prevent value leaking from the pool
+            let fee_amount = diff
+                .checked_mul(fee_numerator)?
+                .checked_add(fee_denominator.checked_sub(U256::one())?)?
+                .checked_div(fee_denominator)?;
+
+            to_balance!(updated.checked_sub(fee_amount)?)
+        })
+        .collect::<Option<Vec<Balance>>>()?;
+
+    let adjusted_d = calculate_d(&adjusted_reserves, amplification)?;
+
+    let (share_issuance, initial_d, adjusted_d) = to_u256!(share_issuance, initial_d, adjusted_d);
+
+    let shares = share_issuance
+        .checked_mul(adjusted_d.checked_sub(initial_d)?)?
+        .checked_div(initial_d)?;
+
+    to_balance!(shares)
+}
+
+/// Calculating amounts of all assets to be received for removed shares and withdraw fee
+/// which stays in the pool.
+/// Formula : RESERVE * SHARES / SHARE_ISSUANCE - FEE
+///
+/// - `reserves` - reserves of all assets of the pool
+/// - `shares` - removed shares
+/// - `share_issuance` - total issuance of shares of the pool
+/// - `fee` - withdraw fee
+///
+/// Returns amounts without fee and fee amounts of all assets, or None in case of error
+pub fn calculate_liquidity_out_with_fee(
+    reserves: &[Balance],
+    shares: Balance,
+    share_issuance: Balance,
+    fee: Permill,
+) -> Option<Vec<(Balance, Balance)>> {
+    ensure!(share_issuance != 0 && shares <= share_issuance);
+
+    reserves
+        .iter()
+        .map(|reserve| {
+            let (reserve, shares, share_issuance) = to_u256!(*reserve, shares, share_issuance);
+
+            // Rounded down to prevent value leaking from the pool
+            let amount = to_balance!(reserve.checked_mul(shares)?.checked_div(share_issuance)?)?;
+
+            // Fee is rounded up to prevent value leaking from the pool
+            let fee_amount = fee.mul_ceil(amount);
+
+            Some((amount.checked_sub(fee_amount)?, fee_amount))
+        })
+        .collect()
+}
+
+/// Calculating amplification at given block of its linear change.
+/// Amplification is `initial` before `start` and `final` after `end`.
+///
+/// - `initial` - amplification at `start`
+/// - `final_amplification` - amplification at `end`
+/// - `start` - block in which the change starts
+/// - `end` - block in which the change ends
+/// - `at` - block
+///
+/// Returns None in case of error
+pub fn calculate_amplification<BlockNumber: AtLeast32BitUnsigned>(
+    initial: Balance,
+    final_amplification: Balance,
+    start: BlockNumber,
+    end: BlockNumber,
+    at: BlockNumber,
+) -> Option<Balance> {
+    if at <= start || end <= start {
+        return Some(initial);
+    }
+    if at >= end {
+        return Some(final_amplification);
+    }
+
+    let from_start: u128 = (at - start.clone()).saturated_into();
+    let length: u128 = (end - start).saturated_into();
+
+    let (initial, final_amplification, from_start, length) =
+        to_u256!(initial, final_amplification, from_start, length);
+
+    let amplification = if final_amplification >= initial {
+        initial.checked_add(
+            final_amplification
+                .checked_sub(initial)?
+                .checked_mul(from_start)?
+                .checked_div(length)?,
+        )?
+    } else {
+        initial.checked_sub(
+            initial
+                .checked_sub(final_amplification)?
+                .checked_mul(from_start)?
+                .checked_div(length)?,
+        )?
+    };
+
+    to_balance!(amplification)
+}
@@ -0,0 +1,8 @@
+mod math;
+
+pub use self::math::*;
+
+#[cfg(test)]
+mod invariants;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,401 @@
+use crate::stableswap::*;
+use sp_arithmetic::Permill;
+use std::vec;
+
+const ONE: Balance = 1_000_000_000_000;
+
+#[test]
+fn calculate_d_should_work() {
+    let cases = vec![
+        (
+            vec![1000 * ONE, 1000 * ONE],
+            100,
+            Some(2000 * ONE),
+            "Balanced reserves",
+        ),
+        (
+            vec![1000 * ONE, 500 * ONE],
+            100,
+            Some(1_499_534_015_561_310),
+            "Imbalanced reserves",
+        ),
+        (
+            vec![1000 * ONE, 500 * ONE],
+            1,
+            Some(1_470_278_518_099_803),
+            "Low amplification",
+        ),
+        (
+            vec![100 * ONE, 200 * ONE, 300 * ONE],
+            50,
+            Some(599_852_325_652_925),
+            "Three assets",
+        ),
+        (vec![0, 0], 100, Some(0), "Empty pool"),
+        (vec![1000 * ONE, 0], 100, None, "Zero reserve"),
+        (vec![1000 * ONE], 100, None, "Single asset"),
+        (vec![1000 * ONE, 1000 * ONE], 0, None, "Zero amplification"),
+    ];
+
+    for case in cases {
+        assert_eq!(calculate_d(&case.0, case.1), case.2, "{}", case.3);
+    }
+}
+
+#[test]
+fn out_given_in_should_work() {
+    let cases = vec![
+        (
+            vec![1000 * ONE, 1000 * ONE],
+            0,
+            1,
+            10 * ONE,
+            100,
+            Some(9_999_502_463_057),
+            "Balanced reserves",
+        ),
+        (
+            vec![1000 * ONE, 500 * ONE],
+            0,
+            1,
+            10 * ONE,
+            100,
+            Some(9_956_939_984_771),
+            "Imbalanced reserves",
+        ),
+        (
+            vec![1000 * ONE, 500 * ONE],
+            0,
+            1,
+            10 * ONE,
+            1,
+            Some(7_744_779_151_859),
+            "Low amplification",
+        ),
+        (
+            vec![100 * ONE, 200 * ONE, 300 * ONE],
+            0,
+            2,
+            10 * ONE,
+            50,
+            Some(10_035_193_175_281),
+            "Three assets",
+        ),
+        (
+            vec![1000 * ONE, 1000 * ONE],
+            0,
+            1,
+            0,
+            100,
+            Some(0),
+            "Zero amount in",
+        ),
+        (
+            vec![1000 * ONE, 1000 * ONE],
+            0,
+            0,
+            ONE,
+            100,
+            None,
+            "Same asset",
+        ),
+        (
+            vec![1000 * ONE, 1000 * ONE],
+            0,
+            2,
+            ONE,
+            100,
+            None,
+            "Unknown asset",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_out_given_in(&case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn in_given_out_should_work() {
+    let cases = vec![
+        (
+            vec![1000 * ONE, 1000 * ONE],
+            0,
+            1,
+            10 * ONE,
+            100,
+            Some(10_000_497_586_461),
+            "Balanced reserves",
+        ),
+        (
+            vec![1000 * ONE, 500 * ONE],
+            0,
+            1,
+            10 * ONE,
+            100,
+            Some(10_043_251_841_417),
+            "Imbalanced reserves",
+        ),
+        (
+            vec![100 * ONE, 200 * ONE, 300 * ONE],
+            0,
+            2,
+            10 * ONE,
+            50,
+            Some(9_964_916_876_520),
+            "Three assets",
+        ),
+        (
+            vec![1000 * ONE, 1000 * ONE],
+            0,
+            1,
+            0,
+            100,
+            Some(0),
+            "Zero amount out",
+        ),
+        (
+            vec![1000 * ONE, 1000 * ONE],
+            0,
+            1,
+            1000 * ONE,
+            100,
+            None,
+            "Amount out equal to reserve",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_in_given_out(&case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn with_fee_should_work() {
+    let reserves = vec![1000 * ONE, 1000 * ONE];
+
+    assert_eq!(
+        calculate_out_given_in_with_fee(&reserves, 0, 1, 10 * ONE, 100, Permill::from_percent(1)),
+        Some((9_899_507_438_426, 99_995_024_631))
+    );
+    assert_eq!(
+        calculate_in_given_out_with_fee(&reserves, 0, 1, 10 * ONE, 100, Permill::from_percent(1)),
+        Some((10_100_502_562_326, 100_004_975_865))
+    );
+}
+
+#[test]
+fn spot_price_should_work() {
+    let cases = vec![
+        (
+            vec![1000 * ONE, 1000 * ONE],
+            0,
+            1,
+            ONE,
+            100,
+            Some(ONE),
+            "Balanced reserves",
+        ),
+        (
+            vec![1000 * ONE, 500 * ONE],
+            0,
+            1,
+            ONE,
+            100,
+            Some(995_820_412_932),
+            "Imbalanced reserves",
+        ),
+        (
+            vec![1000 * ONE, 500 * ONE],
+            0,
+            1,
+            ONE,
+            1,
+            Some(778_616_378_583),
+            "Low amplification",
+        ),
+        (
+            vec![1000 * ONE, 1000 * ONE],
+            0,
+            1,
+            0,
+            100,
+            Some(0),
+            "Zero amount",
+        ),
+        (vec![1000 * ONE, 0], 0, 1, ONE, 100, None, "Zero reserve"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_spot_price(&case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn calculate_shares_should_work() {
+    let cases = vec![
+        (
+            vec![100 * ONE, 100 * ONE, 100 * ONE],
+            vec![110 * ONE, 110 * ONE, 110 * ONE],
+            100,
+            300 * ONE,
+            Permill::zero(),
+            Some(30 * ONE),
+            "Balanced deposit",
+        ),
+        (
+            vec![100 * ONE, 100 * ONE, 100 * ONE],
+            vec![110 * ONE, 110 * ONE, 110 * ONE],
+            100,
+            300 * ONE,
+            Permill::from_percent(1),
+            Some(30 * ONE),
+            "Balanced deposit pays no fee",
+        ),
+        (
+            vec![100 * ONE, 100 * ONE, 100 * ONE],
+            vec![130 * ONE, 100 * ONE, 100 * ONE],
+            100,
+            300 * ONE,
+            Permill::zero(),
+            Some(29_997_088_807_530),
+            "Single asset",
+        ),
+        (
+            vec![100 * ONE, 100 * ONE, 100 * ONE],
+            vec![130 * ONE, 100 * ONE, 100 * ONE],
+            100,
+            300 * ONE,
+            Permill::from_percent(1),
+            Some(29_847_098_358_166),
+            "Single asset with fee",
+        ),
+        (
+            vec![100 * ONE, 200 * ONE, 300 * ONE],
+            vec![100 * ONE, 200 * ONE, 330 * ONE],
+            50,
+            600 * ONE,
+            Permill::from_parts(3_000),
+            Some(29_933_788_783_714),
+            "Imbalanced pool",
+        ),
+        (
+            vec![100 * ONE, 100 * ONE],
+            vec![100 * ONE, 100 * ONE],
+            100,
+            200 * ONE,
+            Permill::zero(),
+            None,
+            "Nothing added",
+        ),
+        (
+            vec![100 * ONE, 100 * ONE],
+            vec![100 * ONE],
+            100,
+            200 * ONE,
+            Permill::zero(),
+            None,
+            "Different number of assets",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_shares(&case.0, &case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn liquidity_out_with_fee_should_work() {
+    let cases = vec![
+        (
+            vec![100, 200, 300],
+            10,
+            100,
+            Permill::zero(),
+            Some(vec![(10, 0), (20, 0), (30, 0)]),
+            "No fee",
+        ),
+        (
+            vec![100, 200, 300],
+            10,
+            100,
+            Permill::from_percent(3),
+            Some(vec![(9, 1), (19, 1), (29, 1)]),
+            "Fee is rounded up",
+        ),
+        (
+            vec![100, 200, 300],
+            100,
+            100,
+            Permill::zero(),
+            Some(vec![(100, 0), (200, 0), (300, 0)]),
+            "All shares",
+        ),
+        (
+            vec![100, 200, 300],
+            101,
+            100,
+            Permill::zero(),
+            None,
+            "More than issuance",
+        ),
+        (
+            vec![100, 200, 300],
+            10,
+            0,
+            Permill::zero(),
+            None,
+            "Zero issuance",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_liquidity_out_with_fee(&case.0, case.1, case.2, case.3),
+            case.4,
+            "{}",
+            case.5
+        );
+    }
+}
+
+#[test]
+fn amplification_should_work() {
+    let cases = vec![
+        (100, 200, 10u32, 20u32, 5u32, Some(100), "Before start"),
+        (100, 200, 10, 20, 10, Some(100), "Start"),
+        (100, 200, 10, 20, 15, Some(150), "Increasing"),
+        (200, 100, 10, 20, 12, Some(180), "Decreasing"),
+        (100, 200, 10, 20, 20, Some(200), "End"),
+        (100, 200, 10, 20, 25, Some(200), "After end"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_amplification(case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
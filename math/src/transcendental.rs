@@ -0,0 +1,104 @@
+use crate::ensure;
+use fixed::types::I32F96;
+
+/// Fixed point number used in transcendental functions.
+pub type FixedRatio = I32F96;
+
+const FRAC_BITS: u32 = 96;
+
+/// Natural logarithm of 2 with 96 fractional bits.
+const LN_2: FixedRatio = FixedRatio::from_bits(0xb17217f7d1cf79abc9e3b398);
+
+/// Number of terms of the Taylor series used to calculate exp2 of fractional part.
+const EXP_TERMS: u32 = 40;
+
+fn one() -> FixedRatio {
+    FixedRatio::from_num(1)
+}
+
+/// Calculating binary logarithm of a positive number.
+/// Integer part is given by the highest bit, fractional part is calculated bit by bit
+/// by repeated squaring of the normalized number.
+///
+/// Returns None in case of error
+pub fn log2(x: FixedRatio) -> Option<FixedRatio> {
+    ensure!(x > FixedRatio::from_num(0));
+
+    let bits = x.to_bits() as u128;
+    let integer_part = (127 - bits.leading_zeros()) as i32 - FRAC_BITS as i32;
+
+    // Normalize to [1, 2)
+    let normalized = if integer_part >= 0 {
+        bits >> integer_part
+    } else {
+        bits << -integer_part
+    };
+
+    let two = FixedRatio::from_num(2);
+    let mut y = FixedRatio::from_bits(normalized as i128);
+    let mut result = FixedRatio::from_num(integer_part);
+    let mut bit = FixedRatio::from_bits(one().to_bits() >> 1);
+
+    while bit > FixedRatio::from_num(0) {
+        y = y.checked_mul(y)?;
+        if y >= two {
+            y = FixedRatio::from_bits(y.to_bits() >> 1);
+            result = result.checked_add(bit)?;
+        }
+        bit = FixedRatio::from_bits(bit.to_bits() >> 1);
+    }
+
+    Some(result)
+}
+
+/// Calculating 2 to the power of a number.
+/// Power of fractional part is calculated as Taylor series of e^(fraction * ln 2)
+/// and then shifted by integer part.
+///
+/// Returns None in case of error
+pub fn exp2(x: FixedRatio) -> Option<FixedRatio> {
+    let integer_part = (x.to_bits() >> FRAC_BITS) as i32;
+    let fraction = FixedRatio::from_bits(x.to_bits() & ((1i128 << FRAC_BITS) - 1));
+
+    let z = fraction.checked_mul(LN_2)?;
+
+    let mut term = one();
+    let mut result = one();
+    for k in 1..=EXP_TERMS {
+        term = term.checked_mul(z)?.checked_div(FixedRatio::from_num(k))?;
+        if term == FixedRatio::from_num(0) {
+            break;
+        }
+        result = result.checked_add(term)?;
+    }
+
+    // Result of fraction is in [1, 2), so it can be shifted by at most 30 bits to the left
+    if integer_part >= 0 {
+        ensure!(integer_part < 31);
+        Some(FixedRatio::from_bits(result.to_bits() << integer_part))
+    } else if integer_part > -128 {
+        Some(FixedRatio::from_bits(result.to_bits() >> -integer_part))
+    } else {
+        Some(FixedRatio::from_num(0))
+    }
+}
+
+/// Calculating power of a non-negative base to a non-negative exponent.
+/// Formula : 2 ^ (EXPONENT * LOG2(BASE))
+///
+/// Returns None in case of error
+pub fn pow(base: FixedRatio, exponent: FixedRatio) -> Option<FixedRatio> {
+    ensure!(base >= FixedRatio::from_num(0) && exponent >= FixedRatio::from_num(0));
+
+    if exponent == FixedRatio::from_num(0) {
+        return Some(one());
+    }
+    if base == FixedRatio::from_num(0) {
+        return Some(FixedRatio::from_num(0));
+    }
+    if exponent == one() {
+        return Some(base);
+    }
+
+    exp2(log2(base)?.checked_mul(exponent)?)
+}
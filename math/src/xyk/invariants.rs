@@ -1,8 +1,8 @@
 use crate::assert_eq_approx;
 use crate::xyk::*;
-use primitive_types::U256;
+use primitive_types::{U256, U512};
 use proptest::prelude::*;
-use sp_arithmetic::FixedU128;
+use sp_arithmetic::{FixedU128, Permill};
 
 pub const ONE: Balance = 1_000_000_000_000;
 const TOLERANCE: Balance = 1_000;
@@ -15,6 +15,10 @@ fn trade_amount() -> impl Strategy<Value = Balance> {
     ONE..100 * ONE
 }
 
+fn trade_fee() -> impl Strategy<Value = Permill> {
+    (0u32..100_000u32).prop_map(Permill::from_parts)
+}
+
 fn assert_asset_invariant(
     old_state: (Balance, Balance),
     new_state: (Balance, Balance),
@@ -70,6 +74,48 @@ proptest! {
     }
 }
 
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn sell_with_fee_invariants( asset_in_reserve in asset_reserve(),
+        asset_out_reserve in asset_reserve(),
+        amount in  trade_amount(),
+        fee in trade_fee(),
+    ) {
+        let amount_out = calculate_out_given_in(asset_in_reserve, asset_out_reserve, amount).unwrap();
+        let (amount_out_without_fee, fee_amount) = calculate_out_given_in_with_fee(asset_in_reserve, asset_out_reserve, amount, fee).unwrap();
+
+        assert_eq!(amount_out_without_fee + fee_amount, amount_out);
+        assert!(fee_amount >= fee * amount_out);
+
+        let new_s = U256::from(asset_in_reserve + amount) * U256::from(asset_out_reserve - amount_out_without_fee);
+        let old_s = U256::from(asset_in_reserve) * U256::from(asset_out_reserve);
+
+        assert!(new_s >= old_s, "Invariant decreased for out given in with fee");
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn buy_with_fee_invariants( asset_in_reserve in asset_reserve(),
+        asset_out_reserve in asset_reserve(),
+        amount in  trade_amount(),
+        fee in trade_fee(),
+    ) {
+        let amount_in = calculate_in_given_out(asset_out_reserve, asset_in_reserve, amount).unwrap();
+        let (amount_in_with_fee, fee_amount) = calculate_in_given_out_with_fee(asset_out_reserve, asset_in_reserve, amount, fee).unwrap();
+
+        assert_eq!(amount_in_with_fee, amount_in + fee_amount);
+        assert!(fee_amount >= fee * amount_in);
+
+        let new_s = U256::from(asset_in_reserve + amount_in_with_fee) * U256::from(asset_out_reserve - amount);
+        let old_s = U256::from(asset_in_reserve) * U256::from(asset_out_reserve);
+
+        assert!(new_s >= old_s, "Invariant decreased for in given out with fee");
+    }
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(1000))]
     #[test]
@@ -155,3 +201,125 @@ proptest! {
         assert!(l >= r);
     }
 }
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn remove_liquidity_with_fee_share_value( asset_a_reserve in asset_reserve(),
+        asset_b_reserve in asset_reserve(),
+        shares in  trade_amount(),
+        issuance in asset_reserve(),
+        fee in trade_fee(),
+    ) {
+        let (amount_a, amount_b) = calculate_liquidity_out(asset_a_reserve, asset_b_reserve, shares, issuance).unwrap();
+        let ((amount_a_without_fee, amount_b_without_fee), (fee_a, fee_b)) =
+            calculate_liquidity_out_with_fee(asset_a_reserve, asset_b_reserve, shares, issuance, fee).unwrap();
+
+        assert_eq!(amount_a_without_fee + fee_a, amount_a);
+        assert_eq!(amount_b_without_fee + fee_b, amount_b);
+
+        // Value of a share must not decrease for remaining liquidity providers
+        // (X - delta_X) / (S - delta_S) >= X / S
+        // (Y - delta_Y) / (S - delta_S) >= Y / S
+
+        let s = U256::from(issuance);
+        let delta_s = U256::from(shares);
+        let x = U256::from(asset_a_reserve);
+        let y = U256::from(asset_b_reserve);
+        let new_x = U256::from(asset_a_reserve - amount_a_without_fee);
+        let new_y = U256::from(asset_b_reserve - amount_b_without_fee);
+
+        assert!(new_x * s >= x * (s - delta_s));
+        assert!(new_y * s >= y * (s - delta_s));
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn initial_shares( amount_a in asset_reserve(),
+        amount_b in asset_reserve(),
+    ) {
+        let shares = calculate_initial_shares(amount_a, amount_b).unwrap();
+
+        // Shares are the largest integer not greater than geometric mean of the amounts
+        // S * S <= X * Y < (S + 1) * (S + 1)
+
+        let s = U256::from(shares);
+        let product = U256::from(amount_a) * U256::from(amount_b);
+
+        assert!(s * s <= product);
+        assert!((s + 1) * (s + 1) > product);
+
+        // Issuance does not depend on the order of assets
+        assert_eq!(calculate_initial_shares(amount_b, amount_a), Some(shares));
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn add_liquidity_single_asset( asset_reserve in asset_reserve(),
+        other_reserve in asset_reserve(),
+        amount in trade_amount(),
+        issuance in asset_reserve(),
+        fee in trade_fee(),
+    ) {
+        let (shares, swap_amount, swap_fee) = calculate_shares_for_single_asset(asset_reserve, other_reserve, amount, issuance, fee).unwrap();
+
+        // Value of a share, as square root of the product of reserves per share, must not decrease
+        // X' * Y * S^2 >= X * Y * S'^2
+
+        let s = U512::from(issuance);
+        let new_s = U512::from(issuance + shares);
+        let x = U512::from(asset_reserve);
+        let y = U512::from(other_reserve);
+        let new_x = U512::from(asset_reserve + amount);
+
+        assert!(new_x * y * s * s >= x * y * new_s * new_s);
+
+        // Must not leak value: removing the shares right away and swapping the other asset back
+        // must not return more than was added
+        let (asset_out, other_out) = calculate_liquidity_out(asset_reserve + amount, other_reserve, shares, issuance + shares).unwrap();
+        let (swapped_back, _) = calculate_out_given_in_with_fee(other_reserve - other_out, asset_reserve + amount - asset_out, other_out, fee).unwrap();
+
+        assert!(asset_out + swapped_back <= amount);
+
+        assert_eq!(calculate_single_asset_swap_amount(asset_reserve, amount, fee), Some(swap_amount));
+        let (amount_received, expected_fee) = calculate_out_given_in_with_fee(asset_reserve, other_reserve, swap_amount, fee).unwrap();
+        assert_eq!(swap_fee, expected_fee);
+        let amount_required = calculate_liquidity_in(asset_reserve + swap_amount, other_reserve - amount_received, amount - swap_amount).unwrap();
+
+        // Amount received in the swap matches the rest in ratio of reserves, up to rounding of swapped amount
+        assert_eq_approx!(amount_required, amount_received, 2 * other_reserve / asset_reserve + 3, "Swapped amount is not optimal");
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+    #[test]
+    fn remove_liquidity_single_asset( asset_reserve in asset_reserve(),
+        other_reserve in asset_reserve(),
+        shares in trade_amount(),
+        issuance in asset_reserve(),
+        withdraw_fee in trade_fee(),
+        fee in trade_fee(),
+    ) {
+        let (amount, swap_amount, _) = calculate_single_asset_liquidity_out(asset_reserve, other_reserve, shares, issuance, withdraw_fee, fee).unwrap();
+
+        // Value of a share, as square root of the product of reserves per share, must not decrease
+        // X' * Y * S^2 >= X * Y * S'^2
+
+        let s = U512::from(issuance);
+        let new_s = U512::from(issuance - shares);
+        let x = U512::from(asset_reserve);
+        let y = U512::from(other_reserve);
+        let new_x = U512::from(asset_reserve - amount);
+
+        assert!(new_x * y * s * s >= x * y * new_s * new_s);
+
+        // Whole amount of the other asset withdrawn by the shares is swapped
+        let ((_, other_out), _) = calculate_liquidity_out_with_fee(asset_reserve, other_reserve, shares, issuance, withdraw_fee).unwrap();
+        assert_eq!(swap_amount, other_out);
+    }
+}
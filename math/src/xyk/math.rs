@@ -1,7 +1,8 @@
 use crate::{ensure, round_up, to_balance, to_u256};
 use core::convert::TryFrom;
 use num_traits::Zero;
-use primitive_types::U256;
+use primitive_types::{U256, U512};
+use sp_arithmetic::{PerThing, Permill};
 
 pub type Balance = u128;
 
@@ -95,6 +96,56 @@ pub fn calculate_in_given_out(
     round_up!(result)
 }
 
+/// Calculating amount to be received from the pool given the amount to be sent to the pool, both reserves and trade fee.
+/// Fee is taken from the amount out and stays in the pool.
+/// Formula : AMOUNT_OUT - AMOUNT_OUT * FEE
+///
+/// - `in_reserve` - reserve amount of selling asset
+/// - `out_reserve` - reserve amount of buying asset
+/// - `amount_in` - amount
+/// - `fee` - trade fee
+///
+/// Returns amount out without fee and the fee amount, or None in case of error
+pub fn calculate_out_given_in_with_fee(
+    in_reserve: Balance,
+    out_reserve: Balance,
+    amount_in: Balance,
+    fee: Permill,
+) -> Option<(Balance, Balance)> {
+    let amount_out = calculate_out_given_in(in_reserve, out_reserve, amount_in)?;
+
+    // Fee is rounded up to prevent value leaking from the pool
+    let fee_amount = fee.mul_ceil(amount_out);
+    let amount_out_without_fee = amount_out.checked_sub(fee_amount)?;
+
+    Some((amount_out_without_fee, fee_amount))
+}
+
+/// Calculating amount to be sent to the pool given the amount to be received from the pool, both reserves and trade fee.
+/// Fee is added to the amount in and stays in the pool.
+/// Formula : AMOUNT_IN + AMOUNT_IN * FEE
+///
+/// - `out_reserve` - reserve amount of buying asset
+/// - `in_reserve` - reserve amount of selling asset
+/// - `amount_out` - buy amount
+/// - `fee` - trade fee
+///
+/// Returns amount in including fee and the fee amount, or None in case of error
+pub fn calculate_in_given_out_with_fee(
+    out_reserve: Balance,
+    in_reserve: Balance,
+    amount_out: Balance,
+    fee: Permill,
+) -> Option<(Balance, Balance)> {
+    let amount_in = calculate_in_given_out(out_reserve, in_reserve, amount_out)?;
+
+    // Fee is rounded up to prevent value leaking from the pool
+    let fee_amount = fee.mul_ceil(amount_in);
+    let amount_in_with_fee = amount_in.checked_add(fee_amount)?;
+
+    Some((amount_in_with_fee, fee_amount))
+}
+
 /// Calculating required amount of asset b given asset a.
 /// Formula : AMOUNT * ASSET_B_RESERVE / ASSET_A_RESERVE
 ///
@@ -160,6 +211,52 @@ pub fn calculate_liquidity_out(
     Some((remove_amount_a, remove_amount_b))
 }
 
+/// Calculating amount of assets returned when removing liquidity with withdraw fee.
+/// Fee is taken from both amounts and stays in the pool.
+/// Formula : AMOUNT_OUT - AMOUNT_OUT * FEE
+///
+/// - `asset_a_reserve` - reserve amount of asset a
+/// - `asset_b_reserve` - reserve amount of asset b
+/// - `amount` - liquidity amount
+/// - `total_liquidity` - total issuance of share asset
+/// - `fee` - withdraw fee
+///
+/// Returns amounts without fee and the fee amounts, or None in case of error
+pub fn calculate_liquidity_out_with_fee(
+    asset_a_reserve: Balance,
+    asset_b_reserve: Balance,
+    amount: Balance,
+    total_liquidity: Balance,
+    fee: Permill,
+) -> Option<((Balance, Balance), (Balance, Balance))> {
+    let (amount_a, amount_b) =
+        calculate_liquidity_out(asset_a_reserve, asset_b_reserve, amount, total_liquidity)?;
+
+    // Fee is rounded up to prevent value leaking from the pool
+    let fee_a = fee.mul_ceil(amount_a);
+    let fee_b = fee.mul_ceil(amount_b);
+
+    Some((
+        (amount_a.checked_sub(fee_a)?, amount_b.checked_sub(fee_b)?),
+        (fee_a, fee_b),
+    ))
+}
+
+/// Calculating amount of shares issued for initial liquidity of a pool.
+/// Formula : SQRT(AMOUNT_A * AMOUNT_B)
+///
+/// - `amount_a` - initial amount of asset a
+/// - `amount_b` - initial amount of asset b
+///
+/// Returns None in case of error
+pub fn calculate_initial_shares(amount_a: Balance, amount_b: Balance) -> Option<Balance> {
+    let (amount_a_hp, amount_b_hp) = to_u256!(amount_a, amount_b);
+
+    let shares_hp = amount_a_hp.checked_mul(amount_b_hp)?.integer_sqrt();
+
+    to_balance!(shares_hp)
+}
+
 /// Calculating amount of shares given to LP for added liquidity
 /// shares = issuance * amount / reserve
 ///
@@ -187,3 +284,206 @@ pub fn calculate_shares(
 
     to_balance!(result)
 }
+
+/// Calculating part of a single asset amount which is swapped to the other asset of the pool,
+/// so that both assets can be added as liquidity in ratio of reserves after the swap.
+/// Fee is taken from the amount out of the swap, as in `calculate_out_given_in_with_fee`.
+/// Formula : positive root of S^2 + S * ((2 - FEE) * ASSET_RESERVE - AMOUNT * FEE) - AMOUNT * ASSET_RESERVE = 0
+///
+/// - `asset_reserve` - reserve amount of added asset
+/// - `amount` - amount of added asset
+/// - `fee` - trade fee
+///
+/// Returns None in case of error
+pub fn calculate_single_asset_swap_amount(
+    asset_reserve: Balance,
+    amount: Balance,
+    fee: Permill,
+) -> Option<Balance> {
+    ensure!(asset_reserve != 0);
+
+    if amount.is_zero() {
+        return Some(Balance::zero());
+    }
+
+    // Equation is multiplied by ONE, so that all coefficients are integers
+    let one = U512::from(Permill::ACCURACY);
+    let fee = U512::from(fee.deconstruct());
+    let (reserve, amount) = (U512::from(asset_reserve), U512::from(amount));
+
+    let b_positive = one
+        .checked_mul(U512::from(2))?
+        .checked_sub(fee)?
+        .checked_mul(reserve)?;
+    let b_negative = amount.checked_mul(fee)?;
+
+    let four_ac = one
+        .checked_mul(one)?
+        .checked_mul(amount)?
+        .checked_mul(reserve)?
+        .checked_mul(U512::from(4))?;
+
+    // Linear coefficient can be negative
+    let b_abs = if b_positive >= b_negative {
+        b_positive - b_negative
+    } else {
+        b_negative - b_positive
+    };
+
+    let discriminant_sqrt = b_abs
+        .checked_mul(b_abs)?
+        .checked_add(four_ac)?
+        .integer_sqrt();
+
+    let numerator = if b_positive >= b_negative {
+        discriminant_sqrt.checked_sub(b_abs)?
+    } else {
+        discriminant_sqrt.checked_add(b_abs)?
+    };
+
+    let swap_amount = numerator.checked_div(one.checked_mul(U512::from(2))?)?;
+
+    Balance::try_from(swap_amount).ok()
+}
+
+/// Calculating amount of shares given to LP for liquidity added in a single asset.
+/// Part of the amount is swapped internally and the rest is added with the amount received.
+///
+/// - `asset_reserve` - reserve amount of added asset
+/// - `other_reserve` - reserve amount of the other asset of the pool
+/// - `amount` - amount of added asset
+/// - `share_issuance` - total issuance of share asset
+/// - `fee` - trade fee
+///
+/// Returns shares, swapped amount of added asset and fee of the swap in the other asset,
+/// or None in case of error
+pub fn calculate_shares_for_single_asset(
+    asset_reserve: Balance,
+    other_reserve: Balance,
+    amount: Balance,
+    share_issuance: Balance,
+    fee: Permill,
+) -> Option<(Balance, Balance, Balance)> {
+    let swap_amount = calculate_single_asset_swap_amount(asset_reserve, amount, fee)?;
+
+    let (amount_received, swap_fee) =
+        calculate_out_given_in_with_fee(asset_reserve, other_reserve, swap_amount, fee)?;
+
+    let asset_reserve = asset_reserve.checked_add(swap_amount)?;
+    let other_reserve = other_reserve.checked_sub(amount_received)?;
+
+    // Shares are calculated from both assets, so that rounding never favours the LP
+    let shares_by_asset = calculate_shares(
+        asset_reserve,
+        amount.checked_sub(swap_amount)?,
+        share_issuance,
+    )?;
+    let shares_by_other = calculate_shares(other_reserve, amount_received, share_issuance)?;
+
+    Some((shares_by_asset.min(shares_by_other), swap_amount, swap_fee))
+}
+
+/// Calculating amount of a single asset returned when removing liquidity.
+/// Amount of the other asset is swapped internally to the returned asset.
+///
+/// - `asset_reserve` - reserve amount of returned asset
+/// - `other_reserve` - reserve amount of the other asset of the pool
+/// - `shares` - liquidity amount
+/// - `share_issuance` - total issuance of share asset
+/// - `withdraw_fee` - withdraw fee
+/// - `trade_fee` - trade fee
+///
+/// Returns amount of returned asset, swapped amount of the other asset and fee of the swap
+/// in returned asset, or None in case of error
+pub fn calculate_single_asset_liquidity_out(
+    asset_reserve: Balance,
+    other_reserve: Balance,
+    shares: Balance,
+    share_issuance: Balance,
+    withdraw_fee: Permill,
+    trade_fee: Permill,
+) -> Option<(Balance, Balance, Balance)> {
+    let ((amount, other_amount), _) = calculate_liquidity_out_with_fee(
+        asset_reserve,
+        other_reserve,
+        shares,
+        share_issuance,
+        withdraw_fee,
+    )?;
+
+    let (amount_received, swap_fee) = calculate_out_given_in_with_fee(
+        other_reserve.checked_sub(other_amount)?,
+        asset_reserve.checked_sub(amount)?,
+        other_amount,
+        trade_fee,
+    )?;
+
+    Some((amount.checked_add(amount_received)?, other_amount, swap_fee))
+}
+
+/// Calculating fees of a flash swap of `amount_out`, given reserves before and after it.
+/// Fee is taken from the amounts returned to the pool over the reserves after borrowing.
+///
+/// - `in_reserve` - reserve of asset in before the swap
+/// - `out_reserve` - reserve of borrowed asset before the swap
+/// - `in_reserve_after` - reserve of asset in after the repayment
+/// - `out_reserve_after` - reserve of borrowed asset after the repayment
+/// - `amount_out` - borrowed amount
+/// - `fee` - trade fee
+///
+/// Returns fees in asset in and in borrowed asset, or None in case of error
+pub fn calculate_flash_swap_fees(
+    in_reserve: Balance,
+    out_reserve: Balance,
+    in_reserve_after: Balance,
+    out_reserve_after: Balance,
+    amount_out: Balance,
+    fee: Permill,
+) -> Option<(Balance, Balance)> {
+    let out_reserve_borrowed = out_reserve.checked_sub(amount_out)?;
+
+    // Fee is rounded up to prevent value leaking from the pool
+    let amount_in = in_reserve_after.saturating_sub(in_reserve);
+    let repaid_out = out_reserve_after.saturating_sub(out_reserve_borrowed);
+
+    Some((fee.mul_ceil(amount_in), fee.mul_ceil(repaid_out)))
+}
+
+/// Checking whether a flash swap of `amount_out` is repaid, given reserves before and after it.
+/// Fee is taken from the amounts returned to the pool over the reserves after borrowing
+/// and the product of reserves without fee must not decrease.
+/// Formula : (IN_RESERVE_AFTER - FEE_IN) * (OUT_RESERVE_AFTER - FEE_OUT) >= IN_RESERVE * OUT_RESERVE
+///
+/// - `in_reserve` - reserve of asset in before the swap
+/// - `out_reserve` - reserve of borrowed asset before the swap
+/// - `in_reserve_after` - reserve of asset in after the repayment
+/// - `out_reserve_after` - reserve of borrowed asset after the repayment
+/// - `amount_out` - borrowed amount
+/// - `fee` - trade fee
+///
+/// Returns None in case of error
+pub fn is_flash_swap_repaid(
+    in_reserve: Balance,
+    out_reserve: Balance,
+    in_reserve_after: Balance,
+    out_reserve_after: Balance,
+    amount_out: Balance,
+    fee: Permill,
+) -> Option<bool> {
+    let (fee_in, fee_out) = calculate_flash_swap_fees(
+        in_reserve,
+        out_reserve,
+        in_reserve_after,
+        out_reserve_after,
+        amount_out,
+        fee,
+    )?;
+
+    let in_reserve_after = in_reserve_after.checked_sub(fee_in)?;
+    let out_reserve_after = out_reserve_after.checked_sub(fee_out)?;
+
+    let (in_reserve, out_reserve, in_reserve_after, out_reserve_after) =
+        to_u256!(in_reserve, out_reserve, in_reserve_after, out_reserve_after);
+
+    Some(in_reserve_after.checked_mul(out_reserve_after)? >= in_reserve.checked_mul(out_reserve)?)
+}
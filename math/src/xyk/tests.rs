@@ -1,4 +1,5 @@
 use crate::xyk::*;
+use sp_arithmetic::Permill;
 use std::vec;
 
 #[test]
@@ -84,6 +85,86 @@ fn in_given_out_should_work() {
     }
 }
 
+#[test]
+fn out_given_in_with_fee_should_work() {
+    let cases = vec![
+        (1000, 2000, 500, Permill::zero(), Some((666, 0)), "No fee"),
+        (
+            1000,
+            2000,
+            500,
+            Permill::from_percent(1),
+            Some((659, 7)),
+            "Fee is rounded up",
+        ),
+        (
+            1000,
+            2000,
+            500,
+            Permill::from_percent(100),
+            Some((0, 666)),
+            "Whole amount is fee",
+        ),
+        (
+            1000,
+            1000,
+            0,
+            Permill::from_percent(1),
+            Some((0, 0)),
+            "Zero amount in",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_out_given_in_with_fee(case.0, case.1, case.2, case.3),
+            case.4,
+            "{}",
+            case.5
+        );
+    }
+}
+
+#[test]
+fn in_given_out_with_fee_should_work() {
+    let cases = vec![
+        (2000, 1000, 500, Permill::zero(), Some((334, 0)), "No fee"),
+        (
+            2000,
+            1000,
+            500,
+            Permill::from_percent(1),
+            Some((338, 4)),
+            "Fee is rounded up",
+        ),
+        (
+            1000,
+            1000,
+            0,
+            Permill::from_percent(1),
+            Some((0, 0)),
+            "Zero amount out",
+        ),
+        (
+            0,
+            10,
+            1000,
+            Permill::from_percent(1),
+            None,
+            "amount cannot be > buy reserve",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_in_given_out_with_fee(case.0, case.1, case.2, case.3),
+            case.4,
+            "{}",
+            case.5
+        );
+    }
+}
+
 #[test]
 fn add_liquidity_should_work() {
     let cases = vec![
@@ -125,6 +206,84 @@ fn remove_liquidity_should_work() {
     }
 }
 
+#[test]
+fn remove_liquidity_with_fee_should_work() {
+    let cases = vec![
+        (
+            1000,
+            2000,
+            500,
+            2500,
+            Permill::zero(),
+            Some(((200, 400), (0, 0))),
+            "No fee",
+        ),
+        (
+            1000,
+            2000,
+            500,
+            2500,
+            Permill::from_percent(1),
+            Some(((198, 396), (2, 4))),
+            "Easy case",
+        ),
+        (
+            1000,
+            2000,
+            50,
+            2500,
+            Permill::from_percent(3),
+            Some(((19, 38), (1, 2))),
+            "Fee is rounded up",
+        ),
+        (
+            100,
+            100,
+            100,
+            0,
+            Permill::from_percent(1),
+            None,
+            "total liquidity is zero",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_liquidity_out_with_fee(case.0, case.1, case.2, case.3, case.4),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn calculate_initial_shares_should_work() {
+    let one: Balance = 1_000_000_000_000;
+
+    let cases = vec![
+        (100 * one, 100 * one, Some(100 * one), "Same amounts"),
+        (20 * one, 10 * one, Some(14_142_135_623_730), "Rounded down"),
+        (
+            10 * one,
+            20 * one,
+            Some(14_142_135_623_730),
+            "Order of assets",
+        ),
+        (0, 100 * one, Some(0), "Zero amount"),
+        (u128::MAX, u128::MAX, Some(u128::MAX), "Max amounts"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_initial_shares(case.0, case.1),
+            case.2,
+            "{}",
+            case.3
+        );
+    }
+}
+
 #[test]
 fn calculate_shares_should_work() {
     let one: Balance = 1_000_000_000_000;
@@ -162,3 +321,286 @@ fn calculate_shares_should_work() {
         );
     }
 }
+
+#[test]
+fn single_asset_swap_amount_should_work() {
+    let cases = vec![
+        (1000, 100, Permill::zero(), Some(48), "Easy case"),
+        (
+            1000,
+            100,
+            Permill::from_parts(3_000),
+            Some(48),
+            "Easy case with fee",
+        ),
+        (
+            100,
+            1000,
+            Permill::from_percent(50),
+            Some(536),
+            "Negative linear coefficient",
+        ),
+        (
+            100,
+            1_000_000,
+            Permill::from_percent(100),
+            Some(1_000_000),
+            "Whole amount is swapped when fee is 100%",
+        ),
+        (1000, 0, Permill::zero(), Some(0), "Zero amount"),
+        (0, 100, Permill::zero(), None, "Zero reserve"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_single_asset_swap_amount(case.0, case.1, case.2),
+            case.3,
+            "{}",
+            case.4
+        );
+    }
+}
+
+#[test]
+fn shares_for_single_asset_should_work() {
+    let one: Balance = 1_000_000_000_000;
+
+    let cases = vec![
+        (
+            1000,
+            2000,
+            100,
+            1000,
+            Permill::zero(),
+            Some(47),
+            "Easy case",
+        ),
+        (
+            20 * one,
+            10 * one,
+            5 * one,
+            14_142_135_623_730,
+            Permill::zero(),
+            Some(1_669_252_677_109),
+            "No fee",
+        ),
+        (
+            20 * one,
+            10 * one,
+            5 * one,
+            14_142_135_623_730,
+            Permill::from_parts(3_000),
+            Some(1_666_449_298_764),
+            "Fee",
+        ),
+        (0, 10, 100, 100, Permill::zero(), None, "Zero reserve"),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_shares_for_single_asset(case.0, case.1, case.2, case.3, case.4)
+                .map(|(shares, _, _)| shares),
+            case.5,
+            "{}",
+            case.6
+        );
+    }
+}
+
+#[test]
+fn single_asset_liquidity_out_should_work() {
+    let one: Balance = 1_000_000_000_000;
+
+    let cases = vec![
+        (
+            1000,
+            2000,
+            100,
+            1000,
+            Permill::zero(),
+            Permill::zero(),
+            Some(190),
+            "Easy case",
+        ),
+        (
+            1000,
+            2000,
+            100,
+            1000,
+            Permill::from_percent(1),
+            Permill::from_parts(3_000),
+            Some(187),
+            "Withdraw and trade fee",
+        ),
+        (
+            20 * one,
+            10 * one,
+            one,
+            14_142_135_623_730,
+            Permill::zero(),
+            Permill::zero(),
+            Some(2_728_427_124_745),
+            "No fee",
+        ),
+        (
+            100,
+            100,
+            100,
+            0,
+            Permill::zero(),
+            Permill::zero(),
+            None,
+            "total liquidity is zero",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_single_asset_liquidity_out(case.0, case.1, case.2, case.3, case.4, case.5)
+                .map(|(amount, _, _)| amount),
+            case.6,
+            "{}",
+            case.7
+        );
+    }
+}
+
+#[test]
+fn is_flash_swap_repaid_should_work() {
+    let cases = vec![
+        (
+            1000,
+            1000,
+            1000,
+            1000,
+            100,
+            Permill::zero(),
+            Some(true),
+            "Repaid in asset out",
+        ),
+        (
+            1000,
+            1000,
+            1000,
+            900,
+            100,
+            Permill::zero(),
+            Some(false),
+            "Not repaid",
+        ),
+        (
+            1000,
+            1000,
+            1112,
+            900,
+            100,
+            Permill::zero(),
+            Some(true),
+            "Repaid in asset in",
+        ),
+        (
+            1000,
+            1000,
+            1111,
+            900,
+            100,
+            Permill::zero(),
+            Some(false),
+            "Insufficient repayment in asset in",
+        ),
+        (
+            1000,
+            1000,
+            1000,
+            1000,
+            100,
+            Permill::from_percent(1),
+            Some(false),
+            "Fee not repaid",
+        ),
+        (
+            1000,
+            1000,
+            1000,
+            1002,
+            100,
+            Permill::from_percent(1),
+            Some(true),
+            "Repaid with fee in asset out",
+        ),
+        (
+            1000,
+            1000,
+            1124,
+            900,
+            100,
+            Permill::from_percent(1),
+            Some(true),
+            "Repaid with fee in asset in",
+        ),
+        (
+            1000,
+            1000,
+            1000,
+            1000,
+            1001,
+            Permill::zero(),
+            None,
+            "Borrowed more than reserve",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            is_flash_swap_repaid(case.0, case.1, case.2, case.3, case.4, case.5),
+            case.6,
+            "{}",
+            case.7
+        );
+    }
+}
+
+#[test]
+fn flash_swap_fees_should_work() {
+    let cases = vec![
+        (
+            1000,
+            1000,
+            1000,
+            1002,
+            100,
+            Permill::from_percent(1),
+            Some((0, 2)),
+            "Repaid in asset out",
+        ),
+        (
+            1000,
+            1000,
+            1124,
+            900,
+            100,
+            Permill::from_percent(1),
+            Some((2, 0)),
+            "Repaid in asset in",
+        ),
+        (
+            1000,
+            1000,
+            1000,
+            1000,
+            1001,
+            Permill::from_percent(1),
+            None,
+            "Borrowed more than reserve",
+        ),
+    ];
+
+    for case in cases {
+        assert_eq!(
+            calculate_flash_swap_fees(case.0, case.1, case.2, case.3, case.4, case.5),
+            case.6,
+            "{}",
+            case.7
+        );
+    }
+}
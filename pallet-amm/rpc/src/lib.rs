@@ -0,0 +1,377 @@
+//! RPC interface for the AMM pallet.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    proc_macros::rpc,
+    types::error::{CallError, ErrorObject},
+};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT, FixedU128};
+
+pub use pallet_amm_runtime_api::AmmApi as AmmRuntimeApi;
+
+/// Trade quote.
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Quote<Balance> {
+    /// Amount received when selling, amount to be paid including fee when buying.
+    pub amount: Balance,
+    /// Trade fee.
+    pub fee: Balance,
+}
+
+impl<Balance> From<(Balance, Balance)> for Quote<Balance> {
+    fn from((amount, fee): (Balance, Balance)) -> Self {
+        Self { amount, fee }
+    }
+}
+
+/// Trade route with the amount received when selling or the amount to be paid when buying.
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Route<AssetId, Balance> {
+    /// Assets of the route, from the asset sold to the asset bought.
+    pub route: Vec<AssetId>,
+    /// Amount received when selling, amount to be paid including fees when buying.
+    pub amount: Balance,
+}
+
+impl<AssetId, Balance> From<(Vec<AssetId>, Balance)> for Route<AssetId, Balance> {
+    fn from((route, amount): (Vec<AssetId>, Balance)) -> Self {
+        Self { route, amount }
+    }
+}
+
+/// Price and liquidity averaged by the oracle.
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OraclePrice<Balance> {
+    /// Price of the first asset denominated in the second asset.
+    pub price: FixedU128,
+    /// Liquidity of the pool, as square root of the product of reserves.
+    pub liquidity: Balance,
+}
+
+impl<Balance> From<(FixedU128, Balance)> for OraclePrice<Balance> {
+    fn from((price, liquidity): (FixedU128, Balance)) -> Self {
+        Self { price, liquidity }
+    }
+}
+
+#[rpc(client, server)]
+pub trait AmmApi<BlockHash, AssetId, Balance, PoolId> {
+    #[method(name = "amm_quoteSell")]
+    fn quote_sell(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<Quote<Balance>>>;
+
+    #[method(name = "amm_quoteBuy")]
+    fn quote_buy(
+        &self,
+        asset_out: AssetId,
+        asset_in: AssetId,
+        amount_out: Balance,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<Quote<Balance>>>;
+
+    #[method(name = "amm_poolReserves")]
+    fn pool_reserves(
+        &self,
+        asset_a: AssetId,
+        asset_b: AssetId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<(Balance, Balance)>>;
+
+    #[method(name = "amm_quoteSellByPoolId")]
+    fn quote_sell_by_pool_id(
+        &self,
+        pool_id: PoolId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<Quote<Balance>>>;
+
+    #[method(name = "amm_quoteBuyByPoolId")]
+    fn quote_buy_by_pool_id(
+        &self,
+        pool_id: PoolId,
+        asset_out: AssetId,
+        asset_in: AssetId,
+        amount_out: Balance,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<Quote<Balance>>>;
+
+    #[method(name = "amm_poolReservesByPoolId")]
+    fn pool_reserves_by_pool_id(
+        &self,
+        pool_id: PoolId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<Vec<(AssetId, Balance)>>>;
+
+    #[method(name = "amm_shareAsset")]
+    fn share_asset(
+        &self,
+        asset_a: AssetId,
+        asset_b: AssetId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<AssetId>>;
+
+    #[method(name = "amm_spotPrice")]
+    fn spot_price(
+        &self,
+        asset_a: AssetId,
+        asset_b: AssetId,
+        amount: Balance,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<Balance>>;
+
+    #[method(name = "amm_bestSellRoute")]
+    fn best_sell_route(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        max_hops: u32,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<Route<AssetId, Balance>>>;
+
+    #[method(name = "amm_bestBuyRoute")]
+    fn best_buy_route(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: Balance,
+        max_hops: u32,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<Route<AssetId, Balance>>>;
+
+    #[method(name = "amm_oraclePrice")]
+    fn oracle_price(
+        &self,
+        asset_a: AssetId,
+        asset_b: AssetId,
+        window: u32,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<OraclePrice<Balance>>>;
+}
+
+/// Provides RPC methods to query AMM pools and trade quotes.
+pub struct Amm<C, Block> {
+    client: Arc<C>,
+    _marker: PhantomData<Block>,
+}
+
+impl<C, Block> Amm<C, Block> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+    /// The call to runtime failed.
+    RuntimeError,
+}
+
+impl From<Error> for i32 {
+    fn from(e: Error) -> i32 {
+        match e {
+            Error::RuntimeError => 1,
+        }
+    }
+}
+
+fn runtime_error(message: &'static str, e: impl std::fmt::Debug) -> jsonrpsee::core::Error {
+    CallError::Custom(ErrorObject::owned(
+        Error::RuntimeError.into(),
+        message,
+        Some(format!("{:?}", e)),
+    ))
+    .into()
+}
+
+#[async_trait]
+impl<C, Block, AssetId, Balance, PoolId>
+    AmmApiServer<<Block as BlockT>::Hash, AssetId, Balance, PoolId> for Amm<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: AmmRuntimeApi<Block, AssetId, Balance, PoolId>,
+    AssetId: Codec + Send + Sync + 'static,
+    Balance: Codec + Send + Sync + 'static,
+    PoolId: Codec + Send + Sync + 'static,
+{
+    fn quote_sell(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<Quote<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.quote_sell(&at, asset_in, asset_out, amount_in)
+            .map(|quote| quote.map(Into::into))
+            .map_err(|e| runtime_error("Unable to quote sell.", e))
+    }
+
+    fn quote_buy(
+        &self,
+        asset_out: AssetId,
+        asset_in: AssetId,
+        amount_out: Balance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<Quote<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.quote_buy(&at, asset_out, asset_in, amount_out)
+            .map(|quote| quote.map(Into::into))
+            .map_err(|e| runtime_error("Unable to quote buy.", e))
+    }
+
+    fn pool_reserves(
+        &self,
+        asset_a: AssetId,
+        asset_b: AssetId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<(Balance, Balance)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.pool_reserves(&at, asset_a, asset_b)
+            .map_err(|e| runtime_error("Unable to retrieve pool reserves.", e))
+    }
+
+    fn quote_sell_by_pool_id(
+        &self,
+        pool_id: PoolId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<Quote<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.quote_sell_by_pool_id(&at, pool_id, asset_in, asset_out, amount_in)
+            .map(|quote| quote.map(Into::into))
+            .map_err(|e| runtime_error("Unable to quote sell.", e))
+    }
+
+    fn quote_buy_by_pool_id(
+        &self,
+        pool_id: PoolId,
+        asset_out: AssetId,
+        asset_in: AssetId,
+        amount_out: Balance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<Quote<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.quote_buy_by_pool_id(&at, pool_id, asset_out, asset_in, amount_out)
+            .map(|quote| quote.map(Into::into))
+            .map_err(|e| runtime_error("Unable to quote buy.", e))
+    }
+
+    fn pool_reserves_by_pool_id(
+        &self,
+        pool_id: PoolId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<Vec<(AssetId, Balance)>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.pool_reserves_by_pool_id(&at, pool_id)
+            .map_err(|e| runtime_error("Unable to retrieve pool reserves.", e))
+    }
+
+    fn share_asset(
+        &self,
+        asset_a: AssetId,
+        asset_b: AssetId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<AssetId>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.share_asset(&at, asset_a, asset_b)
+            .map_err(|e| runtime_error("Unable to retrieve share asset.", e))
+    }
+
+    fn spot_price(
+        &self,
+        asset_a: AssetId,
+        asset_b: AssetId,
+        amount: Balance,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<Balance>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.spot_price(&at, asset_a, asset_b, amount)
+            .map_err(|e| runtime_error("Unable to retrieve spot price.", e))
+    }
+
+    fn best_sell_route(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        max_hops: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<Route<AssetId, Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.best_sell_route(&at, asset_in, asset_out, amount_in, max_hops)
+            .map(|route| route.map(Into::into))
+            .map_err(|e| runtime_error("Unable to find sell route.", e))
+    }
+
+    fn best_buy_route(
+        &self,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: Balance,
+        max_hops: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<Route<AssetId, Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.best_buy_route(&at, asset_in, asset_out, amount_out, max_hops)
+            .map(|route| route.map(Into::into))
+            .map_err(|e| runtime_error("Unable to find buy route.", e))
+    }
+
+    fn oracle_price(
+        &self,
+        asset_a: AssetId,
+        asset_b: AssetId,
+        window: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<OraclePrice<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.oracle_price(&at, asset_a, asset_b, window)
+            .map(|price| price.map(Into::into))
+            .map_err(|e| runtime_error("Unable to retrieve oracle price.", e))
+    }
+}
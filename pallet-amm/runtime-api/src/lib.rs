@@ -0,0 +1,51 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_arithmetic::FixedU128;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// The API to query AMM pools and trade quotes.
+    pub trait AmmApi<AssetId, Balance, PoolId> where
+        AssetId: Codec,
+        Balance: Codec,
+        PoolId: Codec,
+    {
+        /// Returns amount received and fee paid when selling `amount_in` of `asset_in` for `asset_out`.
+        fn quote_sell(asset_in: AssetId, asset_out: AssetId, amount_in: Balance) -> Option<(Balance, Balance)>;
+
+        /// Returns amount to be paid, including fee, and fee paid when buying `amount_out` of `asset_out` for `asset_in`.
+        fn quote_buy(asset_out: AssetId, asset_in: AssetId, amount_out: Balance) -> Option<(Balance, Balance)>;
+
+        /// Returns reserves of the pool of given pair, in the order of given assets.
+        fn pool_reserves(asset_a: AssetId, asset_b: AssetId) -> Option<(Balance, Balance)>;
+
+        /// Returns amount received and fee paid when selling `amount_in` of `asset_in` for `asset_out` in pool `pool_id`.
+        fn quote_sell_by_pool_id(pool_id: PoolId, asset_in: AssetId, asset_out: AssetId, amount_in: Balance) -> Option<(Balance, Balance)>;
+
+        /// Returns amount to be paid, including fee, and fee paid when buying `amount_out` of `asset_out` for `asset_in` in pool `pool_id`.
+        fn quote_buy_by_pool_id(pool_id: PoolId, asset_out: AssetId, asset_in: AssetId, amount_out: Balance) -> Option<(Balance, Balance)>;
+
+        /// Returns assets of pool `pool_id` and their reserves.
+        fn pool_reserves_by_pool_id(pool_id: PoolId) -> Option<Vec<(AssetId, Balance)>>;
+
+        /// Returns share asset of the pool of given pair.
+        fn share_asset(asset_a: AssetId, asset_b: AssetId) -> Option<AssetId>;
+
+        /// Returns price of `amount` of `asset_a` denominated in `asset_b`.
+        fn spot_price(asset_a: AssetId, asset_b: AssetId, amount: Balance) -> Option<Balance>;
+
+        /// Returns the route selling `amount_in` of `asset_in` which receives the most of `asset_out`,
+        /// trading through at most `max_hops` pools, and the amount received.
+        fn best_sell_route(asset_in: AssetId, asset_out: AssetId, amount_in: Balance, max_hops: u32) -> Option<(Vec<AssetId>, Balance)>;
+
+        /// Returns the route buying `amount_out` of `asset_out` which costs the least of `asset_in`,
+        /// trading through at most `max_hops` pools, and the amount to be paid.
+        fn best_buy_route(asset_in: AssetId, asset_out: AssetId, amount_out: Balance, max_hops: u32) -> Option<(Vec<AssetId>, Balance)>;
+
+        /// Returns price of `asset_a` denominated in `asset_b` and liquidity of their pool,
+        /// both averaged by the oracle over the last `window` blocks.
+        fn oracle_price(asset_a: AssetId, asset_b: AssetId, window: u32) -> Option<(FixedU128, Balance)>;
+    }
+}
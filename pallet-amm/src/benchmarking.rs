@@ -0,0 +1,562 @@
+use super::*;
+
+use crate::types::{PoolType, MAX_WEIGHT};
+use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::tokens::fungibles::{Inspect, Mutate};
+use frame_support::traits::EnsureOrigin;
+use frame_support::BoundedVec;
+use frame_system::RawOrigin;
+use math::concentrated::Tick;
+use sp_runtime::{FixedPointNumber, FixedU128};
+use sp_std::vec::Vec;
+
+const SEED: u32 = 1;
+
+const ONE: Balance = 1_000_000_000_000;
+
+const ASSET_ID_OFFSET: AssetId = 1_000;
+
+fn funded_account<T: Config>(name: &'static str, index: u32, assets: &[AssetId]) -> T::AccountId {
+    let who: T::AccountId = account(name, index, SEED);
+
+    for asset in assets {
+        T::Currency::mint_into(*asset, &who, 1_000_000 * ONE).unwrap();
+    }
+
+    who
+}
+
+/// Returns origin allowed to create pools and its account, funded with given assets
+/// and creation fees of `pools` pools.
+fn pool_creator<T: Config>(assets: &[AssetId], pools: u32) -> (T::Origin, T::AccountId) {
+    let origin = T::CreatePoolOrigin::successful_origin();
+    let who = T::CreatePoolOrigin::ensure_origin(origin.clone()).unwrap();
+
+    for asset in assets {
+        T::Currency::mint_into(*asset, &who, 1_000_000 * ONE).unwrap();
+    }
+
+    T::Currency::mint_into(
+        T::CreationFeeAsset::get(),
+        &who,
+        T::CreationFee::get()
+            .saturating_mul(pools.into())
+            .saturating_add(1_000_000 * ONE),
+    )
+    .unwrap();
+
+    (origin, who)
+}
+
+/// Creates concentrated liquidity pool of two assets at price of one and returns its id.
+fn create_concentrated_pool<T: Config>() -> PoolId {
+    let (origin, _) = pool_creator::<T>(&[], 1);
+    let pool_id = NextPoolId::<T>::get();
+
+    Pallet::<T>::create_concentrated_pool(
+        origin,
+        ASSET_ID_OFFSET,
+        ASSET_ID_OFFSET + 1,
+        10,
+        1 << 64,
+        T::MaxTradeFee::get(),
+    )
+    .unwrap();
+
+    pool_id
+}
+
+/// Mints a position around the price of a pool created by `create_concentrated_pool`.
+fn mint_position<T: Config>(who: &T::AccountId, pool_id: PoolId) -> PositionId {
+    let position_id = NextPositionId::<T>::get();
+
+    Pallet::<T>::mint_position(
+        RawOrigin::Signed(who.clone()).into(),
+        pool_id,
+        -1_000,
+        1_000,
+        1_000 * ONE,
+        1_000 * ONE,
+        0,
+        None,
+    )
+    .unwrap();
+
+    position_id
+}
+
+/// Creates pools of consecutive assets of given route, each with the same liquidity.
+fn create_route_pools<T: Config>(route: &[AssetId]) {
+    let (origin, _) = pool_creator::<T>(route, route.len() as u32);
+
+    for hop in route.windows(2) {
+        Pallet::<T>::create_pool(
+            origin.clone(),
+            hop[0],
+            1_000 * ONE,
+            hop[1],
+            1_000 * ONE,
+            T::MaxTradeFee::get(),
+        )
+        .unwrap();
+    }
+}
+
+fn route<T: Config>(n: u32) -> BoundedVec<AssetId, T::MaxRouteLength> {
+    (0..n)
+        .map(|idx| ASSET_ID_OFFSET + idx)
+        .collect::<Vec<AssetId>>()
+        .try_into()
+        .unwrap()
+}
+
+/// Returns `n` assets of a multi-asset pool, each with the same amount.
+fn multi_assets<T: Config>(
+    n: u32,
+    amount: Balance,
+) -> BoundedVec<(AssetId, Balance), T::MaxAssetsInPool> {
+    (0..n)
+        .map(|idx| (ASSET_ID_OFFSET + idx, amount))
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap()
+}
+
+/// Creates a multi-asset pool of `n` assets and returns its id.
+fn create_multi_asset_pool<T: Config>(n: u32) -> PoolId {
+    let assets = multi_assets::<T>(n, 1_000 * ONE);
+    let (origin, _) = pool_creator::<T>(
+        &assets.iter().map(|(asset, _)| *asset).collect::<Vec<_>>(),
+        1,
+    );
+
+    let pool_id = NextPoolId::<T>::get();
+    Pallet::<T>::create_multi_asset_pool(origin, assets, 100).unwrap();
+
+    pool_id
+}
+
+benchmarks! {
+    create_pool {
+        let asset_a = ASSET_ID_OFFSET;
+        let asset_b = ASSET_ID_OFFSET + 1;
+        let (origin, _) = pool_creator::<T>(&[asset_a, asset_b], 1);
+    }: _(origin, asset_a, 1_000 * ONE, asset_b, 1_000 * ONE, T::MaxTradeFee::get())
+    verify {
+        assert!(Pallet::<T>::share_asset(asset_a, asset_b).is_some());
+    }
+
+    create_lbp_pool {
+        let asset_a = ASSET_ID_OFFSET;
+        let asset_b = ASSET_ID_OFFSET + 1;
+        let (origin, _) = pool_creator::<T>(&[asset_a, asset_b], 1);
+    }: _(origin, asset_a, 1_000 * ONE, asset_b, 1_000 * ONE, 10u32.into(), 20u32.into(), MAX_WEIGHT / 5, MAX_WEIGHT / 2)
+    verify {
+        assert!(Pallet::<T>::share_asset(asset_a, asset_b).is_some());
+    }
+
+    create_stable_pool {
+        let asset_a = ASSET_ID_OFFSET;
+        let asset_b = ASSET_ID_OFFSET + 1;
+        let (origin, _) = pool_creator::<T>(&[asset_a, asset_b], 1);
+    }: _(origin, asset_a, 1_000 * ONE, asset_b, 1_000 * ONE, 100)
+    verify {
+        assert!(Pallet::<T>::share_asset(asset_a, asset_b).is_some());
+    }
+
+    update_amplification {
+        let asset_a = ASSET_ID_OFFSET;
+        let asset_b = ASSET_ID_OFFSET + 1;
+        let (origin, _) = pool_creator::<T>(&[asset_a, asset_b], 1);
+        Pallet::<T>::create_stable_pool(origin, asset_a, 1_000 * ONE, asset_b, 1_000 * ONE, 100)?;
+        let pool_id = Pallet::<T>::pair_pool_id((asset_a, asset_b)).unwrap();
+        let origin = T::AuthorityOrigin::successful_origin();
+    }: _(origin, pool_id, 200, 10u32.into(), 20u32.into())
+    verify {
+        let pool = Pallet::<T>::pools(pool_id).unwrap();
+        assert!(matches!(pool.pool_type, PoolType::Stable(params) if params.final_amplification == 200));
+    }
+
+    update_pool_fee {
+        let asset_a = ASSET_ID_OFFSET;
+        let asset_b = ASSET_ID_OFFSET + 1;
+        create_route_pools::<T>(&[asset_a, asset_b]);
+
+        let origin = T::AuthorityOrigin::successful_origin();
+        let pool_id = Pallet::<T>::pair_pool_id((asset_a, asset_b)).unwrap();
+    }: _(origin, pool_id, T::MinTradeFee::get())
+    verify {
+        assert_eq!(Pallet::<T>::pools(pool_id).unwrap().fee, T::MinTradeFee::get());
+    }
+
+    add_liquidity {
+        let asset_a = ASSET_ID_OFFSET;
+        let asset_b = ASSET_ID_OFFSET + 1;
+        create_route_pools::<T>(&[asset_a, asset_b]);
+
+        let caller = funded_account::<T>("caller", 0, &[asset_a, asset_b]);
+    }: _(RawOrigin::Signed(caller.clone()), asset_a, asset_b, 10 * ONE, Balance::MAX, 0, None)
+    verify {
+        let share_asset = Pallet::<T>::share_asset(asset_a, asset_b).unwrap();
+        assert!(T::Currency::balance(share_asset, &caller) > 0);
+    }
+
+    remove_liquidity {
+        let asset_a = ASSET_ID_OFFSET;
+        let asset_b = ASSET_ID_OFFSET + 1;
+        create_route_pools::<T>(&[asset_a, asset_b]);
+
+        let caller = funded_account::<T>("caller", 0, &[asset_a, asset_b]);
+        Pallet::<T>::add_liquidity(
+            RawOrigin::Signed(caller.clone()).into(),
+            asset_a,
+            asset_b,
+            10 * ONE,
+            Balance::MAX,
+            0,
+            None,
+        )?;
+
+        let share_asset = Pallet::<T>::share_asset(asset_a, asset_b).unwrap();
+        let shares = T::Currency::balance(share_asset, &caller);
+    }: _(RawOrigin::Signed(caller.clone()), asset_a, asset_b, shares, 0, 0, None)
+    verify {
+        assert_eq!(T::Currency::balance(share_asset, &caller), 0);
+    }
+
+    add_liquidity_single_asset {
+        let asset_a = ASSET_ID_OFFSET;
+        let asset_b = ASSET_ID_OFFSET + 1;
+        create_route_pools::<T>(&[asset_a, asset_b]);
+
+        let caller = funded_account::<T>("caller", 0, &[asset_a]);
+    }: _(RawOrigin::Signed(caller.clone()), asset_a, asset_b, 10 * ONE, 0, None)
+    verify {
+        let share_asset = Pallet::<T>::share_asset(asset_a, asset_b).unwrap();
+        assert!(T::Currency::balance(share_asset, &caller) > 0);
+    }
+
+    remove_liquidity_single_asset {
+        let asset_a = ASSET_ID_OFFSET;
+        let asset_b = ASSET_ID_OFFSET + 1;
+        create_route_pools::<T>(&[asset_a, asset_b]);
+
+        let caller = funded_account::<T>("caller", 0, &[asset_a, asset_b]);
+        Pallet::<T>::add_liquidity(
+            RawOrigin::Signed(caller.clone()).into(),
+            asset_a,
+            asset_b,
+            10 * ONE,
+            Balance::MAX,
+            0,
+            None,
+        )?;
+
+        let share_asset = Pallet::<T>::share_asset(asset_a, asset_b).unwrap();
+        let shares = T::Currency::balance(share_asset, &caller);
+    }: _(RawOrigin::Signed(caller.clone()), asset_a, asset_b, shares, 0, None)
+    verify {
+        assert_eq!(T::Currency::balance(share_asset, &caller), 0);
+    }
+
+    create_multi_asset_pool {
+        let n in 3 .. T::MaxAssetsInPool::get();
+
+        let assets = multi_assets::<T>(n, 1_000 * ONE);
+        let (origin, _) = pool_creator::<T>(&assets.iter().map(|(asset, _)| *asset).collect::<Vec<_>>(), 1);
+        let pool_id = NextPoolId::<T>::get();
+    }: _(origin, assets, 100)
+    verify {
+        assert!(Pallet::<T>::pools(pool_id).is_some());
+    }
+
+    add_liquidity_multi_asset {
+        let n in 3 .. T::MaxAssetsInPool::get();
+
+        let pool_id = create_multi_asset_pool::<T>(n);
+        let amounts = multi_assets::<T>(n, 10 * ONE);
+
+        let caller = funded_account::<T>("caller", 0, &amounts.iter().map(|(asset, _)| *asset).collect::<Vec<_>>());
+        let share_asset = Pallet::<T>::pools(pool_id).unwrap().share_asset.unwrap();
+    }: _(RawOrigin::Signed(caller.clone()), pool_id, amounts, 0, None)
+    verify {
+        assert!(T::Currency::balance(share_asset, &caller) > 0);
+    }
+
+    remove_liquidity_multi_asset {
+        let n in 3 .. T::MaxAssetsInPool::get();
+
+        let pool_id = create_multi_asset_pool::<T>(n);
+        let amounts = multi_assets::<T>(n, 10 * ONE);
+
+        let caller = funded_account::<T>("caller", 0, &amounts.iter().map(|(asset, _)| *asset).collect::<Vec<_>>());
+        Pallet::<T>::add_liquidity_multi_asset(RawOrigin::Signed(caller.clone()).into(), pool_id, amounts, 0, None)?;
+
+        let share_asset = Pallet::<T>::pools(pool_id).unwrap().share_asset.unwrap();
+        let shares = T::Currency::balance(share_asset, &caller);
+    }: _(RawOrigin::Signed(caller.clone()), pool_id, shares, BoundedVec::default(), None)
+    verify {
+        assert_eq!(T::Currency::balance(share_asset, &caller), 0);
+    }
+
+    sell_multi_asset {
+        let n in 3 .. T::MaxAssetsInPool::get();
+
+        let pool_id = create_multi_asset_pool::<T>(n);
+        let asset_in = ASSET_ID_OFFSET;
+        let asset_out = ASSET_ID_OFFSET + n - 1;
+
+        let caller = funded_account::<T>("caller", 0, &[asset_in]);
+    }: _(RawOrigin::Signed(caller.clone()), pool_id, asset_in, asset_out, 10 * ONE, 0, None)
+    verify {
+        assert!(T::Currency::balance(asset_out, &caller) > 0);
+    }
+
+    buy_multi_asset {
+        let n in 3 .. T::MaxAssetsInPool::get();
+
+        let pool_id = create_multi_asset_pool::<T>(n);
+        let asset_in = ASSET_ID_OFFSET;
+        let asset_out = ASSET_ID_OFFSET + n - 1;
+
+        let caller = funded_account::<T>("caller", 0, &[asset_in]);
+    }: _(RawOrigin::Signed(caller.clone()), pool_id, asset_out, asset_in, 10 * ONE, Balance::MAX, None)
+    verify {
+        assert_eq!(T::Currency::balance(asset_out, &caller), 10 * ONE);
+    }
+
+    create_concentrated_pool {
+        let (origin, _) = pool_creator::<T>(&[], 1);
+        let pool_id = NextPoolId::<T>::get();
+    }: _(origin, ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1, 10, 1 << 64, T::MaxTradeFee::get())
+    verify {
+        assert!(Pallet::<T>::concentrated_pool(pool_id).is_some());
+    }
+
+    mint_position {
+        let pool_id = create_concentrated_pool::<T>();
+
+        let caller = funded_account::<T>("caller", 0, &[ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1]);
+        let position_id = NextPositionId::<T>::get();
+    }: _(RawOrigin::Signed(caller.clone()), pool_id, -1_000, 1_000, 1_000 * ONE, 1_000 * ONE, 0, None)
+    verify {
+        assert!(Pallet::<T>::position(position_id).is_some());
+    }
+
+    burn_position {
+        let pool_id = create_concentrated_pool::<T>();
+
+        let caller = funded_account::<T>("caller", 0, &[ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1]);
+        let position_id = mint_position::<T>(&caller, pool_id);
+        let liquidity = Pallet::<T>::position(position_id).unwrap().liquidity;
+
+        // Fees earned by the position are collected as well.
+        Pallet::<T>::sell(RawOrigin::Signed(caller.clone()).into(), ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1, 10 * ONE, 0, None)?;
+    }: _(RawOrigin::Signed(caller.clone()), position_id, liquidity, 0, 0, None)
+    verify {
+        assert!(Pallet::<T>::position(position_id).is_none());
+    }
+
+    collect_fees {
+        let pool_id = create_concentrated_pool::<T>();
+
+        let caller = funded_account::<T>("caller", 0, &[ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1]);
+        let position_id = mint_position::<T>(&caller, pool_id);
+
+        Pallet::<T>::sell(RawOrigin::Signed(caller.clone()).into(), ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1, 10 * ONE, 0, None)?;
+    }: _(RawOrigin::Signed(caller.clone()), position_id)
+    verify {
+        assert_eq!(Pallet::<T>::position(position_id).map(|position| position.fees_owed_a), Some(0));
+    }
+
+    swap_ticks {
+        let t in 2 .. T::MaxTicksPerPool::get();
+
+        let pool_id = create_concentrated_pool::<T>();
+        let asset_in = ASSET_ID_OFFSET;
+        let asset_out = ASSET_ID_OFFSET + 1;
+
+        let caller = funded_account::<T>("caller", 0, &[asset_in, asset_out]);
+        Pallet::<T>::mint_position(RawOrigin::Signed(caller.clone()).into(), pool_id, -20_000, 20_000, 1_000 * ONE, 1_000 * ONE, 0, None)?;
+
+        // Lower ticks of narrow positions around the price are crossed by the swap.
+        let positions = (t - 2) / 2;
+        for idx in 0..positions {
+            let width = 10 * (idx as Tick + 1);
+            Pallet::<T>::mint_position(RawOrigin::Signed(caller.clone()).into(), pool_id, -width, width, ONE, ONE, 0, None)?;
+        }
+
+        let fee = Pallet::<T>::pools(pool_id).unwrap().fee;
+    }: {
+        let (swap, _) = Pallet::<T>::calculate_concentrated_swap(pool_id, asset_in, asset_out, 1_000 * ONE, fee, true)?;
+        Pallet::<T>::apply_concentrated_swap(pool_id, asset_in, asset_out, &swap)?;
+    }
+    verify {
+        assert!(Pallet::<T>::concentrated_pool(pool_id).unwrap().tick < -10 * positions as Tick);
+    }
+
+    place_limit_order {
+        let pool_id = NextPoolId::<T>::get();
+        create_route_pools::<T>(&[ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1]);
+
+        let caller = funded_account::<T>("caller", 0, &[ASSET_ID_OFFSET]);
+        let order_id = NextOrderId::<T>::get();
+    }: _(RawOrigin::Signed(caller.clone()), ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1, 10 * ONE, FixedU128::from(2), None)
+    verify {
+        assert!(Pallet::<T>::limit_order(pool_id, order_id).is_some());
+    }
+
+    cancel_limit_order {
+        let pool_id = NextPoolId::<T>::get();
+        create_route_pools::<T>(&[ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1]);
+
+        let caller = funded_account::<T>("caller", 0, &[ASSET_ID_OFFSET]);
+        let order_id = NextOrderId::<T>::get();
+
+        Pallet::<T>::place_limit_order(RawOrigin::Signed(caller.clone()).into(), ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1, 10 * ONE, FixedU128::from(2), None)?;
+    }: _(RawOrigin::Signed(caller.clone()), pool_id, order_id)
+    verify {
+        assert!(Pallet::<T>::limit_order(pool_id, order_id).is_none());
+    }
+
+    execute_limit_orders {
+        let n in 1 .. T::MaxLimitOrdersPerBlock::get();
+
+        // Every order has a pool of its own, so that fills do not move prices of other orders.
+        let route = (0..=n).map(|idx| ASSET_ID_OFFSET + idx).collect::<Vec<_>>();
+        create_route_pools::<T>(&route);
+
+        // Price of every order is already reached, but the amount is so high that only
+        // the last of `FILL_ATTEMPTS` halvings of it is sold, which is the worst case.
+        let amount = 768_000 * ONE;
+        for idx in 0..n {
+            let caller = funded_account::<T>("caller", idx, &[route[idx as usize]]);
+            Pallet::<T>::place_limit_order(RawOrigin::Signed(caller).into(), route[idx as usize], route[idx as usize + 1], amount, FixedU128::from_rational(1, 10), None)?;
+        }
+    }: {
+        Pallet::<T>::execute_limit_orders(n);
+    }
+    verify {
+        assert_eq!(LimitOrders::<T>::iter().count(), n as usize);
+        for (_, _, order) in LimitOrders::<T>::iter() {
+            assert_eq!(order.amount, amount - amount / 128);
+        }
+    }
+
+    schedule_sell {
+        create_route_pools::<T>(&[ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1]);
+
+        let caller = funded_account::<T>("caller", 0, &[ASSET_ID_OFFSET]);
+        let schedule_id = NextScheduleId::<T>::get();
+    }: _(RawOrigin::Signed(caller.clone()), ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1, ONE, 10u32.into(), 10 * ONE, Permill::from_percent(50), None)
+    verify {
+        assert!(Pallet::<T>::schedule(schedule_id).is_some());
+    }
+
+    cancel_schedule {
+        create_route_pools::<T>(&[ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1]);
+
+        let caller = funded_account::<T>("caller", 0, &[ASSET_ID_OFFSET]);
+        let schedule_id = NextScheduleId::<T>::get();
+
+        Pallet::<T>::schedule_sell(RawOrigin::Signed(caller.clone()).into(), ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1, ONE, 10u32.into(), 10 * ONE, Permill::from_percent(50), None)?;
+    }: _(RawOrigin::Signed(caller.clone()), schedule_id)
+    verify {
+        assert!(Pallet::<T>::schedule(schedule_id).is_none());
+    }
+
+    execute_schedules {
+        let n in 1 .. T::MaxSchedulesPerBlock::get();
+
+        create_route_pools::<T>(&[ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1]);
+
+        // All schedules are executed in the next block and planned again.
+        for idx in 0..n {
+            let caller = funded_account::<T>("caller", idx, &[ASSET_ID_OFFSET]);
+            Pallet::<T>::schedule_sell(RawOrigin::Signed(caller).into(), ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1, ONE, 10u32.into(), 10 * ONE, Permill::from_percent(50), None)?;
+        }
+
+        let block = frame_system::Pallet::<T>::block_number() + 1u32.into();
+    }: {
+        Pallet::<T>::execute_schedules(block);
+    }
+    verify {
+        for schedule_id in 0..n as ScheduleId {
+            assert_eq!(Pallet::<T>::schedule(schedule_id).map(|schedule| schedule.remaining), Some(9 * ONE));
+        }
+    }
+
+    sell {
+        let asset_in = ASSET_ID_OFFSET;
+        let asset_out = ASSET_ID_OFFSET + 1;
+        create_route_pools::<T>(&[asset_in, asset_out]);
+
+        let caller = funded_account::<T>("caller", 0, &[asset_in]);
+    }: _(RawOrigin::Signed(caller.clone()), asset_in, asset_out, 10 * ONE, 0, None)
+    verify {
+        assert!(T::Currency::balance(asset_out, &caller) > 0);
+    }
+
+    buy {
+        let asset_in = ASSET_ID_OFFSET;
+        let asset_out = ASSET_ID_OFFSET + 1;
+        create_route_pools::<T>(&[asset_in, asset_out]);
+
+        let caller = funded_account::<T>("caller", 0, &[asset_in]);
+    }: _(RawOrigin::Signed(caller.clone()), asset_out, asset_in, 10 * ONE, Balance::MAX, None)
+    verify {
+        assert_eq!(T::Currency::balance(asset_out, &caller), 10 * ONE);
+    }
+
+    router_sell {
+        let n in 2 .. T::MaxRouteLength::get();
+
+        let route = route::<T>(n);
+        create_route_pools::<T>(&route);
+
+        let caller = funded_account::<T>("caller", 0, &route[..1]);
+        let asset_out = route[route.len() - 1];
+    }: _(RawOrigin::Signed(caller.clone()), route, 10 * ONE, 0, None)
+    verify {
+        assert!(T::Currency::balance(asset_out, &caller) > 0);
+    }
+
+    router_buy {
+        let n in 2 .. T::MaxRouteLength::get();
+
+        let route = route::<T>(n);
+        create_route_pools::<T>(&route);
+
+        let caller = funded_account::<T>("caller", 0, &route[..1]);
+        let asset_out = route[route.len() - 1];
+    }: _(RawOrigin::Signed(caller.clone()), route, 10 * ONE, Balance::MAX, None)
+    verify {
+        assert_eq!(T::Currency::balance(asset_out, &caller), 10 * ONE);
+    }
+
+    update_oracles {
+        let p in 1 .. T::MaxPools::get();
+        let w in 1 .. 10;
+
+        // A single route with a pool between each two consecutive assets.
+        create_route_pools::<T>(&(0..=p).map(|idx| ASSET_ID_OFFSET + idx).collect::<Vec<_>>());
+
+        let windows: Vec<u32> = (1..=w).collect();
+
+        // Entries already exist, so that averages are calculated for every window.
+        Pallet::<T>::update_oracle_windows(1u32.into(), &windows);
+    }: {
+        Pallet::<T>::update_oracle_windows(2u32.into(), &windows);
+    }
+    verify {
+        let pair = (ASSET_ID_OFFSET, ASSET_ID_OFFSET + 1);
+        for window in windows {
+            assert_eq!(
+                Pallet::<T>::oracle(pair, window).map(|entry| entry.updated_at),
+                Some(2u32.into())
+            );
+        }
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::ExtBuilder::default().build(), crate::mock::Test);
+}
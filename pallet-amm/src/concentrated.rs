@@ -0,0 +1,339 @@
+use crate::traits::AccountIdFor;
+use crate::types::{ConcentratedParams, ConcentratedState, Position, TickInfo, Trade};
+use crate::weights::WeightInfo;
+use crate::{
+    AssetId, Balance, ConcentratedPools, Config, Error, Event, Pallet, PoolId, PositionId, Ticks,
+};
+use frame_support::dispatch::DispatchResult;
+use frame_support::ensure;
+use frame_support::traits::tokens::fungibles::Transfer;
+use frame_support::traits::Get;
+use frame_support::weights::Weight;
+use math::concentrated::{
+    self, Liquidity, SqrtPrice, SwapResult, SwapState, Tick, MAX_TICK, MIN_TICK,
+};
+use sp_runtime::traits::Zero;
+use sp_runtime::{DispatchError, Permill};
+use sp_std::vec::Vec;
+
+impl<T: Config> Pallet<T> {
+    /// Ensures that ticks of a position are valid in a pool of given parameters.
+    pub(crate) fn ensure_valid_ticks(
+        params: &ConcentratedParams,
+        tick_lower: Tick,
+        tick_upper: Tick,
+    ) -> DispatchResult {
+        ensure!(
+            MIN_TICK <= tick_lower
+                && tick_lower < tick_upper
+                && tick_upper <= MAX_TICK
+                && tick_lower % params.tick_spacing == 0
+                && tick_upper % params.tick_spacing == 0,
+            Error::<T>::InvalidTick
+        );
+
+        Ok(())
+    }
+
+    /// Returns square roots of prices of both ends of a range.
+    pub(crate) fn range_sqrt_prices(
+        tick_lower: Tick,
+        tick_upper: Tick,
+    ) -> Result<(SqrtPrice, SqrtPrice), DispatchError> {
+        let sqrt_price_lower =
+            concentrated::sqrt_price_at_tick(tick_lower).ok_or(Error::<T>::InvalidTick)?;
+        let sqrt_price_upper =
+            concentrated::sqrt_price_at_tick(tick_upper).ok_or(Error::<T>::InvalidTick)?;
+
+        Ok((sqrt_price_lower, sqrt_price_upper))
+    }
+
+    /// Changes liquidity of a position by `liquidity_delta` and adds fees earned since its last
+    /// update to fees owed to it.
+    ///
+    /// Ticks of the position are initialized by the first position ending at them and removed
+    /// with the last one. Liquidity of the pool changes if the range of the position contains
+    /// the price.
+    pub(crate) fn update_position(
+        position: &mut Position<T::AccountId>,
+        liquidity_delta: i128,
+    ) -> DispatchResult {
+        let mut state =
+            Self::concentrated_pool(position.pool_id).ok_or(Error::<T>::PoolNotFound)?;
+
+        let lower = Self::update_tick(
+            position.pool_id,
+            &mut state,
+            position.tick_lower,
+            liquidity_delta,
+            false,
+        )?;
+        let upper = Self::update_tick(
+            position.pool_id,
+            &mut state,
+            position.tick_upper,
+            liquidity_delta,
+            true,
+        )?;
+
+        let fee_growth_inside_a = concentrated::calculate_fee_growth_inside(
+            state.tick,
+            position.tick_lower,
+            position.tick_upper,
+            lower.fee_growth_outside_a,
+            upper.fee_growth_outside_a,
+            state.fee_growth_a,
+        );
+        let fee_growth_inside_b = concentrated::calculate_fee_growth_inside(
+            state.tick,
+            position.tick_lower,
+            position.tick_upper,
+            lower.fee_growth_outside_b,
+            upper.fee_growth_outside_b,
+            state.fee_growth_b,
+        );
+
+        let fees_a = concentrated::calculate_fees_owed(
+            position.liquidity,
+            fee_growth_inside_a,
+            position.fee_growth_inside_a,
+        )
+        .ok_or(Error::<T>::Math)?;
+        let fees_b = concentrated::calculate_fees_owed(
+            position.liquidity,
+            fee_growth_inside_b,
+            position.fee_growth_inside_b,
+        )
+        .ok_or(Error::<T>::Math)?;
+
+        position.fees_owed_a = position
+            .fees_owed_a
+            .checked_add(fees_a)
+            .ok_or(Error::<T>::Math)?;
+        position.fees_owed_b = position
+            .fees_owed_b
+            .checked_add(fees_b)
+            .ok_or(Error::<T>::Math)?;
+        position.fee_growth_inside_a = fee_growth_inside_a;
+        position.fee_growth_inside_b = fee_growth_inside_b;
+        position.liquidity = Self::add_liquidity_delta(position.liquidity, liquidity_delta)?;
+
+        if position.tick_lower <= state.tick && state.tick < position.tick_upper {
+            state.liquidity = Self::add_liquidity_delta(state.liquidity, liquidity_delta)?;
+        }
+
+        ConcentratedPools::<T>::insert(position.pool_id, state);
+
+        Ok(())
+    }
+
+    /// Changes liquidity of positions ending at `tick` by `liquidity_delta`.
+    ///
+    /// Number of initialized ticks of the pool is updated in `state`, and a new tick can be
+    /// initialized only if the pool has less than `MaxTicksPerPool` of them.
+    ///
+    /// Returns the tick after the change, even if it was removed.
+    fn update_tick(
+        pool_id: PoolId,
+        state: &mut ConcentratedState,
+        tick: Tick,
+        liquidity_delta: i128,
+        is_upper: bool,
+    ) -> Result<TickInfo, DispatchError> {
+        let mut info = Self::tick(pool_id, tick).unwrap_or_default();
+
+        let is_initialized = !info.liquidity_gross.is_zero();
+
+        // All fees so far are considered to be earned below the tick.
+        if !is_initialized && tick <= state.tick {
+            info.fee_growth_outside_a = state.fee_growth_a;
+            info.fee_growth_outside_b = state.fee_growth_b;
+        }
+
+        info.liquidity_gross = Self::add_liquidity_delta(info.liquidity_gross, liquidity_delta)?;
+
+        // Liquidity of a position is added when the price crosses its lower tick upwards
+        // and removed when it crosses its upper tick.
+        info.liquidity_net = if is_upper {
+            info.liquidity_net.checked_sub(liquidity_delta)
+        } else {
+            info.liquidity_net.checked_add(liquidity_delta)
+        }
+        .ok_or(Error::<T>::Math)?;
+
+        if info.liquidity_gross.is_zero() {
+            if is_initialized {
+                state.ticks = state.ticks.saturating_sub(1);
+            }
+            Ticks::<T>::remove(pool_id, tick);
+        } else {
+            if !is_initialized {
+                ensure!(
+                    state.ticks < T::MaxTicksPerPool::get(),
+                    Error::<T>::TooManyTicks
+                );
+                state.ticks = state.ticks.saturating_add(1);
+            }
+            Ticks::<T>::insert(pool_id, tick, info);
+        }
+
+        Ok(info)
+    }
+
+    fn add_liquidity_delta(
+        liquidity: Liquidity,
+        liquidity_delta: i128,
+    ) -> Result<Liquidity, DispatchError> {
+        if liquidity_delta >= 0 {
+            liquidity.checked_add(liquidity_delta.unsigned_abs())
+        } else {
+            liquidity.checked_sub(liquidity_delta.unsigned_abs())
+        }
+        .ok_or_else(|| Error::<T>::Math.into())
+    }
+
+    /// Transfers fees owed to a position to `who`.
+    pub(crate) fn collect_position_fees(
+        who: &T::AccountId,
+        position_id: PositionId,
+        position: &mut Position<T::AccountId>,
+    ) -> DispatchResult {
+        let pool = Self::pools(position.pool_id).ok_or(Error::<T>::PoolNotFound)?;
+        let pool_account = T::Account::create_account_id(position.pool_id)?;
+
+        let (amount_a, amount_b) = (position.fees_owed_a, position.fees_owed_b);
+
+        T::Currency::transfer(pool.assets[0], &pool_account, who, amount_a, false)?;
+        T::Currency::transfer(pool.assets[1], &pool_account, who, amount_b, false)?;
+
+        position.fees_owed_a = Balance::zero();
+        position.fees_owed_b = Balance::zero();
+
+        Self::deposit_event(Event::FeesCollected {
+            who: who.clone(),
+            position_id,
+            amount_a,
+            amount_b,
+        });
+
+        Ok(())
+    }
+
+    /// Calculates swap in a concentrated liquidity pool, selling `amount` of `asset_in`
+    /// if `is_sell`, otherwise buying `amount` of `asset_out`.
+    ///
+    /// Returns the swap and number of ticks read to calculate it.
+    pub(crate) fn calculate_concentrated_swap(
+        pool_id: PoolId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount: Balance,
+        fee: Permill,
+        is_sell: bool,
+    ) -> Result<(SwapResult, u32), DispatchError> {
+        let state = Self::concentrated_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+
+        // Selling the first asset of the pair moves the price down.
+        let a_to_b = asset_in < asset_out;
+
+        // Number of ticks is bounded by `MaxTicksPerPool`.
+        let mut ticks: Vec<(Tick, i128)> = Ticks::<T>::iter_prefix(pool_id)
+            .map(|(tick, info)| (tick, info.liquidity_net))
+            .collect();
+        ticks.sort_unstable_by_key(|(tick, _)| *tick);
+
+        let swap_state = SwapState {
+            sqrt_price: state.sqrt_price,
+            tick: state.tick,
+            liquidity: state.liquidity,
+            fee_growth: if a_to_b {
+                state.fee_growth_a
+            } else {
+                state.fee_growth_b
+            },
+        };
+
+        let swap = if is_sell {
+            concentrated::calculate_swap(
+                swap_state,
+                &ticks,
+                amount,
+                fee,
+                T::ProtocolFee::get(),
+                a_to_b,
+            )
+        } else {
+            concentrated::calculate_swap_exact_out(
+                swap_state,
+                &ticks,
+                amount,
+                fee,
+                T::ProtocolFee::get(),
+                a_to_b,
+            )
+        };
+
+        // Swap fails only when positions do not provide enough liquidity for the amount.
+        let swap = swap.ok_or(Error::<T>::InsufficientLiquidity)?;
+
+        Ok((swap, ticks.len() as u32))
+    }
+
+    /// Returns weight of reading and crossing ticks by swaps of given trades in concentrated
+    /// liquidity pools, which is not included in weights of trades.
+    pub(crate) fn swap_ticks_weight(trades: &[Trade<T::AccountId>]) -> Weight {
+        trades.iter().fold(Weight::zero(), |weight, trade| {
+            weight.saturating_add(T::WeightInfo::swap_ticks(trade.ticks))
+        })
+    }
+
+    /// Returns weight of reading and crossing ticks by a swap in a concentrated liquidity pool
+    /// with `MaxTicksPerPool` ticks.
+    pub(crate) fn max_swap_ticks_weight() -> Weight {
+        T::WeightInfo::swap_ticks(T::MaxTicksPerPool::get())
+    }
+
+    /// Moves price of a concentrated liquidity pool by executed swap.
+    ///
+    /// Fee growths outside of crossed ticks are flipped to the other side of the price.
+    pub(crate) fn apply_concentrated_swap(
+        pool_id: PoolId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        swap: &SwapResult,
+    ) -> DispatchResult {
+        let mut state = Self::concentrated_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+
+        let a_to_b = asset_in < asset_out;
+
+        for &(tick, fee_growth) in swap.crossed_ticks.iter() {
+            let (fee_growth_a, fee_growth_b) = if a_to_b {
+                (fee_growth, state.fee_growth_b)
+            } else {
+                (state.fee_growth_a, fee_growth)
+            };
+
+            Ticks::<T>::mutate(pool_id, tick, |maybe_info| {
+                if let Some(info) = maybe_info {
+                    info.fee_growth_outside_a =
+                        fee_growth_a.wrapping_sub(info.fee_growth_outside_a);
+                    info.fee_growth_outside_b =
+                        fee_growth_b.wrapping_sub(info.fee_growth_outside_b);
+                }
+            });
+        }
+
+        state.sqrt_price = swap.state.sqrt_price;
+        state.tick = swap.state.tick;
+        state.liquidity = swap.state.liquidity;
+        if a_to_b {
+            state.fee_growth_a = swap.state.fee_growth;
+        } else {
+            state.fee_growth_b = swap.state.fee_growth;
+        }
+
+        ConcentratedPools::<T>::insert(pool_id, state);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use crate::traits::FeeCurve;
+use crate::types::FeeEntry;
+use crate::{Config, DynamicFees, Pallet, PoolId};
+use frame_support::traits::Get;
+use sp_runtime::traits::Saturating;
+use sp_runtime::{Permill, SaturatedConversion};
+use sp_std::marker::PhantomData;
+
+/// Fee curve which raises the fee by `ImpactFactor` of price impact of every trade
+/// and removes `Decay` of the excess over the fee of the pool every block.
+pub struct VolatilityFeeCurve<Decay, ImpactFactor>(PhantomData<(Decay, ImpactFactor)>);
+
+impl<Decay: Get<Permill>, ImpactFactor: Get<Permill>> FeeCurve
+    for VolatilityFeeCurve<Decay, ImpactFactor>
+{
+    fn decay(fee: Permill, floor: Permill, elapsed: u32) -> Permill {
+        let retained = Permill::one()
+            .saturating_sub(Decay::get())
+            .saturating_pow(elapsed as usize);
+
+        floor.saturating_add(retained.saturating_mul(fee.saturating_sub(floor)))
+    }
+
+    fn raise(fee: Permill, price_impact: Permill) -> Permill {
+        fee.saturating_add(ImpactFactor::get().saturating_mul(price_impact))
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Returns trade fee of a pool with fee `floor` in the current block.
+    ///
+    /// Fee raised by previous trades decays towards `floor` and never exceeds `MaxTradeFee`,
+    /// unless the fee of the pool itself is higher.
+    pub(crate) fn current_fee(pool_id: PoolId, floor: Permill) -> Permill {
+        let entry = match Self::dynamic_fee(pool_id) {
+            Some(entry) => entry,
+            None => return floor,
+        };
+
+        let now = frame_system::Pallet::<T>::block_number();
+        let elapsed = now.saturating_sub(entry.updated_at).saturated_into();
+
+        Self::bound_fee(T::FeeCurve::decay(entry.fee, floor, elapsed), floor)
+    }
+
+    /// Raises dynamic fee of a pool by the price impact of a trade executed in it.
+    pub(crate) fn update_dynamic_fee(pool_id: PoolId, price_impact: Permill) {
+        let floor = match Self::pools(pool_id) {
+            Some(pool) => pool.fee,
+            None => return,
+        };
+
+        let fee = Self::bound_fee(
+            T::FeeCurve::raise(Self::current_fee(pool_id, floor), price_impact),
+            floor,
+        );
+
+        if fee == floor {
+            DynamicFees::<T>::remove(pool_id);
+        } else {
+            DynamicFees::<T>::insert(
+                pool_id,
+                FeeEntry {
+                    fee,
+                    updated_at: frame_system::Pallet::<T>::block_number(),
+                },
+            );
+        }
+    }
+
+    fn bound_fee(fee: Permill, floor: Permill) -> Permill {
+        fee.clamp(floor, T::MaxTradeFee::get().max(floor))
+    }
+}
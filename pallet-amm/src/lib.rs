@@ -4,37 +4,105 @@ use frame_support::sp_runtime::Permill;
 use frame_support::{dispatch::DispatchResult, traits::Get};
 use frame_system::ensure_signed;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod concentrated;
+mod fee;
+mod limit_order;
 #[cfg(test)]
 mod mock;
+mod oracle;
+mod router;
+mod schedule;
 #[cfg(test)]
 mod tests;
 pub mod traits;
-mod weights;
+pub mod types;
+pub mod weights;
 
 use weights::WeightInfo;
 
+pub use fee::VolatilityFeeCurve;
 pub use pallet::*;
 
 type AssetId = u32;
 type Balance = u128;
+type PoolId = u32;
+type PositionId = u64;
+type OrderId = u64;
+type ScheduleId = u64;
 
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use crate::traits::{AccountIdFor, Create};
+    use crate::traits::{
+        AccountIdFor, Create, FeeCurve, FlashSwapHandler, OnLiquidityChanged, OnTrade,
+    };
+    use crate::types::{
+        ConcentratedParams, ConcentratedState, FeeEntry, LBPParams, LimitOrder, OracleEntry,
+        PoolInfo, PoolType, Position, Schedule, StableParams, TickInfo, Trade, MAX_AMPLIFICATION,
+        MAX_WEIGHT,
+    };
     use frame_support::pallet_prelude::*;
-    use frame_support::traits::tokens::fungibles::{Inspect, Mutate, Transfer};
+    use frame_support::traits::tokens::fungibles::{Inspect, Mutate, MutateHold, Transfer};
     use frame_system::pallet_prelude::OriginFor;
-    use sp_runtime::traits::Zero;
-
+    use sp_runtime::traits::{One, Saturating, Zero};
+    use sp_runtime::FixedU128;
+    use sp_std::vec::Vec;
+
+    use math::concentrated;
+    use math::concentrated::{Liquidity, SqrtPrice, Tick, MAX_TICK};
+    use math::lbp;
+    use math::lbp::LBPWeight;
+    use math::stableswap;
     use math::xyk::*;
-    use sp_runtime::{FixedPointNumber, FixedU128};
+
+    /// Version of the storage layout.
+    ///
+    /// Layout of version 0 is the first deployed one. Earlier layouts of `Pools`, keyed by pair,
+    /// were never deployed, so there is no state to migrate. Any later change of the layout
+    /// must bump the version and come with a migration.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
 
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            let schedules = Self::execute_schedules(n);
+
+            T::WeightInfo::update_oracles(
+                PairPools::<T>::count(),
+                T::OracleWindows::get().len() as u32,
+            )
+            .saturating_add(T::WeightInfo::execute_schedules(schedules))
+            .saturating_add(
+                Self::max_swap_ticks_weight()
+                    .saturating_add(T::OnTrade::on_trade_weight())
+                    .saturating_mul(schedules as u64),
+            )
+        }
+
+        fn on_finalize(n: T::BlockNumber) {
+            Self::update_oracles(n);
+        }
+
+        fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            // Orders checked in a block are limited by the remaining weight as well.
+            let mut max_orders = T::MaxLimitOrdersPerBlock::get();
+            while max_orders > 0 && Self::limit_orders_weight(max_orders) > remaining_weight {
+                max_orders -= 1;
+            }
+
+            if max_orders.is_zero() {
+                return Weight::zero();
+            }
+
+            Self::limit_orders_weight(Self::execute_limit_orders(max_orders))
+        }
+    }
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -43,37 +111,278 @@ pub mod pallet {
         /// Multi currency support
         type Currency: Inspect<Self::AccountId, AssetId = AssetId, Balance = Balance>
             + Mutate<Self::AccountId>
+            + MutateHold<Self::AccountId>
             + Transfer<Self::AccountId>;
 
         /// Registry support
-        type AssetRegistry: Create<(AssetId, AssetId), AssetId = AssetId, Error = DispatchError>;
+        type AssetRegistry: Create<Vec<AssetId>, AssetId = AssetId, Error = DispatchError>;
 
         /// Pool account creations
-        type Account: AccountIdFor<
-            (AssetId, AssetId),
-            AccountId = Self::AccountId,
-            Error = DispatchError,
-        >;
-
-        //#[pallet::constant]
-        //type CreationFee: Get<Permill>;
+        type Account: AccountIdFor<PoolId, AccountId = Self::AccountId, Error = DispatchError>;
 
+        /// Trade fee of pools which are not created with a fee of their own.
         #[pallet::constant]
         type TradeFee: Get<Permill>;
 
-        //#[pallet::constant]
-        //type WithdrawFee: Get<Permill>;
+        /// Minimum trade fee of a pool.
+        #[pallet::constant]
+        type MinTradeFee: Get<Permill>;
+
+        /// Maximum trade fee of a pool.
+        #[pallet::constant]
+        type MaxTradeFee: Get<Permill>;
+
+        /// Curve of dynamic trade fees of pools.
+        type FeeCurve: FeeCurve;
+
+        /// Handler of flash swaps, which repays the borrowed assets.
+        type FlashSwapHandler: FlashSwapHandler<Self::AccountId, AssetId, Balance>;
+
+        /// Part of the trade fee which is transferred to `ProtocolFeeReceiver`.
+        /// The rest of the fee stays in the pool.
+        #[pallet::constant]
+        type ProtocolFee: Get<Permill>;
+
+        /// Account which receives the protocol fee.
+        #[pallet::constant]
+        type ProtocolFeeReceiver: Get<Self::AccountId>;
+
+        /// Maximum number of assets in a trade route.
+        #[pallet::constant]
+        type MaxRouteLength: Get<u32>;
+
+        /// Maximum number of assets in a pool.
+        #[pallet::constant]
+        type MaxAssetsInPool: Get<u32>;
+
+        /// Maximum number of pools, which bounds oracle updates at the end of every block.
+        #[pallet::constant]
+        type MaxPools: Get<u32>;
+
+        /// Maximum number of initialized ticks of a concentrated liquidity pool, which bounds
+        /// ticks read and crossed by a swap in the pool.
+        #[pallet::constant]
+        type MaxTicksPerPool: Get<u32>;
+
+        /// Maximum number of limit orders checked in a block.
+        #[pallet::constant]
+        type MaxLimitOrdersPerBlock: Get<u32>;
+
+        /// Maximum number of scheduled sells executed in a block.
+        #[pallet::constant]
+        type MaxSchedulesPerBlock: Get<u32>;
+
+        /// Number of consecutive failed executions of a schedule after which the schedule is
+        /// terminated.
+        #[pallet::constant]
+        type MaxScheduleFailures: Get<u32>;
+
+        /// Lengths of windows, in blocks, over which the oracle averages prices and liquidity.
+        #[pallet::constant]
+        type OracleWindows: Get<Vec<u32>>;
+
+        /// Amount of initial shares of a pool locked in the pool account forever.
+        #[pallet::constant]
+        type MinimumLiquidity: Get<Balance>;
+
+        /// Fraction of removed liquidity which stays in the pool.
+        #[pallet::constant]
+        type WithdrawFee: Get<Permill>;
+
+        /// The origin that can create a pool. Its account provides the initial liquidity
+        /// and pays `CreationFee`.
+        type CreatePoolOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+
+        /// The origin that can update parameters of pools, e.g. governance.
+        type AuthorityOrigin: EnsureOrigin<Self::Origin>;
+
+        /// Asset in which the pool creation fee is paid.
+        #[pallet::constant]
+        type CreationFeeAsset: Get<AssetId>;
+
+        /// Amount paid by the creator of a pool to the treasury.
+        #[pallet::constant]
+        type CreationFee: Get<Balance>;
+
+        /// Account receiving pool creation fees.
+        #[pallet::constant]
+        type TreasuryAccount: Get<Self::AccountId>;
+
+        /// Handler of executed trades.
+        type OnTrade: OnTrade<Self::AccountId, AssetId, Balance>;
 
-        /// The origin that can create a pool
-        //type AuthorityOrigin: EnsureOrigin<Self::Origin>;
+        /// Handler of added and removed liquidity.
+        type OnLiquidityChanged: OnLiquidityChanged<Self::AccountId, AssetId, Balance>;
 
         type WeightInfo: WeightInfo;
     }
 
+    pub(crate) type PoolAssetsOf<T> = BoundedVec<AssetId, <T as Config>::MaxAssetsInPool>;
+
+    pub(crate) type PoolInfoOf<T> = PoolInfo<
+        <T as frame_system::Config>::AccountId,
+        <T as frame_system::Config>::BlockNumber,
+        <T as Config>::MaxAssetsInPool,
+    >;
+
     #[pallet::storage]
     #[pallet::getter(fn pools)]
     pub(crate) type Pools<T: Config> =
-        StorageMap<_, Blake2_128Concat, (AssetId, AssetId), AssetId, OptionQuery>;
+        CountedStorageMap<_, Blake2_128Concat, PoolId, PoolInfoOf<T>, OptionQuery>;
+
+    /// Pools of pairs of assets.
+    #[pallet::storage]
+    #[pallet::getter(fn pair_pool_id)]
+    pub(crate) type PairPools<T: Config> =
+        CountedStorageMap<_, Blake2_128Concat, (AssetId, AssetId), PoolId, OptionQuery>;
+
+    /// Id of the next created pool.
+    #[pallet::storage]
+    pub(crate) type NextPoolId<T: Config> = StorageValue<_, PoolId, ValueQuery>;
+
+    /// Dynamic trade fees of pools raised by recent trades.
+    #[pallet::storage]
+    #[pallet::getter(fn dynamic_fee)]
+    pub(crate) type DynamicFees<T: Config> =
+        StorageMap<_, Blake2_128Concat, PoolId, FeeEntry<T::BlockNumber>, OptionQuery>;
+
+    /// States of concentrated liquidity pools.
+    #[pallet::storage]
+    #[pallet::getter(fn concentrated_pool)]
+    pub(crate) type ConcentratedPools<T: Config> =
+        StorageMap<_, Blake2_128Concat, PoolId, ConcentratedState, OptionQuery>;
+
+    /// Ticks of concentrated liquidity pools at which positions end.
+    #[pallet::storage]
+    #[pallet::getter(fn tick)]
+    pub(crate) type Ticks<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, PoolId, Twox64Concat, Tick, TickInfo, OptionQuery>;
+
+    /// Positions of liquidity in concentrated liquidity pools.
+    #[pallet::storage]
+    #[pallet::getter(fn position)]
+    pub(crate) type Positions<T: Config> =
+        StorageMap<_, Blake2_128Concat, PositionId, Position<T::AccountId>, OptionQuery>;
+
+    /// Id of the next minted position.
+    #[pallet::storage]
+    pub(crate) type NextPositionId<T: Config> = StorageValue<_, PositionId, ValueQuery>;
+
+    /// Limit orders of pools which are not filled yet.
+    #[pallet::storage]
+    #[pallet::getter(fn limit_order)]
+    pub(crate) type LimitOrders<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        PoolId,
+        Twox64Concat,
+        OrderId,
+        LimitOrder<T::AccountId>,
+        OptionQuery,
+    >;
+
+    /// Id of the next placed limit order.
+    #[pallet::storage]
+    pub(crate) type NextOrderId<T: Config> = StorageValue<_, OrderId, ValueQuery>;
+
+    /// Limit order checked last, after which checking continues in the next block.
+    #[pallet::storage]
+    pub(crate) type LimitOrderCursor<T: Config> = StorageValue<_, (PoolId, OrderId), OptionQuery>;
+
+    /// Schedules of recurring sells.
+    #[pallet::storage]
+    #[pallet::getter(fn schedule)]
+    pub(crate) type Schedules<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        ScheduleId,
+        Schedule<T::AccountId, T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// Id of the next registered schedule.
+    #[pallet::storage]
+    pub(crate) type NextScheduleId<T: Config> = StorageValue<_, ScheduleId, ValueQuery>;
+
+    /// Schedules executed in a block.
+    #[pallet::storage]
+    #[pallet::getter(fn scheduled_executions)]
+    pub(crate) type ScheduledExecutions<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::BlockNumber,
+        BoundedVec<ScheduleId, T::MaxSchedulesPerBlock>,
+        ValueQuery,
+    >;
+
+    /// Average prices and liquidity of pools, per oracle window.
+    #[pallet::storage]
+    #[pallet::getter(fn oracle)]
+    pub(crate) type Oracles<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        (AssetId, AssetId),
+        Twox64Concat,
+        u32,
+        OracleEntry<T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config> {
+        /// Pools created at genesis as `(asset_a, asset_b, amount_a, amount_b, owner)`.
+        ///
+        /// Reserves are minted directly into the pool account and initial shares to the owner.
+        pub pools: Vec<(AssetId, AssetId, Balance, Balance, T::AccountId)>,
+    }
+
+    #[cfg(feature = "std")]
+    impl<T: Config> Default for GenesisConfig<T> {
+        fn default() -> Self {
+            GenesisConfig { pools: vec![] }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+        fn build(&self) {
+            for &(asset_a, asset_b, amount_a, amount_b, ref owner) in self.pools.iter() {
+                assert!(asset_a != asset_b, "Genesis pool has same assets.");
+
+                let pair = Pallet::<T>::pair(asset_a, asset_b);
+                assert!(
+                    !PairPools::<T>::contains_key(pair),
+                    "Genesis pool is defined more than once."
+                );
+
+                let pool_id = Pallet::<T>::next_pool_id().expect("Failed to create pool id.");
+                let pool_account =
+                    T::Account::create_account_id(pool_id).expect("Failed to create pool account.");
+
+                T::Currency::mint_into(asset_a, &pool_account, amount_a)
+                    .expect("Failed to mint pool reserve.");
+                T::Currency::mint_into(asset_b, &pool_account, amount_b)
+                    .expect("Failed to mint pool reserve.");
+
+                let amounts = if pair.0 == asset_a {
+                    (amount_a, amount_b)
+                } else {
+                    (amount_b, amount_a)
+                };
+
+                Pallet::<T>::initialize_pool(
+                    owner,
+                    pool_id,
+                    &[pair.0, pair.1],
+                    &pool_account,
+                    &[amounts.0, amounts.1],
+                    PoolType::XYK,
+                    T::TradeFee::get(),
+                )
+                .expect("Failed to initialize pool.");
+            }
+        }
+    }
 
     #[pallet::error]
     pub enum Error<T> {
@@ -83,7 +392,7 @@ pub mod pallet {
         /// Pool with given pair already exists
         PoolAlreadyExists,
 
-        /// Pool with given pair does not exist.
+        /// Pool does not exist.
         PoolNotFound,
 
         /// Limit
@@ -91,6 +400,99 @@ pub mod pallet {
 
         /// Math
         Math,
+
+        /// Route must contain at least two assets and must not trade in the same pool twice.
+        InvalidRoute,
+
+        /// Shares, or liquidity of a position, received for added liquidity are below the minimum.
+        InsufficientSharesOut,
+
+        /// Amounts received for removed liquidity are below the minimum.
+        InsufficientAmountOut,
+
+        /// Deadline of the transaction has passed.
+        DeadlineExpired,
+
+        /// Liquidity is not sufficient for the operation, e.g. to lock minimum liquidity
+        /// of a new pool or to mint any shares for added liquidity.
+        InsufficientLiquidity,
+
+        /// Sale of liquidity bootstrapping pool must not start in the past and must end after
+        /// it starts. Weights must be between zero and `MAX_WEIGHT`.
+        InvalidLBPParams,
+
+        /// Trading in liquidity bootstrapping pool is allowed only during its sale.
+        SaleNotRunning,
+
+        /// Liquidity cannot be added to liquidity bootstrapping pool once its sale started.
+        SaleAlreadyStarted,
+
+        /// Liquidity of liquidity bootstrapping pool can be withdrawn only after its sale ended.
+        SaleNotEnded,
+
+        /// Only owner of liquidity bootstrapping pool can change its liquidity.
+        NotPoolOwner,
+
+        /// Operation is not supported by the type of the pool.
+        NotSupportedByPoolType,
+
+        /// Amplification must be between zero and `MAX_AMPLIFICATION`. Its change must not start
+        /// in the past and must end after it starts.
+        InvalidAmplification,
+
+        /// Multi-asset pool must consist of at least three different assets
+        /// and at most `MaxAssetsInPool`.
+        InvalidAssets,
+
+        /// Asset is not in the pool.
+        AssetNotInPool,
+
+        /// Trade fee must be between `MinTradeFee` and `MaxTradeFee`.
+        InvalidFee,
+
+        /// Borrowed assets were not repaid with fee, or liquidity of the pool was changed
+        /// during the flash swap.
+        FlashSwapNotRepaid,
+
+        /// Tick spacing must be positive and ticks of a position must be its multiples, with
+        /// the lower tick below the upper one. Ticks and prices must be between those of
+        /// `MIN_TICK` and `MAX_TICK`.
+        InvalidTick,
+
+        /// Pool already has `MaxTicksPerPool` initialized ticks, so that a position can only
+        /// end at one of them.
+        TooManyTicks,
+
+        /// Position does not exist.
+        PositionNotFound,
+
+        /// Only owner of a position can change it.
+        NotPositionOwner,
+
+        /// Amount and price of a limit order must not be zero.
+        InvalidLimitOrder,
+
+        /// Limit order does not exist.
+        LimitOrderNotFound,
+
+        /// Only owner of a limit order can cancel it.
+        NotLimitOrderOwner,
+
+        /// Amount and period of a schedule must not be zero and its budget must cover
+        /// at least one execution.
+        InvalidSchedule,
+
+        /// Schedule does not exist.
+        ScheduleNotFound,
+
+        /// Only owner of a schedule can cancel it.
+        NotScheduleOwner,
+
+        /// None of the next blocks has room for another scheduled execution.
+        TooManySchedules,
+
+        /// Number of pools reached `MaxPools`.
+        TooManyPools,
     }
 
     #[pallet::event]
@@ -103,6 +505,7 @@ pub mod pallet {
             asset_b: AssetId,
             shares: Balance,
             share_asset_id: AssetId,
+            fee: Permill,
         },
         /// Liquidity added to the pool.
         LiquidityAdded {
@@ -118,6 +521,30 @@ pub mod pallet {
             asset_a: AssetId,
             asset_b: AssetId,
             shares: Balance,
+            amount_a: Balance,
+            amount_b: Balance,
+            fee_a: Balance,
+            fee_b: Balance,
+        },
+        /// Liquidity added to the pool in a single asset. Protocol fee of the swap is paid
+        /// in `asset_b`.
+        LiquidityAddedSingleAsset {
+            who: T::AccountId,
+            asset_a: AssetId,
+            asset_b: AssetId,
+            amount_a: Balance,
+            shares: Balance,
+            protocol_fee: Balance,
+        },
+        /// Liquidity was removed from the pool in a single asset. Protocol fee of the swap
+        /// is paid in `asset_a`.
+        LiquidityRemovedSingleAsset {
+            who: T::AccountId,
+            asset_a: AssetId,
+            asset_b: AssetId,
+            shares: Balance,
+            amount_a: Balance,
+            protocol_fee: Balance,
         },
 
         /// Pool was destroyed.
@@ -127,6 +554,35 @@ pub mod pallet {
             asset_b: AssetId,
             share_asset_id: AssetId,
         },
+        /// Multi-asset pool was created.
+        MultiAssetPoolCreated {
+            who: T::AccountId,
+            pool_id: PoolId,
+            assets: Vec<AssetId>,
+            shares: Balance,
+            share_asset_id: AssetId,
+        },
+        /// Liquidity added to the multi-asset pool.
+        LiquidityAddedMultiAsset {
+            who: T::AccountId,
+            pool_id: PoolId,
+            amounts: Vec<(AssetId, Balance)>,
+            shares: Balance,
+        },
+        /// Liquidity was removed from the multi-asset pool.
+        LiquidityRemovedMultiAsset {
+            who: T::AccountId,
+            pool_id: PoolId,
+            shares: Balance,
+            amounts: Vec<(AssetId, Balance)>,
+            fees: Vec<(AssetId, Balance)>,
+        },
+        /// Multi-asset pool was destroyed.
+        MultiAssetPoolDestroyed {
+            who: T::AccountId,
+            pool_id: PoolId,
+            share_asset_id: AssetId,
+        },
         /// Swap executed.
         SwapExecuted {
             who: T::AccountId,
@@ -134,82 +590,377 @@ pub mod pallet {
             asset_out: AssetId,
             amount_in: Balance,
             amount_out: Balance,
-            fee: Balance,
+            lp_fee: Balance,
+            protocol_fee: Balance,
+        },
+        /// Flash swap executed. Amounts are net changes of reserves of the pool.
+        FlashSwapExecuted {
+            who: T::AccountId,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_in: Balance,
+            amount_out: Balance,
+        },
+        /// Trades of a route executed.
+        RouteExecuted {
+            who: T::AccountId,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_in: Balance,
+            amount_out: Balance,
+        },
+        /// Trade fee of a pool was updated.
+        PoolFeeUpdated { pool_id: PoolId, fee: Permill },
+        /// Change of amplification of a stableswap pool was scheduled.
+        AmplificationUpdated {
+            pool_id: PoolId,
+            initial_amplification: Balance,
+            final_amplification: Balance,
+            start: T::BlockNumber,
+            end: T::BlockNumber,
+        },
+        /// Concentrated liquidity pool was created.
+        ConcentratedPoolCreated {
+            who: T::AccountId,
+            pool_id: PoolId,
+            asset_a: AssetId,
+            asset_b: AssetId,
+            tick_spacing: Tick,
+            sqrt_price: SqrtPrice,
+            fee: Permill,
+        },
+        /// Position was minted in a concentrated liquidity pool.
+        PositionMinted {
+            who: T::AccountId,
+            pool_id: PoolId,
+            position_id: PositionId,
+            tick_lower: Tick,
+            tick_upper: Tick,
+            liquidity: Liquidity,
+            amount_a: Balance,
+            amount_b: Balance,
+        },
+        /// Liquidity of a position was withdrawn.
+        PositionBurned {
+            who: T::AccountId,
+            position_id: PositionId,
+            liquidity: Liquidity,
+            amount_a: Balance,
+            amount_b: Balance,
+        },
+        /// Fees earned by a position were collected.
+        FeesCollected {
+            who: T::AccountId,
+            position_id: PositionId,
+            amount_a: Balance,
+            amount_b: Balance,
+        },
+        /// Limit order was placed and its amount held.
+        LimitOrderPlaced {
+            who: T::AccountId,
+            pool_id: PoolId,
+            order_id: OrderId,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount: Balance,
+            price: FixedU128,
+        },
+        /// Limit order was filled, completely or partially.
+        LimitOrderFilled {
+            who: T::AccountId,
+            pool_id: PoolId,
+            order_id: OrderId,
+            amount_in: Balance,
+            amount_out: Balance,
+            remaining: Balance,
+        },
+        /// Limit order was cancelled and its remaining amount released.
+        LimitOrderCancelled {
+            who: T::AccountId,
+            pool_id: PoolId,
+            order_id: OrderId,
+            amount: Balance,
+        },
+        /// Recurring sell was scheduled and its budget held.
+        SellScheduled {
+            who: T::AccountId,
+            schedule_id: ScheduleId,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount: Balance,
+            period: T::BlockNumber,
+            budget: Balance,
+            slippage: Permill,
+        },
+        /// Scheduled sell was executed. Schedule is finished when nothing remains.
+        ScheduleExecuted {
+            who: T::AccountId,
+            schedule_id: ScheduleId,
+            amount_in: Balance,
+            amount_out: Balance,
+            remaining: Balance,
+        },
+        /// Scheduled sell failed and is retried in the next period.
+        ScheduleExecutionFailed {
+            who: T::AccountId,
+            schedule_id: ScheduleId,
+            error: DispatchError,
+        },
+        /// Schedule was terminated after repeated failures and its remaining budget released.
+        ScheduleTerminated {
+            who: T::AccountId,
+            schedule_id: ScheduleId,
+            remaining: Balance,
+            error: DispatchError,
+        },
+        /// Schedule was cancelled and its remaining budget released.
+        ScheduleCancelled {
+            who: T::AccountId,
+            schedule_id: ScheduleId,
+            remaining: Balance,
         },
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        #[pallet::weight(<T as Config>::WeightInfo::create_pool())]
+        /// Creates a pool of given assets with initial liquidity provided by the creator.
+        ///
+        /// `fee` is the trade fee of the pool and must be between `MinTradeFee` and `MaxTradeFee`.
+        ///
+        /// Origin must be `CreatePoolOrigin`. The creator pays `CreationFee` to the treasury.
+        #[pallet::weight(<T as Config>::WeightInfo::create_pool()
+            .saturating_add(T::OnLiquidityChanged::on_liquidity_changed_weight()))]
         pub fn create_pool(
             origin: OriginFor<T>,
             asset_a: AssetId,
             amount_a: Balance,
             asset_b: AssetId,
             amount_b: Balance,
+            fee: Permill,
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
-
-            ensure!(
-                asset_a != asset_b,
-                Error::<T>::CannotCreatePoolWithSameAssets
-            );
-
-            let (pair, amounts) = if asset_a < asset_b {
-                ((asset_a, asset_b), (amount_a, amount_b))
-            } else {
-                ((asset_b, asset_a), (amount_b, amount_a))
-            };
+            let who = T::CreatePoolOrigin::ensure_origin(origin)?;
 
-            ensure!(Self::pools(&pair).is_none(), Error::<T>::PoolAlreadyExists);
+            Self::ensure_valid_fee(fee)?;
 
-            let pool_account = T::Account::create_account_id(pair)?;
+            Self::do_create_pool(
+                who,
+                asset_a,
+                amount_a,
+                asset_b,
+                amount_b,
+                PoolType::XYK,
+                fee,
+            )
+        }
 
-            let share_asset_id = T::AssetRegistry::create_share_asset(pair)?;
+        /// Creates a liquidity bootstrapping pool of given assets with initial liquidity provided
+        /// by the creator, who becomes owner of the pool.
+        ///
+        /// Weight of `asset_a` changes linearly from `initial_weight` in block `start` to
+        /// `final_weight` in block `end` and weight of `asset_b` is the rest of `MAX_WEIGHT`.
+        /// Trading is allowed only between these blocks and only the owner can withdraw
+        /// the liquidity after the sale ends.
+        ///
+        /// Origin must be `CreatePoolOrigin`. The creator pays `CreationFee` to the treasury.
+        #[pallet::weight(<T as Config>::WeightInfo::create_lbp_pool()
+            .saturating_add(T::OnLiquidityChanged::on_liquidity_changed_weight()))]
+        #[allow(clippy::too_many_arguments)]
+        pub fn create_lbp_pool(
+            origin: OriginFor<T>,
+            asset_a: AssetId,
+            amount_a: Balance,
+            asset_b: AssetId,
+            amount_b: Balance,
+            start: T::BlockNumber,
+            end: T::BlockNumber,
+            initial_weight: LBPWeight,
+            final_weight: LBPWeight,
+        ) -> DispatchResult {
+            let who = T::CreatePoolOrigin::ensure_origin(origin)?;
 
-            let shares = calculate_shares(Balance::zero(), amounts.0, Balance::zero())
-                .ok_or(Error::<T>::Math)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            let is_valid_weight = |weight: LBPWeight| weight > 0 && weight < MAX_WEIGHT;
 
-            T::Currency::transfer(asset_a, &who, &pool_account, amounts.0, true)?;
-            T::Currency::transfer(asset_b, &who, &pool_account, amounts.1, true)?;
+            ensure!(
+                now <= start
+                    && start < end
+                    && is_valid_weight(initial_weight)
+                    && is_valid_weight(final_weight),
+                Error::<T>::InvalidLBPParams
+            );
 
-            T::Currency::mint_into(share_asset_id, &who, shares)?;
+            // Weights are stored for the first asset of the pair.
+            let (initial_weight, final_weight) = if asset_a < asset_b {
+                (initial_weight, final_weight)
+            } else {
+                (MAX_WEIGHT - initial_weight, MAX_WEIGHT - final_weight)
+            };
 
-            <Pools<T>>::insert(&pair, share_asset_id);
+            let pool_type = PoolType::LBP(LBPParams {
+                owner: who.clone(),
+                start,
+                end,
+                initial_weight,
+                final_weight,
+            });
 
-            Self::deposit_event(Event::PoolCreated {
+            Self::do_create_pool(
                 who,
                 asset_a,
+                amount_a,
                 asset_b,
-                shares,
-                share_asset_id,
-            });
-
-            Ok(())
+                amount_b,
+                pool_type,
+                T::TradeFee::get(),
+            )
         }
 
-        #[pallet::weight(<T as Config>::WeightInfo::add_liquidity())]
-        pub fn add_liquidity(
+        /// Creates a stableswap pool of given assets with initial liquidity provided by the creator.
+        ///
+        /// Origin must be `CreatePoolOrigin`. The creator pays `CreationFee` to the treasury.
+        #[pallet::weight(<T as Config>::WeightInfo::create_stable_pool()
+            .saturating_add(T::OnLiquidityChanged::on_liquidity_changed_weight()))]
+        pub fn create_stable_pool(
             origin: OriginFor<T>,
             asset_a: AssetId,
-            asset_b: AssetId,
             amount_a: Balance,
-            amount_b_max_limit: Balance,
+            asset_b: AssetId,
+            amount_b: Balance,
+            amplification: Balance,
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
-
-            let pair = if asset_a < asset_b {
-                (asset_a, asset_b)
-            } else {
-                (asset_b, asset_a)
-            };
+            let who = T::CreatePoolOrigin::ensure_origin(origin)?;
 
-            let share_asset_id = Self::pools(&pair).ok_or(Error::<T>::PoolNotFound)?;
+            ensure!(
+                amplification > 0 && amplification <= MAX_AMPLIFICATION,
+                Error::<T>::InvalidAmplification
+            );
 
-            let pool_account = T::Account::create_account_id(pair)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            let pool_type = PoolType::Stable(StableParams {
+                initial_amplification: amplification,
+                final_amplification: amplification,
+                start: now,
+                end: now,
+            });
 
-            let asset_a_reserve = T::Currency::balance(asset_a, &pool_account);
-            let asset_b_reserve = T::Currency::balance(asset_b, &pool_account);
+            Self::do_create_pool(
+                who,
+                asset_a,
+                amount_a,
+                asset_b,
+                amount_b,
+                pool_type,
+                T::TradeFee::get(),
+            )
+        }
+
+        /// Changes trade fee of a pool.
+        ///
+        /// Origin must be `AuthorityOrigin`.
+        #[pallet::weight(<T as Config>::WeightInfo::update_pool_fee())]
+        pub fn update_pool_fee(
+            origin: OriginFor<T>,
+            pool_id: PoolId,
+            fee: Permill,
+        ) -> DispatchResult {
+            T::AuthorityOrigin::ensure_origin(origin)?;
+
+            Self::ensure_valid_fee(fee)?;
+
+            Pools::<T>::try_mutate(pool_id, |maybe_pool| -> DispatchResult {
+                let pool = maybe_pool.as_mut().ok_or(Error::<T>::PoolNotFound)?;
+                pool.fee = fee;
+
+                Self::deposit_event(Event::PoolFeeUpdated { pool_id, fee });
+
+                Ok(())
+            })
+        }
+
+        /// Changes amplification of a stableswap pool linearly from its current value
+        /// to `final_amplification` between blocks `start` and `end`.
+        ///
+        /// Origin must be `AuthorityOrigin`.
+        #[pallet::weight(<T as Config>::WeightInfo::update_amplification())]
+        pub fn update_amplification(
+            origin: OriginFor<T>,
+            pool_id: PoolId,
+            final_amplification: Balance,
+            start: T::BlockNumber,
+            end: T::BlockNumber,
+        ) -> DispatchResult {
+            T::AuthorityOrigin::ensure_origin(origin)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+
+            ensure!(
+                final_amplification > 0
+                    && final_amplification <= MAX_AMPLIFICATION
+                    && now <= start
+                    && start < end,
+                Error::<T>::InvalidAmplification
+            );
+
+            Pools::<T>::try_mutate(pool_id, |maybe_pool| -> DispatchResult {
+                let pool = maybe_pool.as_mut().ok_or(Error::<T>::PoolNotFound)?;
+
+                let params = match &mut pool.pool_type {
+                    PoolType::Stable(params) => params,
+                    _ => return Err(Error::<T>::NotSupportedByPoolType.into()),
+                };
+
+                // Change starts from the amplification of the pool at the time of the update.
+                let initial_amplification = Self::amplification(params)?;
+
+                *params = StableParams {
+                    initial_amplification,
+                    final_amplification,
+                    start,
+                    end,
+                };
+
+                Self::deposit_event(Event::AmplificationUpdated {
+                    pool_id,
+                    initial_amplification,
+                    final_amplification,
+                    start,
+                    end,
+                });
+
+                Ok(())
+            })
+        }
+
+        #[pallet::weight(<T as Config>::WeightInfo::add_liquidity()
+            .saturating_add(T::OnLiquidityChanged::on_liquidity_changed_weight()))]
+        pub fn add_liquidity(
+            origin: OriginFor<T>,
+            asset_a: AssetId,
+            asset_b: AssetId,
+            amount_a: Balance,
+            amount_b_max_limit: Balance,
+            min_shares_out: Balance,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let pair = if asset_a < asset_b {
+                (asset_a, asset_b)
+            } else {
+                (asset_b, asset_a)
+            };
+
+            let (pool_id, pool) = Self::pair_pool(pair).ok_or(Error::<T>::PoolNotFound)?;
+            let share_asset_id = pool.share_asset.ok_or(Error::<T>::NotSupportedByPoolType)?;
+
+            Self::ensure_can_add_liquidity(&who, &pool.pool_type)?;
+
+            let pool_account = T::Account::create_account_id(pool_id)?;
+
+            let asset_a_reserve = T::Currency::balance(asset_a, &pool_account);
+            let asset_b_reserve = T::Currency::balance(asset_b, &pool_account);
 
             let amount_b = calculate_liquidity_in(asset_a_reserve, asset_b_reserve, amount_a)
                 .ok_or(Error::<T>::Math)?;
@@ -221,11 +972,25 @@ pub mod pallet {
             let shares = calculate_shares(asset_a_reserve, amount_a, share_issuance)
                 .ok_or(Error::<T>::Math)?;
 
+            ensure!(!shares.is_zero(), Error::<T>::InsufficientLiquidity);
+            ensure!(shares >= min_shares_out, Error::<T>::InsufficientSharesOut);
+
             T::Currency::transfer(asset_a, &who, &pool_account, amount_a, true)?;
             T::Currency::transfer(asset_b, &who, &pool_account, amount_b, true)?;
 
             T::Currency::mint_into(share_asset_id, &who, shares)?;
 
+            T::OnLiquidityChanged::on_liquidity_added(
+                &who,
+                &[asset_a, asset_b],
+                &[amount_a, amount_b],
+                shares,
+                &[
+                    T::Currency::balance(asset_a, &pool_account),
+                    T::Currency::balance(asset_b, &pool_account),
+                ],
+            );
+
             Self::deposit_event(Event::LiquidityAdded {
                 who,
                 asset_a,
@@ -237,53 +1002,93 @@ pub mod pallet {
             Ok(())
         }
 
-        #[pallet::weight(<T as Config>::WeightInfo::remove_liquidity())]
+        #[pallet::weight(<T as Config>::WeightInfo::remove_liquidity()
+            .saturating_add(T::OnLiquidityChanged::on_liquidity_changed_weight()))]
         pub fn remove_liquidity(
             origin: OriginFor<T>,
             asset_a: AssetId,
             asset_b: AssetId,
             liquidity_amount: Balance,
+            min_amount_a: Balance,
+            min_amount_b: Balance,
+            deadline: Option<T::BlockNumber>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
             let pair = if asset_a < asset_b {
                 (asset_a, asset_b)
             } else {
                 (asset_b, asset_a)
             };
 
-            let share_asset_id = Self::pools(&pair).ok_or(Error::<T>::PoolNotFound)?;
+            let (pool_id, pool) = Self::pair_pool(pair).ok_or(Error::<T>::PoolNotFound)?;
+            let share_asset_id = pool.share_asset.ok_or(Error::<T>::NotSupportedByPoolType)?;
 
-            let pool_account = T::Account::create_account_id(pair)?;
+            Self::ensure_can_remove_liquidity(&who, &pool.pool_type)?;
+
+            let pool_account = T::Account::create_account_id(pool_id)?;
 
             let asset_a_reserve = T::Currency::balance(asset_a, &pool_account);
             let asset_b_reserve = T::Currency::balance(asset_b, &pool_account);
             let share_issuance = T::Currency::total_issuance(share_asset_id);
 
-            let (amount_a, amount_b) = calculate_liquidity_out(
+            // Last provider has no one to protect, so nothing is left in the pool. Locked
+            // `MinimumLiquidity` is never withdrawn, so pools holding it are never destroyed.
+            let is_last_provider = liquidity_amount == share_issuance;
+            let withdraw_fee = if is_last_provider {
+                Permill::zero()
+            } else {
+                T::WithdrawFee::get()
+            };
+
+            let ((amount_a, amount_b), (fee_a, fee_b)) = calculate_liquidity_out_with_fee(
                 asset_a_reserve,
                 asset_b_reserve,
                 liquidity_amount,
                 share_issuance,
+                withdraw_fee,
             )
             .ok_or(Error::<T>::Math)?;
 
-            T::Currency::transfer(asset_a, &pool_account, &who, amount_a, true)?;
-            T::Currency::transfer(asset_b, &pool_account, &who, amount_b, true)?;
+            ensure!(
+                amount_a >= min_amount_a && amount_b >= min_amount_b,
+                Error::<T>::InsufficientAmountOut
+            );
+
+            T::Currency::transfer(asset_a, &pool_account, &who, amount_a, !is_last_provider)?;
+            T::Currency::transfer(asset_b, &pool_account, &who, amount_b, !is_last_provider)?;
 
             T::Currency::burn_from(share_asset_id, &who, liquidity_amount)?;
 
+            T::OnLiquidityChanged::on_liquidity_removed(
+                &who,
+                &[asset_a, asset_b],
+                &[amount_a, amount_b],
+                liquidity_amount,
+                &[
+                    T::Currency::balance(asset_a, &pool_account),
+                    T::Currency::balance(asset_b, &pool_account),
+                ],
+            );
+
             Self::deposit_event(Event::LiquidityRemoved {
                 who: who.clone(),
                 asset_a,
                 asset_b,
                 shares: liquidity_amount,
+                amount_a,
+                amount_b,
+                fee_a,
+                fee_b,
             });
-            let liquidity_left = share_issuance
-                .checked_sub(liquidity_amount)
-                .ok_or(Error::<T>::Math)?;
 
-            if liquidity_left == 0 {
-                <Pools<T>>::remove(&pair);
+            if is_last_provider {
+                <Pools<T>>::remove(pool_id);
+                <DynamicFees<T>>::remove(pool_id);
+                <PairPools<T>>::remove(&pair);
+                let _ = <Oracles<T>>::clear_prefix(&pair, u32::MAX, None);
 
                 Self::deposit_event(Event::PoolDestroyed {
                     who,
@@ -296,97 +1101,2009 @@ pub mod pallet {
             Ok(())
         }
 
-        #[pallet::weight(<T as Config>::WeightInfo::sell())]
-        pub fn sell(
+        /// Adds liquidity in `asset_a` only.
+        ///
+        /// Part of the amount is swapped to `asset_b` inside the pool, paying the current trade
+        /// fee of the pool and raising it as a trade would, and the rest is added together with the swapped amount in ratio of reserves.
+        #[pallet::weight(<T as Config>::WeightInfo::add_liquidity_single_asset()
+            .saturating_add(T::OnLiquidityChanged::on_liquidity_changed_weight()))]
+        pub fn add_liquidity_single_asset(
             origin: OriginFor<T>,
-            asset_in: AssetId,
-            asset_out: AssetId,
-            amount_in: Balance,
-            min_limit: Balance,
+            asset_a: AssetId,
+            asset_b: AssetId,
+            amount_a: Balance,
+            min_shares_out: Balance,
+            deadline: Option<T::BlockNumber>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            let pair = if asset_in < asset_out {
-                (asset_in, asset_out)
-            } else {
-                (asset_out, asset_in)
-            };
 
-            let _ = Self::pools(&pair).ok_or(Error::<T>::PoolNotFound)?;
+            Self::ensure_not_expired(deadline)?;
 
-            let pool_account = T::Account::create_account_id(pair)?;
+            let pair = Self::pair(asset_a, asset_b);
 
-            let asset_in_reserve = T::Currency::balance(asset_in, &pool_account);
-            let asset_out_reserve = T::Currency::balance(asset_out, &pool_account);
+            let (pool_id, pool) = Self::pair_pool(pair).ok_or(Error::<T>::PoolNotFound)?;
+            let share_asset_id = pool.share_asset.ok_or(Error::<T>::NotSupportedByPoolType)?;
 
-            let amount_out = calculate_out_given_in(asset_in_reserve, asset_out_reserve, amount_in)
-                .ok_or(Error::<T>::Math)?;
+            ensure!(
+                matches!(pool.pool_type, PoolType::XYK),
+                Error::<T>::NotSupportedByPoolType
+            );
 
-            let fee = FixedU128::from(T::TradeFee::get())
-                .checked_mul_int(amount_out)
-                .ok_or(Error::<T>::Math)?;
+            let pool_account = T::Account::create_account_id(pool_id)?;
+
+            let asset_a_reserve = T::Currency::balance(asset_a, &pool_account);
+            let asset_b_reserve = T::Currency::balance(asset_b, &pool_account);
+            let share_issuance = T::Currency::total_issuance(share_asset_id);
 
-            let amount_out_with_fee = amount_out.checked_sub(fee).ok_or(Error::<T>::Math)?;
+            let (shares, swap_amount, swap_fee) = calculate_shares_for_single_asset(
+                asset_a_reserve,
+                asset_b_reserve,
+                amount_a,
+                share_issuance,
+                Self::current_fee(pool_id, pool.fee),
+            )
+            .ok_or(Error::<T>::Math)?;
 
-            ensure!(amount_out_with_fee >= min_limit, Error::<T>::Limit);
+            ensure!(!shares.is_zero(), Error::<T>::InsufficientLiquidity);
+            ensure!(shares >= min_shares_out, Error::<T>::InsufficientSharesOut);
 
-            T::Currency::transfer(asset_in, &who, &pool_account, amount_in, true)?;
-            T::Currency::transfer(asset_out, &pool_account, &who, amount_out_with_fee, true)?;
+            T::Currency::transfer(asset_a, &who, &pool_account, amount_a, true)?;
 
-            Self::deposit_event(Event::<T>::SwapExecuted {
+            T::Currency::mint_into(share_asset_id, &who, shares)?;
+
+            let protocol_fee = T::ProtocolFee::get().mul_floor(swap_fee);
+            if !protocol_fee.is_zero() {
+                T::Currency::transfer(
+                    asset_b,
+                    &pool_account,
+                    &T::ProtocolFeeReceiver::get(),
+                    protocol_fee,
+                    true,
+                )?;
+            }
+
+            Self::update_dynamic_fee(
+                pool_id,
+                Permill::from_rational(swap_amount, asset_a_reserve),
+            );
+
+            T::OnLiquidityChanged::on_liquidity_added(
+                &who,
+                &[asset_a, asset_b],
+                &[amount_a, Balance::zero()],
+                shares,
+                &[
+                    T::Currency::balance(asset_a, &pool_account),
+                    T::Currency::balance(asset_b, &pool_account),
+                ],
+            );
+
+            Self::deposit_event(Event::LiquidityAddedSingleAsset {
                 who,
-                asset_in,
-                asset_out,
-                amount_in,
-                amount_out: amount_out_with_fee,
-                fee,
+                asset_a,
+                asset_b,
+                amount_a,
+                shares,
+                protocol_fee,
             });
 
             Ok(())
         }
 
-        #[pallet::weight(<T as Config>::WeightInfo::buy())]
-        pub fn buy(
+        /// Removes liquidity in `asset_a` only.
+        ///
+        /// Amount of `asset_b` corresponding to the shares is swapped to `asset_a` inside
+        /// the pool, paying the current trade fee of the pool and raising it as a trade would.
+        /// Withdraw fee is applied as in `remove_liquidity`.
+        #[pallet::weight(<T as Config>::WeightInfo::remove_liquidity_single_asset()
+            .saturating_add(T::OnLiquidityChanged::on_liquidity_changed_weight()))]
+        pub fn remove_liquidity_single_asset(
             origin: OriginFor<T>,
-            asset_out: AssetId,
-            asset_in: AssetId,
-            amount_out: Balance,
-            max_limit: Balance,
+            asset_a: AssetId,
+            asset_b: AssetId,
+            liquidity_amount: Balance,
+            min_amount_a: Balance,
+            deadline: Option<T::BlockNumber>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            let pair = if asset_in < asset_out {
-                (asset_in, asset_out)
-            } else {
-                (asset_out, asset_in)
+
+            Self::ensure_not_expired(deadline)?;
+
+            let pair = Self::pair(asset_a, asset_b);
+
+            let (pool_id, pool) = Self::pair_pool(pair).ok_or(Error::<T>::PoolNotFound)?;
+            let share_asset_id = pool.share_asset.ok_or(Error::<T>::NotSupportedByPoolType)?;
+
+            ensure!(
+                matches!(pool.pool_type, PoolType::XYK),
+                Error::<T>::NotSupportedByPoolType
+            );
+
+            let pool_account = T::Account::create_account_id(pool_id)?;
+
+            let asset_a_reserve = T::Currency::balance(asset_a, &pool_account);
+            let asset_b_reserve = T::Currency::balance(asset_b, &pool_account);
+            let share_issuance = T::Currency::total_issuance(share_asset_id);
+
+            // Other asset cannot be swapped once the whole liquidity is removed.
+            ensure!(
+                liquidity_amount < share_issuance,
+                Error::<T>::InsufficientLiquidity
+            );
+
+            let (amount_a, swap_amount, swap_fee) = calculate_single_asset_liquidity_out(
+                asset_a_reserve,
+                asset_b_reserve,
+                liquidity_amount,
+                share_issuance,
+                T::WithdrawFee::get(),
+                Self::current_fee(pool_id, pool.fee),
+            )
+            .ok_or(Error::<T>::Math)?;
+
+            ensure!(amount_a >= min_amount_a, Error::<T>::InsufficientAmountOut);
+
+            T::Currency::transfer(asset_a, &pool_account, &who, amount_a, true)?;
+
+            T::Currency::burn_from(share_asset_id, &who, liquidity_amount)?;
+
+            let protocol_fee = T::ProtocolFee::get().mul_floor(swap_fee);
+            if !protocol_fee.is_zero() {
+                T::Currency::transfer(
+                    asset_a,
+                    &pool_account,
+                    &T::ProtocolFeeReceiver::get(),
+                    protocol_fee,
+                    true,
+                )?;
+            }
+
+            // Withdrawn amount of `asset_b` is swapped back into the pool.
+            Self::update_dynamic_fee(
+                pool_id,
+                Permill::from_rational(swap_amount, asset_b_reserve.saturating_sub(swap_amount)),
+            );
+
+            T::OnLiquidityChanged::on_liquidity_removed(
+                &who,
+                &[asset_a, asset_b],
+                &[amount_a, Balance::zero()],
+                liquidity_amount,
+                &[
+                    T::Currency::balance(asset_a, &pool_account),
+                    T::Currency::balance(asset_b, &pool_account),
+                ],
+            );
+
+            Self::deposit_event(Event::LiquidityRemovedSingleAsset {
+                who,
+                asset_a,
+                asset_b,
+                shares: liquidity_amount,
+                amount_a,
+                protocol_fee,
+            });
+
+            Ok(())
+        }
+
+        /// Creates a stableswap pool of three or more assets with initial liquidity provided
+        /// by the creator.
+        ///
+        /// Unlike pools of pairs, the pool is identified by its id only.
+        ///
+        /// Origin must be `CreatePoolOrigin`. The creator pays `CreationFee` to the treasury.
+        #[pallet::weight(<T as Config>::WeightInfo::create_multi_asset_pool(assets.len() as u32)
+            .saturating_add(T::OnLiquidityChanged::on_liquidity_changed_weight()))]
+        pub fn create_multi_asset_pool(
+            origin: OriginFor<T>,
+            assets: BoundedVec<(AssetId, Balance), T::MaxAssetsInPool>,
+            amplification: Balance,
+        ) -> DispatchResult {
+            let who = T::CreatePoolOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                amplification > 0 && amplification <= MAX_AMPLIFICATION,
+                Error::<T>::InvalidAmplification
+            );
+
+            let mut assets = assets.into_inner();
+            assets.sort_by_key(|(asset, _)| *asset);
+
+            ensure!(
+                assets.len() > 2 && assets.windows(2).all(|w| w[0].0 != w[1].0),
+                Error::<T>::InvalidAssets
+            );
+
+            let pool_id = Self::next_pool_id()?;
+            let pool_account = T::Account::create_account_id(pool_id)?;
+
+            let creation_fee = T::CreationFee::get();
+            if !creation_fee.is_zero() {
+                T::Currency::transfer(
+                    T::CreationFeeAsset::get(),
+                    &who,
+                    &T::TreasuryAccount::get(),
+                    creation_fee,
+                    true,
+                )?;
+            }
+
+            for &(asset, amount) in assets.iter() {
+                T::Currency::transfer(asset, &who, &pool_account, amount, true)?;
+            }
+
+            let (pool_assets, amounts): (Vec<AssetId>, Vec<Balance>) = assets.into_iter().unzip();
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let pool_type = PoolType::Stable(StableParams {
+                initial_amplification: amplification,
+                final_amplification: amplification,
+                start: now,
+                end: now,
+            });
+
+            let (share_asset_id, shares) = Self::initialize_pool(
+                &who,
+                pool_id,
+                &pool_assets,
+                &pool_account,
+                &amounts,
+                pool_type,
+                T::TradeFee::get(),
+            )?;
+
+            T::OnLiquidityChanged::on_liquidity_added(
+                &who,
+                &pool_assets,
+                &amounts,
+                shares,
+                &Self::multi_asset_reserves(&pool_assets, &pool_account),
+            );
+
+            Self::deposit_event(Event::MultiAssetPoolCreated {
+                who,
+                pool_id,
+                assets: pool_assets,
+                shares,
+                share_asset_id,
+            });
+
+            Ok(())
+        }
+
+        /// Adds liquidity to a stableswap pool in any subset of its assets.
+        ///
+        /// Shares are minted in proportion to the growth of the invariant of the pool.
+        /// Deposits which move the pool away from balance pay trade fee of the pool on the imbalance.
+        #[pallet::weight(<T as Config>::WeightInfo::add_liquidity_multi_asset(
+            amounts.len() as u32
+        ).saturating_add(T::OnLiquidityChanged::on_liquidity_changed_weight()))]
+        pub fn add_liquidity_multi_asset(
+            origin: OriginFor<T>,
+            pool_id: PoolId,
+            amounts: BoundedVec<(AssetId, Balance), T::MaxAssetsInPool>,
+            min_shares_out: Balance,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let pool = Self::pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            let amplification = match &pool.pool_type {
+                PoolType::Stable(params) => Self::amplification(params)?,
+                _ => return Err(Error::<T>::NotSupportedByPoolType.into()),
             };
+            let share_asset_id = pool.share_asset.ok_or(Error::<T>::NotSupportedByPoolType)?;
 
-            let _ = Self::pools(&pair).ok_or(Error::<T>::PoolNotFound)?;
+            let pool_account = T::Account::create_account_id(pool_id)?;
 
-            let pool_account = T::Account::create_account_id(pair)?;
+            let initial_reserves = Self::multi_asset_reserves(&pool.assets, &pool_account);
+            let mut updated_reserves = initial_reserves.clone();
+            for &(asset, amount) in amounts.iter() {
+                let index = Self::asset_index(&pool.assets, asset)?;
+                updated_reserves[index] = updated_reserves[index]
+                    .checked_add(amount)
+                    .ok_or(Error::<T>::Math)?;
+            }
 
-            let asset_in_reserve = T::Currency::balance(asset_in, &pool_account);
-            let asset_out_reserve = T::Currency::balance(asset_out, &pool_account);
+            let share_issuance = T::Currency::total_issuance(share_asset_id);
 
-            let amount_in = calculate_in_given_out(asset_out_reserve, asset_in_reserve, amount_out)
-                .ok_or(Error::<T>::Math)?;
+            let shares = stableswap::calculate_shares(
+                &initial_reserves,
+                &updated_reserves,
+                amplification,
+                share_issuance,
+                pool.fee,
+            )
+            .ok_or(Error::<T>::Math)?;
 
-            let fee = FixedU128::from(T::TradeFee::get())
-                .checked_mul_int(amount_in)
-                .ok_or(Error::<T>::Math)?;
+            ensure!(!shares.is_zero(), Error::<T>::InsufficientLiquidity);
+            ensure!(shares >= min_shares_out, Error::<T>::InsufficientSharesOut);
 
-            let amount_in_with_fee = amount_out.checked_sub(fee).ok_or(Error::<T>::Math)?;
+            for &(asset, amount) in amounts.iter() {
+                T::Currency::transfer(asset, &who, &pool_account, amount, true)?;
+            }
 
-            ensure!(amount_in_with_fee <= max_limit, Error::<T>::Limit);
+            T::Currency::mint_into(share_asset_id, &who, shares)?;
 
-            T::Currency::transfer(asset_in, &who, &pool_account, amount_in_with_fee, true)?;
-            T::Currency::transfer(asset_out, &pool_account, &who, amount_out, true)?;
+            let (assets, added_amounts): (Vec<AssetId>, Vec<Balance>) =
+                amounts.iter().copied().unzip();
 
-            Self::deposit_event(Event::<T>::SwapExecuted {
+            T::OnLiquidityChanged::on_liquidity_added(
+                &who,
+                &assets,
+                &added_amounts,
+                shares,
+                &Self::multi_asset_reserves(&assets, &pool_account),
+            );
+
+            Self::deposit_event(Event::LiquidityAddedMultiAsset {
                 who,
-                asset_in,
-                asset_out,
-                amount_in: amount_in_with_fee,
-                amount_out,
-                fee,
+                pool_id,
+                amounts: amounts.into_inner(),
+                shares,
+            });
+
+            Ok(())
+        }
+
+        /// Removes liquidity from a stableswap pool in all of its assets, in ratio of reserves.
+        ///
+        /// `WithdrawFee` is kept in the pool, except when the last provider withdraws,
+        /// which destroys the pool.
+        #[pallet::weight(<T as Config>::WeightInfo::remove_liquidity_multi_asset(
+            T::MaxAssetsInPool::get()
+        ).saturating_add(T::OnLiquidityChanged::on_liquidity_changed_weight()))]
+        pub fn remove_liquidity_multi_asset(
+            origin: OriginFor<T>,
+            pool_id: PoolId,
+            liquidity_amount: Balance,
+            min_amounts_out: BoundedVec<(AssetId, Balance), T::MaxAssetsInPool>,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let pool = Self::pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            ensure!(
+                matches!(pool.pool_type, PoolType::Stable(_)),
+                Error::<T>::NotSupportedByPoolType
+            );
+            let share_asset_id = pool.share_asset.ok_or(Error::<T>::NotSupportedByPoolType)?;
+
+            let pool_account = T::Account::create_account_id(pool_id)?;
+
+            let reserves = Self::multi_asset_reserves(&pool.assets, &pool_account);
+            let share_issuance = T::Currency::total_issuance(share_asset_id);
+
+            // Last provider has no one to protect, so nothing is left in the pool. Locked
+            // `MinimumLiquidity` is never withdrawn, so pools holding it are never destroyed.
+            let is_last_provider = liquidity_amount == share_issuance;
+            let withdraw_fee = if is_last_provider {
+                Permill::zero()
+            } else {
+                T::WithdrawFee::get()
+            };
+
+            let liquidity_out = stableswap::calculate_liquidity_out_with_fee(
+                &reserves,
+                liquidity_amount,
+                share_issuance,
+                withdraw_fee,
+            )
+            .ok_or(Error::<T>::Math)?;
+
+            for &(asset, min_amount) in min_amounts_out.iter() {
+                let (amount, _) = liquidity_out[Self::asset_index(&pool.assets, asset)?];
+                ensure!(amount >= min_amount, Error::<T>::InsufficientAmountOut);
+            }
+
+            for (&asset, &(amount, _)) in pool.assets.iter().zip(liquidity_out.iter()) {
+                T::Currency::transfer(asset, &pool_account, &who, amount, !is_last_provider)?;
+            }
+
+            T::Currency::burn_from(share_asset_id, &who, liquidity_amount)?;
+
+            let (amounts, fees): (Vec<_>, Vec<_>) = pool
+                .assets
+                .iter()
+                .zip(liquidity_out.into_iter())
+                .map(|(&asset, (amount, fee))| ((asset, amount), (asset, fee)))
+                .unzip();
+
+            T::OnLiquidityChanged::on_liquidity_removed(
+                &who,
+                &pool.assets,
+                &amounts
+                    .iter()
+                    .map(|&(_, amount)| amount)
+                    .collect::<Vec<_>>(),
+                liquidity_amount,
+                &Self::multi_asset_reserves(&pool.assets, &pool_account),
+            );
+
+            Self::deposit_event(Event::LiquidityRemovedMultiAsset {
+                who: who.clone(),
+                pool_id,
+                shares: liquidity_amount,
+                amounts,
+                fees,
+            });
+
+            if is_last_provider {
+                <Pools<T>>::remove(pool_id);
+                <DynamicFees<T>>::remove(pool_id);
+                if let &[asset_a, asset_b] = pool.assets.as_slice() {
+                    <PairPools<T>>::remove((asset_a, asset_b));
+                    let _ = <Oracles<T>>::clear_prefix((asset_a, asset_b), u32::MAX, None);
+                }
+
+                Self::deposit_event(Event::MultiAssetPoolDestroyed {
+                    who,
+                    pool_id,
+                    share_asset_id,
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Creates a concentrated liquidity pool of given assets, without liquidity.
+        ///
+        /// `sqrt_price` is square root of the initial price of `asset_a` denominated in `asset_b`,
+        /// in Q64.64 fixed point. Liquidity is provided by anyone in positions, in ranges between
+        /// ticks which are multiples of `tick_spacing`.
+        ///
+        /// Origin must be `CreatePoolOrigin`. The creator pays `CreationFee` to the treasury.
+        #[pallet::weight(<T as Config>::WeightInfo::create_concentrated_pool())]
+        pub fn create_concentrated_pool(
+            origin: OriginFor<T>,
+            asset_a: AssetId,
+            asset_b: AssetId,
+            tick_spacing: Tick,
+            sqrt_price: SqrtPrice,
+            fee: Permill,
+        ) -> DispatchResult {
+            let who = T::CreatePoolOrigin::ensure_origin(origin)?;
+
+            Self::ensure_valid_fee(fee)?;
+
+            ensure!(
+                asset_a != asset_b,
+                Error::<T>::CannotCreatePoolWithSameAssets
+            );
+            ensure!(
+                tick_spacing > 0 && tick_spacing <= MAX_TICK,
+                Error::<T>::InvalidTick
+            );
+
+            let pair = Self::pair(asset_a, asset_b);
+
+            ensure!(
+                !PairPools::<T>::contains_key(pair),
+                Error::<T>::PoolAlreadyExists
+            );
+
+            // Price is stored for the first asset of the pair.
+            let pool_sqrt_price = if asset_a == pair.0 {
+                sqrt_price
+            } else {
+                concentrated::calculate_inverse_sqrt_price(sqrt_price)
+                    .ok_or(Error::<T>::InvalidTick)?
+            };
+            let tick =
+                concentrated::tick_at_sqrt_price(pool_sqrt_price).ok_or(Error::<T>::InvalidTick)?;
+
+            let pool_assets: PoolAssetsOf<T> = [pair.0, pair.1]
+                .to_vec()
+                .try_into()
+                .map_err(|_| Error::<T>::InvalidAssets)?;
+
+            let pool_id = Self::next_pool_id()?;
+
+            let creation_fee = T::CreationFee::get();
+            if !creation_fee.is_zero() {
+                T::Currency::transfer(
+                    T::CreationFeeAsset::get(),
+                    &who,
+                    &T::TreasuryAccount::get(),
+                    creation_fee,
+                    true,
+                )?;
+            }
+
+            PairPools::<T>::insert(pair, pool_id);
+            Pools::<T>::insert(
+                pool_id,
+                PoolInfo {
+                    assets: pool_assets,
+                    share_asset: None,
+                    pool_type: PoolType::Concentrated(ConcentratedParams { tick_spacing }),
+                    fee,
+                },
+            );
+            ConcentratedPools::<T>::insert(
+                pool_id,
+                ConcentratedState {
+                    sqrt_price: pool_sqrt_price,
+                    tick,
+                    liquidity: 0,
+                    fee_growth_a: 0,
+                    fee_growth_b: 0,
+                    ticks: 0,
+                },
+            );
+
+            Self::deposit_event(Event::ConcentratedPoolCreated {
+                who,
+                pool_id,
+                asset_a,
+                asset_b,
+                tick_spacing,
+                sqrt_price,
+                fee,
+            });
+
+            Ok(())
+        }
+
+        /// Provides liquidity to a concentrated liquidity pool in the range of prices between
+        /// `tick_lower` and `tick_upper`, in a new position owned by the caller.
+        ///
+        /// Liquidity of the position is the highest which can be provided by at most `amount_a`
+        /// of the first asset of the pair and `amount_b` of the second one. Range above the current
+        /// price needs only the first asset and range below it only the second one.
+        ///
+        /// Ticks of the range which no position ends at yet count towards `MaxTicksPerPool`.
+        #[pallet::weight(<T as Config>::WeightInfo::mint_position())]
+        #[allow(clippy::too_many_arguments)]
+        pub fn mint_position(
+            origin: OriginFor<T>,
+            pool_id: PoolId,
+            tick_lower: Tick,
+            tick_upper: Tick,
+            amount_a: Balance,
+            amount_b: Balance,
+            min_liquidity: Liquidity,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let pool = Self::pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            let params = match &pool.pool_type {
+                PoolType::Concentrated(params) => params,
+                _ => return Err(Error::<T>::NotSupportedByPoolType.into()),
+            };
+
+            Self::ensure_valid_ticks(params, tick_lower, tick_upper)?;
+
+            let state = Self::concentrated_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            let (sqrt_price_lower, sqrt_price_upper) =
+                Self::range_sqrt_prices(tick_lower, tick_upper)?;
+
+            let liquidity = concentrated::calculate_liquidity_for_amounts(
+                state.sqrt_price,
+                sqrt_price_lower,
+                sqrt_price_upper,
+                amount_a,
+                amount_b,
+            )
+            .ok_or(Error::<T>::Math)?;
+
+            ensure!(
+                !liquidity.is_zero() && liquidity >= min_liquidity,
+                Error::<T>::InsufficientSharesOut
+            );
+
+            // Amounts are rounded up to prevent value leaking from the pool
+            let (amount_a, amount_b) = concentrated::calculate_position_amounts(
+                state.sqrt_price,
+                sqrt_price_lower,
+                sqrt_price_upper,
+                liquidity,
+                true,
+            )
+            .ok_or(Error::<T>::Math)?;
+
+            let pool_account = T::Account::create_account_id(pool_id)?;
+
+            T::Currency::transfer(pool.assets[0], &who, &pool_account, amount_a, true)?;
+            T::Currency::transfer(pool.assets[1], &who, &pool_account, amount_b, true)?;
+
+            let position_id = NextPositionId::<T>::try_mutate(|next_id| {
+                let position_id = *next_id;
+                *next_id = next_id.checked_add(1).ok_or(Error::<T>::Math)?;
+                Ok::<PositionId, DispatchError>(position_id)
+            })?;
+
+            let mut position = Position {
+                owner: who.clone(),
+                pool_id,
+                tick_lower,
+                tick_upper,
+                liquidity: 0,
+                fee_growth_inside_a: 0,
+                fee_growth_inside_b: 0,
+                fees_owed_a: 0,
+                fees_owed_b: 0,
+            };
+
+            Self::update_position(
+                &mut position,
+                i128::try_from(liquidity).map_err(|_| Error::<T>::Math)?,
+            )?;
+
+            Positions::<T>::insert(position_id, position);
+
+            Self::deposit_event(Event::PositionMinted {
+                who,
+                pool_id,
+                position_id,
+                tick_lower,
+                tick_upper,
+                liquidity,
+                amount_a,
+                amount_b,
+            });
+
+            Ok(())
+        }
+
+        /// Withdraws `liquidity` of a position in both assets of the pool, according to
+        /// the current price.
+        ///
+        /// Fees earned by the position stay in it until collected, except when the whole liquidity
+        /// is withdrawn, which collects the fees and removes the position.
+        #[pallet::weight(<T as Config>::WeightInfo::burn_position())]
+        pub fn burn_position(
+            origin: OriginFor<T>,
+            position_id: PositionId,
+            liquidity: Liquidity,
+            min_amount_a: Balance,
+            min_amount_b: Balance,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let mut position = Self::position(position_id).ok_or(Error::<T>::PositionNotFound)?;
+
+            ensure!(position.owner == who, Error::<T>::NotPositionOwner);
+            ensure!(
+                liquidity <= position.liquidity,
+                Error::<T>::InsufficientLiquidity
+            );
+
+            let pool = Self::pools(position.pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            let state =
+                Self::concentrated_pool(position.pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            let (sqrt_price_lower, sqrt_price_upper) =
+                Self::range_sqrt_prices(position.tick_lower, position.tick_upper)?;
+
+            let (amount_a, amount_b) = concentrated::calculate_position_amounts(
+                state.sqrt_price,
+                sqrt_price_lower,
+                sqrt_price_upper,
+                liquidity,
+                false,
+            )
+            .ok_or(Error::<T>::Math)?;
+
+            ensure!(
+                amount_a >= min_amount_a && amount_b >= min_amount_b,
+                Error::<T>::InsufficientAmountOut
+            );
+
+            Self::update_position(
+                &mut position,
+                i128::try_from(liquidity)
+                    .map_err(|_| Error::<T>::Math)?
+                    .checked_neg()
+                    .ok_or(Error::<T>::Math)?,
+            )?;
+
+            let pool_account = T::Account::create_account_id(position.pool_id)?;
+
+            // Last position may withdraw whole reserves of the pool.
+            T::Currency::transfer(pool.assets[0], &pool_account, &who, amount_a, false)?;
+            T::Currency::transfer(pool.assets[1], &pool_account, &who, amount_b, false)?;
+
+            Self::deposit_event(Event::PositionBurned {
+                who: who.clone(),
+                position_id,
+                liquidity,
+                amount_a,
+                amount_b,
+            });
+
+            if position.liquidity.is_zero() {
+                Self::collect_position_fees(&who, position_id, &mut position)?;
+                Positions::<T>::remove(position_id);
+            } else {
+                Positions::<T>::insert(position_id, position);
+            }
+
+            Ok(())
+        }
+
+        /// Transfers fees earned by a position to its owner.
+        #[pallet::weight(<T as Config>::WeightInfo::collect_fees())]
+        pub fn collect_fees(origin: OriginFor<T>, position_id: PositionId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut position = Self::position(position_id).ok_or(Error::<T>::PositionNotFound)?;
+
+            ensure!(position.owner == who, Error::<T>::NotPositionOwner);
+
+            Self::update_position(&mut position, 0)?;
+            Self::collect_position_fees(&who, position_id, &mut position)?;
+
+            Positions::<T>::insert(position_id, position);
+
+            Ok(())
+        }
+
+        #[pallet::weight(<T as Config>::WeightInfo::sell()
+            .saturating_add(Pallet::<T>::max_swap_ticks_weight())
+            .saturating_add(T::OnTrade::on_trade_weight()))]
+        pub fn sell(
+            origin: OriginFor<T>,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_in: Balance,
+            min_limit: Balance,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let trade = Self::calculate_sell(asset_in, asset_out, amount_in)?;
+
+            ensure!(trade.amount_out >= min_limit, Error::<T>::Limit);
+
+            Self::execute_trade(&who, &trade, &who, &who)?;
+
+            // Ticks are charged by the number read by the swap, not the maximum.
+            Ok(Some(
+                <T as Config>::WeightInfo::sell()
+                    .saturating_add(Self::swap_ticks_weight(&[trade]))
+                    .saturating_add(T::OnTrade::on_trade_weight()),
+            )
+            .into())
+        }
+
+        #[pallet::weight(<T as Config>::WeightInfo::buy()
+            .saturating_add(Pallet::<T>::max_swap_ticks_weight())
+            .saturating_add(T::OnTrade::on_trade_weight()))]
+        pub fn buy(
+            origin: OriginFor<T>,
+            asset_out: AssetId,
+            asset_in: AssetId,
+            amount_out: Balance,
+            max_limit: Balance,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let trade = Self::calculate_buy(asset_out, asset_in, amount_out)?;
+
+            ensure!(trade.amount_in <= max_limit, Error::<T>::Limit);
+
+            Self::execute_trade(&who, &trade, &who, &who)?;
+
+            // Ticks are charged by the number read by the swap, not the maximum.
+            Ok(Some(
+                <T as Config>::WeightInfo::buy()
+                    .saturating_add(Self::swap_ticks_weight(&[trade]))
+                    .saturating_add(T::OnTrade::on_trade_weight()),
+            )
+            .into())
+        }
+
+        /// Sells `amount_in` of the first asset of the route for the last asset of the route,
+        /// trading through the pools of consecutive assets of the route.
+        ///
+        /// Either all trades of the route are executed or none is.
+        /// Borrows `amount_out` of `asset_out` from the pool of `asset_in` and `asset_out`.
+        ///
+        /// The amount is sent to the caller first, then `FlashSwapHandler` is called to use it
+        /// and repay the pool. The call fails and nothing is borrowed unless the product
+        /// of reserves, without trade fee paid from the repaid amounts, does not decrease.
+        /// Liquidity of the pool cannot be added or removed meanwhile.
+        ///
+        /// As in trades, protocol part of the fee is transferred to `ProtocolFeeReceiver`,
+        /// the fee of the pool is raised by the price impact of the repayment and `OnTrade`
+        /// is called with the amounts exchanged and the fee paid in `asset_in`.
+        #[pallet::weight(<T as Config>::WeightInfo::flash_swap()
+            .saturating_add(T::FlashSwapHandler::on_flash_swap_weight())
+            .saturating_add(T::OnTrade::on_trade_weight()))]
+        pub fn flash_swap(
+            origin: OriginFor<T>,
+            asset_out: AssetId,
+            asset_in: AssetId,
+            amount_out: Balance,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let pair = Self::pair(asset_in, asset_out);
+
+            let (pool_id, pool) = Self::pair_pool(pair).ok_or(Error::<T>::PoolNotFound)?;
+
+            ensure!(
+                matches!(pool.pool_type, PoolType::XYK),
+                Error::<T>::NotSupportedByPoolType
+            );
+            let share_asset_id = pool.share_asset.ok_or(Error::<T>::NotSupportedByPoolType)?;
+
+            let pool_account = T::Account::create_account_id(pool_id)?;
+
+            let in_reserve = T::Currency::balance(asset_in, &pool_account);
+            let out_reserve = T::Currency::balance(asset_out, &pool_account);
+            let share_issuance = T::Currency::total_issuance(share_asset_id);
+
+            ensure!(amount_out < out_reserve, Error::<T>::InsufficientLiquidity);
+
+            T::Currency::transfer(asset_out, &pool_account, &who, amount_out, true)?;
+
+            T::FlashSwapHandler::on_flash_swap(
+                &who,
+                &pool_account,
+                asset_in,
+                asset_out,
+                amount_out,
+            )?;
+
+            ensure!(
+                T::Currency::total_issuance(share_asset_id) == share_issuance,
+                Error::<T>::FlashSwapNotRepaid
+            );
+
+            let in_reserve_after = T::Currency::balance(asset_in, &pool_account);
+            let out_reserve_after = T::Currency::balance(asset_out, &pool_account);
+
+            let fee = Self::current_fee(pool_id, pool.fee);
+            let is_repaid = is_flash_swap_repaid(
+                in_reserve,
+                out_reserve,
+                in_reserve_after,
+                out_reserve_after,
+                amount_out,
+                fee,
+            )
+            .ok_or(Error::<T>::Math)?;
+
+            ensure!(is_repaid, Error::<T>::FlashSwapNotRepaid);
+
+            let (fee_in, fee_out) = calculate_flash_swap_fees(
+                in_reserve,
+                out_reserve,
+                in_reserve_after,
+                out_reserve_after,
+                amount_out,
+                fee,
+            )
+            .ok_or(Error::<T>::Math)?;
+
+            // Protocol part of the fee is excluded from the product of reserves checked above,
+            // so taking it out of the pool keeps the pool repaid.
+            for (asset, asset_fee) in [(asset_in, fee_in), (asset_out, fee_out)] {
+                let protocol_fee = T::ProtocolFee::get().mul_floor(asset_fee);
+                if !protocol_fee.is_zero() {
+                    T::Currency::transfer(
+                        asset,
+                        &pool_account,
+                        &T::ProtocolFeeReceiver::get(),
+                        protocol_fee,
+                        true,
+                    )?;
+                }
+            }
+
+            let amount_in = in_reserve_after.saturating_sub(in_reserve);
+            let amount_out = out_reserve.saturating_sub(out_reserve_after);
+
+            Self::update_dynamic_fee(pool_id, Permill::from_rational(amount_in, in_reserve));
+
+            // Fee repaid in `asset_out` is paid for borrowing, not for the amount exchanged.
+            T::OnTrade::on_trade(
+                &who,
+                asset_in,
+                asset_out,
+                amount_in,
+                amount_out,
+                fee_in,
+                (
+                    T::Currency::balance(asset_in, &pool_account),
+                    T::Currency::balance(asset_out, &pool_account),
+                ),
+            );
+
+            Self::deposit_event(Event::FlashSwapExecuted {
+                who,
+                asset_in,
+                asset_out,
+                amount_in,
+                amount_out,
+            });
+
+            Ok(())
+        }
+
+        #[pallet::weight(<T as Config>::WeightInfo::router_sell(route.len() as u32)
+            .saturating_add(Pallet::<T>::max_swap_ticks_weight()
+                .saturating_add(T::OnTrade::on_trade_weight())
+                .saturating_mul(route.len().saturating_sub(1) as u64)))]
+        pub fn router_sell(
+            origin: OriginFor<T>,
+            route: BoundedVec<AssetId, T::MaxRouteLength>,
+            amount_in: Balance,
+            min_limit: Balance,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let trades = Self::calculate_sell_route(&route, amount_in)?;
+            let amount_out = trades
+                .last()
+                .map(|trade| trade.amount_out)
+                .ok_or(Error::<T>::InvalidRoute)?;
+
+            ensure!(amount_out >= min_limit, Error::<T>::Limit);
+
+            Self::execute_route(&who, &trades)?;
+
+            Self::deposit_event(Event::<T>::RouteExecuted {
+                who,
+                asset_in: route[0],
+                asset_out: route[route.len() - 1],
+                amount_in,
+                amount_out,
+            });
+
+            Ok(Some(
+                <T as Config>::WeightInfo::router_sell(route.len() as u32)
+                    .saturating_add(Self::swap_ticks_weight(&trades))
+                    .saturating_add(
+                        T::OnTrade::on_trade_weight().saturating_mul(trades.len() as u64),
+                    ),
+            )
+            .into())
+        }
+
+        /// Buys `amount_out` of the last asset of the route for the first asset of the route,
+        /// trading through the pools of consecutive assets of the route.
+        ///
+        /// Either all trades of the route are executed or none is.
+        #[pallet::weight(<T as Config>::WeightInfo::router_buy(route.len() as u32)
+            .saturating_add(Pallet::<T>::max_swap_ticks_weight()
+                .saturating_add(T::OnTrade::on_trade_weight())
+                .saturating_mul(route.len().saturating_sub(1) as u64)))]
+        pub fn router_buy(
+            origin: OriginFor<T>,
+            route: BoundedVec<AssetId, T::MaxRouteLength>,
+            amount_out: Balance,
+            max_limit: Balance,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let trades = Self::calculate_buy_route(&route, amount_out)?;
+            let amount_in = trades
+                .first()
+                .map(|trade| trade.amount_in)
+                .ok_or(Error::<T>::InvalidRoute)?;
+
+            ensure!(amount_in <= max_limit, Error::<T>::Limit);
+
+            Self::execute_route(&who, &trades)?;
+
+            Self::deposit_event(Event::<T>::RouteExecuted {
+                who,
+                asset_in: route[0],
+                asset_out: route[route.len() - 1],
+                amount_in,
+                amount_out,
+            });
+
+            Ok(Some(
+                <T as Config>::WeightInfo::router_buy(route.len() as u32)
+                    .saturating_add(Self::swap_ticks_weight(&trades))
+                    .saturating_add(
+                        T::OnTrade::on_trade_weight().saturating_mul(trades.len() as u64),
+                    ),
+            )
+            .into())
+        }
+
+        /// Places an order selling `amount` of `asset_in` for `asset_out` once the spot price
+        /// of `asset_in` in their pool reaches `price`.
+        ///
+        /// The amount is held until the order is filled or cancelled. Orders are checked
+        /// in idle time of blocks, at most `MaxLimitOrdersPerBlock` in a block, and filled
+        /// partially when price impact of the whole amount would exceed the price. Orders
+        /// of a pool which was destroyed are cancelled when they are checked.
+        #[pallet::weight(<T as Config>::WeightInfo::place_limit_order())]
+        pub fn place_limit_order(
+            origin: OriginFor<T>,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount: Balance,
+            price: FixedU128,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            ensure!(
+                !amount.is_zero() && !price.is_zero(),
+                Error::<T>::InvalidLimitOrder
+            );
+
+            let (pool_id, _) =
+                Self::pair_pool(Self::pair(asset_in, asset_out)).ok_or(Error::<T>::PoolNotFound)?;
+
+            T::Currency::hold(asset_in, &who, amount)?;
+
+            let order_id = NextOrderId::<T>::try_mutate(|next_id| {
+                let order_id = *next_id;
+                *next_id = next_id.checked_add(1).ok_or(Error::<T>::Math)?;
+                Ok::<OrderId, DispatchError>(order_id)
+            })?;
+
+            LimitOrders::<T>::insert(
+                pool_id,
+                order_id,
+                LimitOrder {
+                    owner: who.clone(),
+                    asset_in,
+                    asset_out,
+                    amount,
+                    price,
+                },
+            );
+
+            Self::deposit_event(Event::LimitOrderPlaced {
+                who,
+                pool_id,
+                order_id,
+                asset_in,
+                asset_out,
+                amount,
+                price,
+            });
+
+            Ok(())
+        }
+
+        /// Cancels a limit order and releases its amount which was not sold yet.
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_limit_order())]
+        pub fn cancel_limit_order(
+            origin: OriginFor<T>,
+            pool_id: PoolId,
+            order_id: OrderId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let order =
+                Self::limit_order(pool_id, order_id).ok_or(Error::<T>::LimitOrderNotFound)?;
+
+            ensure!(order.owner == who, Error::<T>::NotLimitOrderOwner);
+
+            T::Currency::release(order.asset_in, &who, order.amount, false)?;
+
+            LimitOrders::<T>::remove(pool_id, order_id);
+
+            Self::deposit_event(Event::LimitOrderCancelled {
+                who,
+                pool_id,
+                order_id,
+                amount: order.amount,
+            });
+
+            Ok(())
+        }
+
+        /// Schedules selling `amount` of `asset_in` for `asset_out` every `period` blocks,
+        /// starting in the next block, until `budget` is sold.
+        ///
+        /// The budget is held until it is sold or the schedule ends. An execution fails when
+        /// it would receive less than the oracle price of `asset_in`, or its spot price if
+        /// the oracle has no price yet, reduced by `slippage`. The slippage should therefore
+        /// cover the trade fee. Schedule is terminated after `MaxScheduleFailures`
+        /// consecutive failed executions, or after the first one when its pool no longer
+        /// exists or the sale of its pool has ended.
+        #[pallet::weight(<T as Config>::WeightInfo::schedule_sell())]
+        #[allow(clippy::too_many_arguments)]
+        pub fn schedule_sell(
+            origin: OriginFor<T>,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount: Balance,
+            period: T::BlockNumber,
+            budget: Balance,
+            slippage: Permill,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            ensure!(
+                !amount.is_zero() && !period.is_zero() && budget >= amount,
+                Error::<T>::InvalidSchedule
+            );
+            ensure!(
+                Self::pair_pool_id(Self::pair(asset_in, asset_out)).is_some(),
+                Error::<T>::PoolNotFound
+            );
+
+            T::Currency::hold(asset_in, &who, budget)?;
+
+            let schedule_id = NextScheduleId::<T>::try_mutate(|next_id| {
+                let schedule_id = *next_id;
+                *next_id = next_id.checked_add(1).ok_or(Error::<T>::Math)?;
+                Ok::<ScheduleId, DispatchError>(schedule_id)
+            })?;
+
+            let next_execution = Self::plan_execution(
+                schedule_id,
+                frame_system::Pallet::<T>::block_number().saturating_add(One::one()),
+            )?;
+
+            Schedules::<T>::insert(
+                schedule_id,
+                Schedule {
+                    owner: who.clone(),
+                    asset_in,
+                    asset_out,
+                    amount,
+                    period,
+                    remaining: budget,
+                    slippage,
+                    next_execution,
+                    failures: 0,
+                },
+            );
+
+            Self::deposit_event(Event::SellScheduled {
+                who,
+                schedule_id,
+                asset_in,
+                asset_out,
+                amount,
+                period,
+                budget,
+                slippage,
+            });
+
+            Ok(())
+        }
+
+        /// Cancels a schedule and releases its budget which was not sold yet.
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_schedule())]
+        pub fn cancel_schedule(origin: OriginFor<T>, schedule_id: ScheduleId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let schedule = Self::schedule(schedule_id).ok_or(Error::<T>::ScheduleNotFound)?;
+
+            ensure!(schedule.owner == who, Error::<T>::NotScheduleOwner);
+
+            T::Currency::release(schedule.asset_in, &who, schedule.remaining, false)?;
+
+            ScheduledExecutions::<T>::mutate(schedule.next_execution, |schedule_ids| {
+                schedule_ids.retain(|id| *id != schedule_id)
+            });
+            Schedules::<T>::remove(schedule_id);
+
+            Self::deposit_event(Event::ScheduleCancelled {
+                who,
+                schedule_id,
+                remaining: schedule.remaining,
+            });
+
+            Ok(())
+        }
+
+        /// Sells `amount_in` of `asset_in` for `asset_out` in a multi-asset pool.
+        ///
+        /// Pools of three or more assets are not found by pair, so they are traded by pool id.
+        #[pallet::weight(<T as Config>::WeightInfo::sell_multi_asset(T::MaxAssetsInPool::get())
+            .saturating_add(T::OnTrade::on_trade_weight()))]
+        pub fn sell_multi_asset(
+            origin: OriginFor<T>,
+            pool_id: PoolId,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_in: Balance,
+            min_limit: Balance,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let trade = Self::calculate_multi_asset_sell(pool_id, asset_in, asset_out, amount_in)?;
+
+            ensure!(trade.amount_out >= min_limit, Error::<T>::Limit);
+
+            Self::execute_trade(&who, &trade, &who, &who)
+        }
+
+        /// Buys `amount_out` of `asset_out` for `asset_in` in a multi-asset pool.
+        ///
+        /// Pools of three or more assets are not found by pair, so they are traded by pool id.
+        #[pallet::weight(<T as Config>::WeightInfo::buy_multi_asset(T::MaxAssetsInPool::get())
+            .saturating_add(T::OnTrade::on_trade_weight()))]
+        pub fn buy_multi_asset(
+            origin: OriginFor<T>,
+            pool_id: PoolId,
+            asset_out: AssetId,
+            asset_in: AssetId,
+            amount_out: Balance,
+            max_limit: Balance,
+            deadline: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_not_expired(deadline)?;
+
+            let trade = Self::calculate_multi_asset_buy(pool_id, asset_out, asset_in, amount_out)?;
+
+            ensure!(trade.amount_in <= max_limit, Error::<T>::Limit);
+
+            Self::execute_trade(&who, &trade, &who, &who)
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Creates a pool of given type with initial liquidity provided by `who`.
+        fn do_create_pool(
+            who: T::AccountId,
+            asset_a: AssetId,
+            amount_a: Balance,
+            asset_b: AssetId,
+            amount_b: Balance,
+            pool_type: PoolType<T::AccountId, T::BlockNumber>,
+            fee: Permill,
+        ) -> DispatchResult {
+            ensure!(
+                asset_a != asset_b,
+                Error::<T>::CannotCreatePoolWithSameAssets
+            );
+
+            let (pair, amounts) = if asset_a < asset_b {
+                ((asset_a, asset_b), (amount_a, amount_b))
+            } else {
+                ((asset_b, asset_a), (amount_b, amount_a))
+            };
+
+            ensure!(
+                !PairPools::<T>::contains_key(pair),
+                Error::<T>::PoolAlreadyExists
+            );
+
+            let pool_id = Self::next_pool_id()?;
+            let pool_account = T::Account::create_account_id(pool_id)?;
+
+            let creation_fee = T::CreationFee::get();
+            if !creation_fee.is_zero() {
+                T::Currency::transfer(
+                    T::CreationFeeAsset::get(),
+                    &who,
+                    &T::TreasuryAccount::get(),
+                    creation_fee,
+                    true,
+                )?;
+            }
+
+            T::Currency::transfer(asset_a, &who, &pool_account, amount_a, true)?;
+            T::Currency::transfer(asset_b, &who, &pool_account, amount_b, true)?;
+
+            let (share_asset_id, shares) = Self::initialize_pool(
+                &who,
+                pool_id,
+                &[pair.0, pair.1],
+                &pool_account,
+                &[amounts.0, amounts.1],
+                pool_type,
+                fee,
+            )?;
+
+            T::OnLiquidityChanged::on_liquidity_added(
+                &who,
+                &[pair.0, pair.1],
+                &[amounts.0, amounts.1],
+                shares,
+                &[
+                    T::Currency::balance(pair.0, &pool_account),
+                    T::Currency::balance(pair.1, &pool_account),
+                ],
+            );
+
+            Self::deposit_event(Event::PoolCreated {
+                who,
+                asset_a,
+                asset_b,
+                shares,
+                share_asset_id,
+                fee,
+            });
+
+            Ok(())
+        }
+
+        /// Returns id of a new pool, unless number of pools reached `MaxPools`.
+        pub(crate) fn next_pool_id() -> Result<PoolId, DispatchError> {
+            ensure!(
+                Pools::<T>::count() < T::MaxPools::get(),
+                Error::<T>::TooManyPools
+            );
+
+            NextPoolId::<T>::try_mutate(|next_id| {
+                let pool_id = *next_id;
+                *next_id = next_id.checked_add(1).ok_or(Error::<T>::Math)?;
+                Ok(pool_id)
+            })
+        }
+
+        /// Returns id and info of the pool of given pair.
+        pub(crate) fn pair_pool(pair: (AssetId, AssetId)) -> Option<(PoolId, PoolInfoOf<T>)> {
+            let pool_id = Self::pair_pool_id(pair)?;
+            Some((pool_id, Self::pools(pool_id)?))
+        }
+
+        /// Registers share asset of a new pool of sorted `assets` and mints shares for its
+        /// initial liquidity.
+        ///
+        /// Initial shares are the geometric mean of the amounts, or D invariant of stableswap
+        /// pool, of which `MinimumLiquidity` is locked in the pool account, so that price
+        /// of a share cannot be inflated by the first provider. Liquidity bootstrapping pools
+        /// are provided by their owner only, so nothing is locked.
+        ///
+        /// Pools of two assets can be looked up by their pair.
+        /// Returns share asset and shares minted to `who`.
+        pub(crate) fn initialize_pool(
+            who: &T::AccountId,
+            pool_id: PoolId,
+            assets: &[AssetId],
+            pool_account: &T::AccountId,
+            amounts: &[Balance],
+            pool_type: PoolType<T::AccountId, T::BlockNumber>,
+            fee: Permill,
+        ) -> Result<(AssetId, Balance), DispatchError> {
+            let pool_assets: PoolAssetsOf<T> = assets
+                .to_vec()
+                .try_into()
+                .map_err(|_| Error::<T>::InvalidAssets)?;
+
+            let initial_shares = match (&pool_type, amounts) {
+                (PoolType::Stable(params), _) => {
+                    stableswap::calculate_d(amounts, params.initial_amplification)
+                }
+                (_, &[amount_a, amount_b]) => calculate_initial_shares(amount_a, amount_b),
+                _ => None,
+            }
+            .ok_or(Error::<T>::Math)?;
+
+            let locked_shares = match pool_type {
+                PoolType::LBP(_) => Balance::zero(),
+                _ => T::MinimumLiquidity::get(),
+            };
+            let shares = initial_shares
+                .checked_sub(locked_shares)
+                .filter(|shares| !shares.is_zero())
+                .ok_or(Error::<T>::InsufficientLiquidity)?;
+
+            let share_asset_id = T::AssetRegistry::create_share_asset(assets.to_vec())?;
+
+            if !locked_shares.is_zero() {
+                T::Currency::mint_into(share_asset_id, pool_account, locked_shares)?;
+            }
+            T::Currency::mint_into(share_asset_id, who, shares)?;
+
+            if let &[asset_a, asset_b] = assets {
+                PairPools::<T>::insert((asset_a, asset_b), pool_id);
+            }
+
+            Pools::<T>::insert(
+                pool_id,
+                PoolInfo {
+                    assets: pool_assets,
+                    share_asset: Some(share_asset_id),
+                    pool_type,
+                    fee,
+                },
+            );
+
+            Ok((share_asset_id, shares))
+        }
+
+        /// Returns reserves of `assets` in the pool account.
+        fn multi_asset_reserves(assets: &[AssetId], pool_account: &T::AccountId) -> Vec<Balance> {
+            assets
+                .iter()
+                .map(|&asset| T::Currency::balance(asset, pool_account))
+                .collect()
+        }
+
+        /// Returns position of `asset` in assets of a pool.
+        fn asset_index(assets: &[AssetId], asset: AssetId) -> Result<usize, DispatchError> {
+            assets
+                .iter()
+                .position(|&a| a == asset)
+                .ok_or_else(|| Error::<T>::AssetNotInPool.into())
+        }
+
+        /// Ensures that trade fee of a pool is within bounds.
+        fn ensure_valid_fee(fee: Permill) -> DispatchResult {
+            ensure!(
+                fee >= T::MinTradeFee::get() && fee <= T::MaxTradeFee::get(),
+                Error::<T>::InvalidFee
+            );
+            Ok(())
+        }
+
+        /// Ensures that the current block is not past the deadline, if any.
+        pub(crate) fn ensure_not_expired(deadline: Option<T::BlockNumber>) -> DispatchResult {
+            if let Some(deadline) = deadline {
+                ensure!(
+                    frame_system::Pallet::<T>::block_number() <= deadline,
+                    Error::<T>::DeadlineExpired
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Ensures that `who` can add liquidity to the pool of given type.
+        ///
+        /// Liquidity of liquidity bootstrapping pool can be added only by its owner before
+        /// the sale starts.
+        fn ensure_can_add_liquidity(
+            who: &T::AccountId,
+            pool_type: &PoolType<T::AccountId, T::BlockNumber>,
+        ) -> DispatchResult {
+            if let PoolType::LBP(params) = pool_type {
+                ensure!(who == &params.owner, Error::<T>::NotPoolOwner);
+                ensure!(
+                    frame_system::Pallet::<T>::block_number() < params.start,
+                    Error::<T>::SaleAlreadyStarted
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Ensures that `who` can remove liquidity from the pool of given type.
+        ///
+        /// Liquidity of liquidity bootstrapping pool can be removed only by its owner after
+        /// the sale ends.
+        fn ensure_can_remove_liquidity(
+            who: &T::AccountId,
+            pool_type: &PoolType<T::AccountId, T::BlockNumber>,
+        ) -> DispatchResult {
+            if let PoolType::LBP(params) = pool_type {
+                ensure!(who == &params.owner, Error::<T>::NotPoolOwner);
+                ensure!(
+                    frame_system::Pallet::<T>::block_number() > params.end,
+                    Error::<T>::SaleNotEnded
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Returns current weights of `asset` and of the other asset of the pair
+        /// in liquidity bootstrapping pool. Fails if the sale is not running.
+        pub(crate) fn lbp_weights(
+            params: &LBPParams<T::AccountId, T::BlockNumber>,
+            pair: (AssetId, AssetId),
+            asset: AssetId,
+        ) -> Result<(LBPWeight, LBPWeight), DispatchError> {
+            let now = frame_system::Pallet::<T>::block_number();
+
+            ensure!(
+                params.start <= now && now <= params.end,
+                Error::<T>::SaleNotRunning
+            );
+
+            let weight = lbp::calculate_linear_weights(
+                params.start,
+                params.end,
+                params.initial_weight,
+                params.final_weight,
+                now,
+            )
+            .ok_or(Error::<T>::Math)?;
+            let other_weight = MAX_WEIGHT.checked_sub(weight).ok_or(Error::<T>::Math)?;
+
+            if asset == pair.0 {
+                Ok((weight, other_weight))
+            } else {
+                Ok((other_weight, weight))
+            }
+        }
+
+        /// Returns current amplification of stableswap pool.
+        pub(crate) fn amplification(
+            params: &StableParams<T::BlockNumber>,
+        ) -> Result<Balance, DispatchError> {
+            stableswap::calculate_amplification(
+                params.initial_amplification,
+                params.final_amplification,
+                params.start,
+                params.end,
+                frame_system::Pallet::<T>::block_number(),
+            )
+            .ok_or_else(|| Error::<T>::Math.into())
+        }
+
+        pub(crate) fn pair(asset_a: AssetId, asset_b: AssetId) -> (AssetId, AssetId) {
+            if asset_a < asset_b {
+                (asset_a, asset_b)
+            } else {
+                (asset_b, asset_a)
+            }
+        }
+
+        /// Returns share asset of the pool of given pair.
+        pub fn share_asset(asset_a: AssetId, asset_b: AssetId) -> Option<AssetId> {
+            Self::pair_pool(Self::pair(asset_a, asset_b)).and_then(|(_, pool)| pool.share_asset)
+        }
+
+        /// Returns reserves of the pool of given pair, in the order of given assets.
+        pub fn pool_reserves(asset_a: AssetId, asset_b: AssetId) -> Option<(Balance, Balance)> {
+            let (pool_id, _) = Self::pair_pool(Self::pair(asset_a, asset_b))?;
+
+            let pool_account = T::Account::create_account_id(pool_id).ok()?;
+
+            Some((
+                T::Currency::balance(asset_a, &pool_account),
+                T::Currency::balance(asset_b, &pool_account),
+            ))
+        }
+
+        /// Returns amount received and fee paid when selling `amount_in` of `asset_in`.
+        pub fn quote_sell(
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_in: Balance,
+        ) -> Option<(Balance, Balance)> {
+            Self::calculate_sell(asset_in, asset_out, amount_in)
+                .ok()
+                .map(|trade| (trade.amount_out, trade.fee))
+        }
+
+        /// Returns amount to be paid, including fee, and fee paid when buying `amount_out` of `asset_out`.
+        pub fn quote_buy(
+            asset_out: AssetId,
+            asset_in: AssetId,
+            amount_out: Balance,
+        ) -> Option<(Balance, Balance)> {
+            Self::calculate_buy(asset_out, asset_in, amount_out)
+                .ok()
+                .map(|trade| (trade.amount_in, trade.fee))
+        }
+
+        /// Returns assets of a pool and their reserves.
+        pub fn pool_reserves_by_pool_id(pool_id: PoolId) -> Option<Vec<(AssetId, Balance)>> {
+            let pool = Self::pools(pool_id)?;
+
+            let pool_account = T::Account::create_account_id(pool_id).ok()?;
+
+            Some(
+                pool.assets
+                    .iter()
+                    .map(|&asset| (asset, T::Currency::balance(asset, &pool_account)))
+                    .collect(),
+            )
+        }
+
+        /// Returns amount received and fee paid when selling `amount_in` of `asset_in`
+        /// in pool `pool_id`.
+        ///
+        /// Pool of the pair is quoted as by `quote_sell`, any other pool as by `sell_multi_asset`.
+        pub fn quote_sell_by_pool_id(
+            pool_id: PoolId,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_in: Balance,
+        ) -> Option<(Balance, Balance)> {
+            if Self::pair_pool_id(Self::pair(asset_in, asset_out)) == Some(pool_id) {
+                Self::calculate_sell(asset_in, asset_out, amount_in)
+            } else {
+                Self::calculate_multi_asset_sell(pool_id, asset_in, asset_out, amount_in)
+            }
+            .ok()
+            .map(|trade| (trade.amount_out, trade.fee))
+        }
+
+        /// Returns amount to be paid, including fee, and fee paid when buying `amount_out`
+        /// of `asset_out` in pool `pool_id`.
+        ///
+        /// Pool of the pair is quoted as by `quote_buy`, any other pool as by `buy_multi_asset`.
+        pub fn quote_buy_by_pool_id(
+            pool_id: PoolId,
+            asset_out: AssetId,
+            asset_in: AssetId,
+            amount_out: Balance,
+        ) -> Option<(Balance, Balance)> {
+            if Self::pair_pool_id(Self::pair(asset_in, asset_out)) == Some(pool_id) {
+                Self::calculate_buy(asset_out, asset_in, amount_out)
+            } else {
+                Self::calculate_multi_asset_buy(pool_id, asset_out, asset_in, amount_out)
+            }
+            .ok()
+            .map(|trade| (trade.amount_in, trade.fee))
+        }
+
+        /// Returns price of `amount` of `asset_a` denominated in `asset_b`.
+        pub fn spot_price(asset_a: AssetId, asset_b: AssetId, amount: Balance) -> Option<Balance> {
+            let pair = Self::pair(asset_a, asset_b);
+            let (pool_id, pool) = Self::pair_pool(pair)?;
+            let (asset_a_reserve, asset_b_reserve) = Self::pool_reserves(asset_a, asset_b)?;
+
+            match pool.pool_type {
+                PoolType::XYK => calculate_spot_price(asset_a_reserve, asset_b_reserve, amount),
+                PoolType::LBP(params) => {
+                    let (weight_a, weight_b) = Self::lbp_weights(&params, pair, asset_a).ok()?;
+
+                    lbp::calculate_spot_price(
+                        asset_a_reserve,
+                        asset_b_reserve,
+                        weight_a,
+                        weight_b,
+                        amount,
+                    )
+                }
+                PoolType::Stable(params) => stableswap::calculate_spot_price(
+                    &[asset_a_reserve, asset_b_reserve],
+                    0,
+                    1,
+                    amount,
+                    Self::amplification(&params).ok()?,
+                ),
+                PoolType::Concentrated(_) => concentrated::calculate_spot_price(
+                    Self::concentrated_pool(pool_id)?.sqrt_price,
+                    amount,
+                    asset_a == pair.0,
+                ),
+            }
+        }
+
+        /// Calculates trade selling `amount_in` of `asset_in` for `asset_out`.
+        pub(crate) fn calculate_sell(
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_in: Balance,
+        ) -> Result<Trade<T::AccountId>, DispatchError> {
+            let pair = Self::pair(asset_in, asset_out);
+
+            let (pool_id, pool) = Self::pair_pool(pair).ok_or(Error::<T>::PoolNotFound)?;
+
+            let pool_account = T::Account::create_account_id(pool_id)?;
+
+            let asset_in_reserve = T::Currency::balance(asset_in, &pool_account);
+            let asset_out_reserve = T::Currency::balance(asset_out, &pool_account);
+
+            let fee_rate = Self::current_fee(pool_id, pool.fee);
+
+            let mut concentrated_swap = None;
+            let mut ticks = 0;
+
+            let (amount_out, fee) = match pool.pool_type {
+                PoolType::XYK => calculate_out_given_in_with_fee(
+                    asset_in_reserve,
+                    asset_out_reserve,
+                    amount_in,
+                    fee_rate,
+                ),
+                PoolType::LBP(params) => {
+                    let (in_weight, out_weight) = Self::lbp_weights(&params, pair, asset_in)?;
+
+                    lbp::calculate_out_given_in_with_fee(
+                        asset_in_reserve,
+                        asset_out_reserve,
+                        in_weight,
+                        out_weight,
+                        amount_in,
+                        fee_rate,
+                    )
+                }
+                PoolType::Stable(params) => stableswap::calculate_out_given_in_with_fee(
+                    &[asset_in_reserve, asset_out_reserve],
+                    0,
+                    1,
+                    amount_in,
+                    Self::amplification(&params)?,
+                    fee_rate,
+                ),
+                PoolType::Concentrated(_) => {
+                    let (swap, swap_ticks) = Self::calculate_concentrated_swap(
+                        pool_id, asset_in, asset_out, amount_in, fee_rate, true,
+                    )?;
+                    let amounts = Some((swap.amount_out, swap.fee));
+                    concentrated_swap = Some(swap);
+                    ticks = swap_ticks;
+                    amounts
+                }
+            }
+            .ok_or(Error::<T>::Math)?;
+
+            // Concentrated liquidity pools take the fee from the amount in.
+            let (fee_asset, protocol_fee) = match &concentrated_swap {
+                Some(swap) => (asset_in, swap.protocol_fee),
+                None => (asset_out, T::ProtocolFee::get().mul_floor(fee)),
+            };
+
+            Ok(Trade {
+                pool_id,
+                pool_account,
+                asset_in,
+                asset_out,
+                amount_in,
+                amount_out,
+                fee,
+                fee_asset,
+                protocol_fee,
+                price_impact: Permill::from_rational(amount_in, asset_in_reserve),
+                concentrated_swap,
+                ticks,
+            })
+        }
+
+        /// Calculates trade buying `amount_out` of `asset_out` for `asset_in`.
+        pub(crate) fn calculate_buy(
+            asset_out: AssetId,
+            asset_in: AssetId,
+            amount_out: Balance,
+        ) -> Result<Trade<T::AccountId>, DispatchError> {
+            let pair = Self::pair(asset_in, asset_out);
+
+            let (pool_id, pool) = Self::pair_pool(pair).ok_or(Error::<T>::PoolNotFound)?;
+
+            let pool_account = T::Account::create_account_id(pool_id)?;
+
+            let asset_in_reserve = T::Currency::balance(asset_in, &pool_account);
+            let asset_out_reserve = T::Currency::balance(asset_out, &pool_account);
+
+            let fee_rate = Self::current_fee(pool_id, pool.fee);
+
+            let mut concentrated_swap = None;
+            let mut ticks = 0;
+
+            let (amount_in, fee) = match pool.pool_type {
+                PoolType::XYK => calculate_in_given_out_with_fee(
+                    asset_out_reserve,
+                    asset_in_reserve,
+                    amount_out,
+                    fee_rate,
+                ),
+                PoolType::LBP(params) => {
+                    let (in_weight, out_weight) = Self::lbp_weights(&params, pair, asset_in)?;
+
+                    lbp::calculate_in_given_out_with_fee(
+                        asset_out_reserve,
+                        asset_in_reserve,
+                        out_weight,
+                        in_weight,
+                        amount_out,
+                        fee_rate,
+                    )
+                }
+                PoolType::Stable(params) => stableswap::calculate_in_given_out_with_fee(
+                    &[asset_in_reserve, asset_out_reserve],
+                    0,
+                    1,
+                    amount_out,
+                    Self::amplification(&params)?,
+                    fee_rate,
+                ),
+                PoolType::Concentrated(_) => {
+                    let (swap, swap_ticks) = Self::calculate_concentrated_swap(
+                        pool_id, asset_in, asset_out, amount_out, fee_rate, false,
+                    )?;
+                    let amounts = Some((swap.amount_in, swap.fee));
+                    concentrated_swap = Some(swap);
+                    ticks = swap_ticks;
+                    amounts
+                }
+            }
+            .ok_or(Error::<T>::Math)?;
+
+            let protocol_fee = match &concentrated_swap {
+                Some(swap) => swap.protocol_fee,
+                None => T::ProtocolFee::get().mul_floor(fee),
+            };
+
+            Ok(Trade {
+                pool_id,
+                pool_account,
+                asset_in,
+                asset_out,
+                amount_in,
+                amount_out,
+                fee,
+                fee_asset: asset_in,
+                protocol_fee,
+                price_impact: Permill::from_rational(amount_in, asset_in_reserve),
+                concentrated_swap,
+                ticks,
+            })
+        }
+
+        /// Calculates trade selling `amount_in` of `asset_in` for `asset_out` in a multi-asset pool.
+        pub(crate) fn calculate_multi_asset_sell(
+            pool_id: PoolId,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount_in: Balance,
+        ) -> Result<Trade<T::AccountId>, DispatchError> {
+            Self::calculate_multi_asset_trade(pool_id, asset_in, asset_out, amount_in, true)
+        }
+
+        /// Calculates trade buying `amount_out` of `asset_out` for `asset_in` in a multi-asset pool.
+        pub(crate) fn calculate_multi_asset_buy(
+            pool_id: PoolId,
+            asset_out: AssetId,
+            asset_in: AssetId,
+            amount_out: Balance,
+        ) -> Result<Trade<T::AccountId>, DispatchError> {
+            Self::calculate_multi_asset_trade(pool_id, asset_in, asset_out, amount_out, false)
+        }
+
+        /// Calculates trade of `amount` in a stableswap pool of any number of assets,
+        /// where `amount` is the amount in of a sell or the amount out of a buy.
+        fn calculate_multi_asset_trade(
+            pool_id: PoolId,
+            asset_in: AssetId,
+            asset_out: AssetId,
+            amount: Balance,
+            is_sell: bool,
+        ) -> Result<Trade<T::AccountId>, DispatchError> {
+            ensure!(asset_in != asset_out, Error::<T>::InvalidAssets);
+
+            let pool = Self::pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+            let amplification = match &pool.pool_type {
+                PoolType::Stable(params) => Self::amplification(params)?,
+                _ => return Err(Error::<T>::NotSupportedByPoolType.into()),
+            };
+
+            let idx_in = Self::asset_index(&pool.assets, asset_in)?;
+            let idx_out = Self::asset_index(&pool.assets, asset_out)?;
+
+            let pool_account = T::Account::create_account_id(pool_id)?;
+            let reserves = Self::multi_asset_reserves(&pool.assets, &pool_account);
+
+            let fee_rate = Self::current_fee(pool_id, pool.fee);
+
+            let (amount_in, amount_out, fee, fee_asset) = if is_sell {
+                let (amount_out, fee) = stableswap::calculate_out_given_in_with_fee(
+                    &reserves,
+                    idx_in,
+                    idx_out,
+                    amount,
+                    amplification,
+                    fee_rate,
+                )
+                .ok_or(Error::<T>::Math)?;
+                (amount, amount_out, fee, asset_out)
+            } else {
+                let (amount_in, fee) = stableswap::calculate_in_given_out_with_fee(
+                    &reserves,
+                    idx_in,
+                    idx_out,
+                    amount,
+                    amplification,
+                    fee_rate,
+                )
+                .ok_or(Error::<T>::Math)?;
+                (amount_in, amount, fee, asset_in)
+            };
+
+            Ok(Trade {
+                pool_id,
+                pool_account,
+                asset_in,
+                asset_out,
+                amount_in,
+                amount_out,
+                fee,
+                fee_asset,
+                protocol_fee: T::ProtocolFee::get().mul_floor(fee),
+                price_impact: Permill::from_rational(amount_in, reserves[idx_in]),
+                concentrated_swap: None,
+                ticks: 0,
+            })
+        }
+
+        /// Executes calculated trade on behalf of `who`.
+        ///
+        /// Amount in is transferred from `source` to the pool, unless `source` is the pool itself,
+        /// and amount out is transferred from the pool to `destination`. Protocol part of the fee
+        /// is transferred from the pool to `ProtocolFeeReceiver`. Swap in concentrated liquidity
+        /// pool moves its price.
+        pub(crate) fn execute_trade(
+            who: &T::AccountId,
+            trade: &Trade<T::AccountId>,
+            source: &T::AccountId,
+            destination: &T::AccountId,
+        ) -> DispatchResult {
+            if source != &trade.pool_account {
+                T::Currency::transfer(
+                    trade.asset_in,
+                    source,
+                    &trade.pool_account,
+                    trade.amount_in,
+                    true,
+                )?;
+            }
+            T::Currency::transfer(
+                trade.asset_out,
+                &trade.pool_account,
+                destination,
+                trade.amount_out,
+                true,
+            )?;
+            if !trade.protocol_fee.is_zero() {
+                T::Currency::transfer(
+                    trade.fee_asset,
+                    &trade.pool_account,
+                    &T::ProtocolFeeReceiver::get(),
+                    trade.protocol_fee,
+                    true,
+                )?;
+            }
+
+            if let Some(swap) = &trade.concentrated_swap {
+                Self::apply_concentrated_swap(
+                    trade.pool_id,
+                    trade.asset_in,
+                    trade.asset_out,
+                    swap,
+                )?;
+            }
+
+            Self::update_dynamic_fee(trade.pool_id, trade.price_impact);
+
+            T::OnTrade::on_trade(
+                who,
+                trade.asset_in,
+                trade.asset_out,
+                trade.amount_in,
+                trade.amount_out,
+                trade.fee,
+                (
+                    T::Currency::balance(trade.asset_in, &trade.pool_account),
+                    T::Currency::balance(trade.asset_out, &trade.pool_account),
+                ),
+            );
+
+            Self::deposit_event(Event::<T>::SwapExecuted {
+                who: who.clone(),
+                asset_in: trade.asset_in,
+                asset_out: trade.asset_out,
+                amount_in: trade.amount_in,
+                amount_out: trade.amount_out,
+                lp_fee: trade.fee.saturating_sub(trade.protocol_fee),
+                protocol_fee: trade.protocol_fee,
             });
 
             Ok(())
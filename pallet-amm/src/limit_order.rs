@@ -0,0 +1,158 @@
+use crate::traits::OnTrade;
+use crate::types::LimitOrder;
+use crate::weights::WeightInfo;
+use crate::{Config, Error, Event, LimitOrderCursor, LimitOrders, OrderId, Pallet, PoolId, Pools};
+use frame_support::dispatch::DispatchResult;
+use frame_support::storage::with_storage_layer;
+use frame_support::traits::tokens::fungibles::MutateHold;
+use frame_support::weights::Weight;
+use sp_runtime::traits::Zero;
+use sp_runtime::{FixedPointNumber, FixedU128};
+use sp_std::vec::Vec;
+
+/// Number of times the sold amount is halved when a fill of the whole remaining amount
+/// would go below the price of the order.
+pub(crate) const FILL_ATTEMPTS: u32 = 8;
+
+impl<T: Config> Pallet<T> {
+    /// Checks at most `max_orders` limit orders, continuing after the order checked last,
+    /// and fills those whose price was reached.
+    ///
+    /// Returns number of checked orders.
+    pub(crate) fn execute_limit_orders(max_orders: u32) -> u32 {
+        let orders: Vec<(PoolId, OrderId, LimitOrder<T::AccountId>)> =
+            match LimitOrderCursor::<T>::get() {
+                Some((pool_id, order_id)) => {
+                    LimitOrders::<T>::iter_from(LimitOrders::<T>::hashed_key_for(pool_id, order_id))
+                }
+                None => LimitOrders::<T>::iter(),
+            }
+            .take(max_orders as usize)
+            .collect();
+
+        // Checking starts from the first order again once all of them were checked.
+        let cursor = if orders.len() < max_orders as usize {
+            None
+        } else {
+            orders
+                .last()
+                .map(|(pool_id, order_id, _)| (*pool_id, *order_id))
+        };
+        LimitOrderCursor::<T>::set(cursor);
+
+        let checked = orders.len() as u32;
+
+        for (pool_id, order_id, order) in orders {
+            // Order of a destroyed pool can never be filled, so it is cancelled.
+            if !Pools::<T>::contains_key(pool_id) {
+                let _ = with_storage_layer(|| Self::drop_limit_order(pool_id, order_id, order));
+                continue;
+            }
+
+            // Order which cannot be filled stays unchanged and is checked again later.
+            let _ = with_storage_layer(|| Self::fill_limit_order(pool_id, order_id, order));
+        }
+
+        checked
+    }
+
+    /// Returns weight of checking `orders` limit orders, each of which may calculate
+    /// a trade `FILL_ATTEMPTS` times before it is filled.
+    pub(crate) fn limit_orders_weight(orders: u32) -> Weight {
+        T::WeightInfo::execute_limit_orders(orders).saturating_add(
+            Self::max_swap_ticks_weight()
+                .saturating_mul(FILL_ATTEMPTS as u64)
+                .saturating_add(T::OnTrade::on_trade_weight())
+                .saturating_mul(orders as u64),
+        )
+    }
+
+    /// Removes a limit order and releases its remaining amount to its owner.
+    fn drop_limit_order(
+        pool_id: PoolId,
+        order_id: OrderId,
+        order: LimitOrder<T::AccountId>,
+    ) -> DispatchResult {
+        T::Currency::release(order.asset_in, &order.owner, order.amount, false)?;
+
+        LimitOrders::<T>::remove(pool_id, order_id);
+
+        Self::deposit_event(Event::LimitOrderCancelled {
+            who: order.owner,
+            pool_id,
+            order_id,
+            amount: order.amount,
+        });
+
+        Ok(())
+    }
+
+    /// Sells the remaining amount of a limit order if the spot price reached the price
+    /// of the order.
+    ///
+    /// If the price of the trade would go below the price of the order, half of the amount
+    /// is tried instead, at most `FILL_ATTEMPTS` times.
+    fn fill_limit_order(
+        pool_id: PoolId,
+        order_id: OrderId,
+        mut order: LimitOrder<T::AccountId>,
+    ) -> DispatchResult {
+        let spot_price = Self::spot_price(order.asset_in, order.asset_out, FixedU128::DIV)
+            .map(FixedU128::from_inner)
+            .ok_or(Error::<T>::PoolNotFound)?;
+
+        if spot_price < order.price {
+            return Ok(());
+        }
+
+        let mut amount_in = order.amount;
+
+        for _ in 0..FILL_ATTEMPTS {
+            if amount_in.is_zero() {
+                break;
+            }
+
+            let trade = Self::calculate_sell(order.asset_in, order.asset_out, amount_in)?;
+
+            if trade.amount_out < order.price.saturating_mul_int(amount_in) {
+                amount_in /= 2;
+                continue;
+            }
+
+            // Held amount is sold directly from the account of the owner to the pool.
+            T::Currency::transfer_held(
+                order.asset_in,
+                &order.owner,
+                &trade.pool_account,
+                amount_in,
+                false,
+                false,
+            )?;
+            Self::execute_trade(&order.owner, &trade, &trade.pool_account, &order.owner)?;
+
+            order.amount = order
+                .amount
+                .checked_sub(amount_in)
+                .ok_or(Error::<T>::Math)?;
+
+            Self::deposit_event(Event::LimitOrderFilled {
+                who: order.owner.clone(),
+                pool_id,
+                order_id,
+                amount_in,
+                amount_out: trade.amount_out,
+                remaining: order.amount,
+            });
+
+            if order.amount.is_zero() {
+                LimitOrders::<T>::remove(pool_id, order_id);
+            } else {
+                LimitOrders::<T>::insert(pool_id, order_id, order);
+            }
+
+            break;
+        }
+
+        Ok(())
+    }
+}
@@ -1,17 +1,21 @@
 use crate as xyk;
-use crate::{AssetId, Balance, Config};
-use frame_support::parameter_types;
+use crate::{AssetId, Balance, Config, PoolId, VolatilityFeeCurve};
 use frame_support::sp_runtime::Permill;
+use frame_support::weights::Weight;
+use frame_support::{ord_parameter_types, parameter_types};
 use frame_system as system;
+use frame_system::{EnsureRoot, EnsureSignedBy};
 use orml_traits::parameter_type_with_key;
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup, One},
-    DispatchError,
+    DispatchError, DispatchResult, FixedU128,
 };
 
+use frame_support::traits::tokens::fungibles::Transfer;
 use frame_support::traits::{Everything, GenesisBuild};
+use std::cell::RefCell;
 
 pub type Amount = i128;
 pub type AccountId = u64;
@@ -19,12 +23,27 @@ pub type AccountId = u64;
 pub const ALICE: AccountId = 1;
 pub const BOB: AccountId = 2;
 pub const CHARLIE: AccountId = 3;
+pub const TREASURY: AccountId = 4;
 
 pub const HDX: AssetId = 1000;
 pub const DOT: AssetId = 2000;
+pub const USDT: AssetId = 3000;
+
+thread_local! {
+    static TRADE_FEE: RefCell<Permill> = RefCell::new(Permill::from_float(0.0));
+    static CREATION_FEE: RefCell<Balance> = RefCell::new(0);
+    static WITHDRAW_FEE: RefCell<Permill> = RefCell::new(Permill::from_float(0.0));
+    static PROTOCOL_FEE: RefCell<Permill> = RefCell::new(Permill::from_float(0.0));
+    static FEE_IMPACT_FACTOR: RefCell<Permill> = RefCell::new(Permill::from_float(0.0));
+    static NEXT_SHARE_ASSET: RefCell<AssetId> = RefCell::new(POOL_SHARE_ASSET);
+    static TRADES: RefCell<Vec<TradeRecord>> = RefCell::new(vec![]);
+    static LIQUIDITY_CHANGES: RefCell<Vec<LiquidityChangeRecord>> = RefCell::new(vec![]);
+    static FLASH_SWAP_REPAYMENT: RefCell<Vec<(AssetId, Balance)>> = RefCell::new(vec![]);
+    static FLASH_SWAPS: RefCell<Vec<FlashSwapRecord>> = RefCell::new(vec![]);
+}
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
-type Block = frame_system::mocking::MockBlock<Test>;
+pub type Block = frame_system::mocking::MockBlock<Test>;
 
 frame_support::construct_runtime!(
     pub enum Test where
@@ -33,7 +52,7 @@ frame_support::construct_runtime!(
      UncheckedExtrinsic = UncheckedExtrinsic,
      {
          System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-         XYK: xyk::{Pallet, Call, Storage, Event<T>},
+         XYK: xyk::{Pallet, Call, Config<T>, Storage, Event<T>},
          Tokens: orml_tokens::{Pallet, Event<T>},
      }
 
@@ -42,8 +61,31 @@ frame_support::construct_runtime!(
 parameter_types! {
     pub const BlockHashCount: u64 = 250;
     pub const SS58Prefix: u8 = 63;
-    pub TradeFee: Permill = Permill::from_float(0.0);
+    pub TradeFee: Permill = TRADE_FEE.with(|v| *v.borrow());
+    pub const MinTradeFee: Permill = Permill::zero();
+    pub const MaxTradeFee: Permill = Permill::from_percent(10);
+    pub const FeeDecay: Permill = Permill::from_percent(50);
+    pub FeeImpactFactor: Permill = FEE_IMPACT_FACTOR.with(|v| *v.borrow());
     pub RegistryStringLimit: u32 = 100;
+    pub const MaxRouteLength: u32 = 4;
+    pub OracleWindows: Vec<u32> = vec![1, 10, 14_400];
+    pub const CreationFeeAsset: AssetId = HDX;
+    pub CreationFee: Balance = CREATION_FEE.with(|v| *v.borrow());
+    pub const TreasuryAccount: AccountId = TREASURY;
+    pub WithdrawFee: Permill = WITHDRAW_FEE.with(|v| *v.borrow());
+    pub ProtocolFee: Permill = PROTOCOL_FEE.with(|v| *v.borrow());
+    pub const ProtocolFeeReceiver: AccountId = TREASURY;
+    pub const MinimumLiquidity: Balance = 1_000;
+    pub const MaxAssetsInPool: u32 = 8;
+    pub const MaxPools: u32 = 5;
+    pub const MaxTicksPerPool: u32 = 6;
+    pub const MaxLimitOrdersPerBlock: u32 = 2;
+    pub const MaxSchedulesPerBlock: u32 = 2;
+    pub const MaxScheduleFailures: u32 = 3;
+}
+
+ord_parameter_types! {
+    pub const PoolCreator: AccountId = ALICE;
 }
 
 impl system::Config for Test {
@@ -97,17 +139,12 @@ impl orml_tokens::Config for Test {
 
 pub struct AccountIdConstructor();
 
-impl crate::traits::AccountIdFor<(AssetId, AssetId)> for AccountIdConstructor {
+impl crate::traits::AccountIdFor<PoolId> for AccountIdConstructor {
     type AccountId = AccountId;
     type Error = DispatchError;
 
-    fn create_account_id(assets: (AssetId, AssetId)) -> Result<Self::AccountId, Self::Error> {
-        let mut a = assets.0 as u64;
-        let mut b = assets.0 as u64;
-        if a > b {
-            std::mem::swap(&mut a, &mut b)
-        }
-        Ok(a * 1000 + b)
+    fn create_account_id(pool_id: PoolId) -> Result<Self::AccountId, Self::Error> {
+        Ok(1_000_000 + pool_id as u64)
     }
 }
 
@@ -117,11 +154,40 @@ impl Config for Test {
     type AssetRegistry = Registry;
     type Account = AccountIdConstructor;
     type TradeFee = TradeFee;
+    type MinTradeFee = MinTradeFee;
+    type MaxTradeFee = MaxTradeFee;
+    type FeeCurve = VolatilityFeeCurve<FeeDecay, FeeImpactFactor>;
+    type ProtocolFee = ProtocolFee;
+    type ProtocolFeeReceiver = ProtocolFeeReceiver;
+    type MaxRouteLength = MaxRouteLength;
+    type MaxAssetsInPool = MaxAssetsInPool;
+    type MaxPools = MaxPools;
+    type MaxTicksPerPool = MaxTicksPerPool;
+    type MaxLimitOrdersPerBlock = MaxLimitOrdersPerBlock;
+    type MaxSchedulesPerBlock = MaxSchedulesPerBlock;
+    type MaxScheduleFailures = MaxScheduleFailures;
+    type OracleWindows = OracleWindows;
+    type CreatePoolOrigin = EnsureSignedBy<PoolCreator, AccountId>;
+    type AuthorityOrigin = EnsureRoot<AccountId>;
+    type CreationFeeAsset = CreationFeeAsset;
+    type CreationFee = CreationFee;
+    type TreasuryAccount = TreasuryAccount;
+    type MinimumLiquidity = MinimumLiquidity;
+    type WithdrawFee = WithdrawFee;
+    type OnTrade = TradeHandler;
+    type OnLiquidityChanged = LiquidityChangeHandler;
+    type FlashSwapHandler = FlashSwapRepayer;
     type WeightInfo = ();
 }
 
 pub struct ExtBuilder {
     endowed_accounts: Vec<(AccountId, AssetId, Balance)>,
+    pools: Vec<(AssetId, AssetId, Balance, Balance, AccountId)>,
+    trade_fee: Permill,
+    creation_fee: Balance,
+    withdraw_fee: Permill,
+    protocol_fee: Permill,
+    fee_impact_factor: Permill,
 }
 
 // Returns default values for genesis config
@@ -131,16 +197,67 @@ impl Default for ExtBuilder {
             endowed_accounts: vec![
                 (ALICE, HDX, 1_000_000_000_000_000u128),
                 (ALICE, DOT, 1_000_000_000_000_000u128),
+                (ALICE, USDT, 1_000_000_000_000_000u128),
                 (BOB, HDX, 10_000_000_000_000u128),
                 (CHARLIE, HDX, 10_000_000_000_000u128),
                 (CHARLIE, DOT, 10_000_000_000_000u128),
             ],
+            pools: vec![],
+            trade_fee: Permill::from_float(0.0),
+            creation_fee: 0,
+            withdraw_fee: Permill::from_float(0.0),
+            protocol_fee: Permill::from_float(0.0),
+            fee_impact_factor: Permill::from_float(0.0),
         }
     }
 }
 
 impl ExtBuilder {
+    pub fn with_trade_fee(mut self, fee: Permill) -> Self {
+        self.trade_fee = fee;
+        self
+    }
+
+    pub fn with_creation_fee(mut self, fee: Balance) -> Self {
+        self.creation_fee = fee;
+        self
+    }
+
+    pub fn with_withdraw_fee(mut self, fee: Permill) -> Self {
+        self.withdraw_fee = fee;
+        self
+    }
+
+    pub fn with_protocol_fee(mut self, fee: Permill) -> Self {
+        self.protocol_fee = fee;
+        self
+    }
+
+    pub fn with_fee_impact_factor(mut self, factor: Permill) -> Self {
+        self.fee_impact_factor = factor;
+        self
+    }
+
+    pub fn with_pool(
+        mut self,
+        asset_a: AssetId,
+        asset_b: AssetId,
+        amount_a: Balance,
+        amount_b: Balance,
+        owner: AccountId,
+    ) -> Self {
+        self.pools
+            .push((asset_a, asset_b, amount_a, amount_b, owner));
+        self
+    }
+
     pub fn build(self) -> sp_io::TestExternalities {
+        TRADE_FEE.with(|v| *v.borrow_mut() = self.trade_fee);
+        CREATION_FEE.with(|v| *v.borrow_mut() = self.creation_fee);
+        WITHDRAW_FEE.with(|v| *v.borrow_mut() = self.withdraw_fee);
+        PROTOCOL_FEE.with(|v| *v.borrow_mut() = self.protocol_fee);
+        FEE_IMPACT_FACTOR.with(|v| *v.borrow_mut() = self.fee_impact_factor);
+
         let mut t = frame_system::GenesisConfig::default()
             .build_storage::<Test>()
             .unwrap();
@@ -151,19 +268,221 @@ impl ExtBuilder {
         .assimilate_storage(&mut t)
         .unwrap();
 
-        t.into()
+        xyk::GenesisConfig::<Test> { pools: self.pools }
+            .assimilate_storage(&mut t)
+            .unwrap();
+
+        let mut ext: sp_io::TestExternalities = t.into();
+        ext.execute_with(|| System::set_block_number(1));
+        ext
     }
 }
 
+pub fn expect_events(e: Vec<Event>) {
+    e.into_iter()
+        .for_each(frame_system::Pallet::<Test>::assert_has_event);
+}
+
 pub struct Registry;
 
+/// Share asset of the first pool created in a test. Every other pool gets the next id.
 pub const POOL_SHARE_ASSET: AssetId = 2222;
 
-impl crate::traits::Create<(AssetId, AssetId)> for Registry {
+impl crate::traits::Create<Vec<AssetId>> for Registry {
     type AssetId = AssetId;
     type Error = DispatchError;
 
-    fn create_share_asset(_assets: (AssetId, AssetId)) -> Result<Self::AssetId, Self::Error> {
-        Ok(POOL_SHARE_ASSET)
+    fn create_share_asset(_assets: Vec<AssetId>) -> Result<Self::AssetId, Self::Error> {
+        Ok(NEXT_SHARE_ASSET.with(|v| {
+            let share_asset = *v.borrow();
+            *v.borrow_mut() = share_asset + 1;
+            share_asset
+        }))
+    }
+}
+
+/// (who, asset_in, asset_out, amount_in, amount_out, fee, reserves)
+pub type TradeRecord = (
+    AccountId,
+    AssetId,
+    AssetId,
+    Balance,
+    Balance,
+    Balance,
+    (Balance, Balance),
+);
+
+/// (added, who, assets, amounts, shares, reserves)
+pub type LiquidityChangeRecord = (
+    bool,
+    AccountId,
+    Vec<AssetId>,
+    Vec<Balance>,
+    Balance,
+    Vec<Balance>,
+);
+
+/// (who, pool_account, asset_in, asset_out, amount_out)
+pub type FlashSwapRecord = (AccountId, AccountId, AssetId, AssetId, Balance);
+
+pub struct TradeHandler;
+
+impl crate::traits::OnTrade<AccountId, AssetId, Balance> for TradeHandler {
+    fn on_trade(
+        who: &AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        amount_out: Balance,
+        fee: Balance,
+        reserves: (Balance, Balance),
+    ) {
+        TRADES.with(|v| {
+            v.borrow_mut().push((
+                *who, asset_in, asset_out, amount_in, amount_out, fee, reserves,
+            ))
+        });
+    }
+
+    fn on_trade_weight() -> Weight {
+        Weight::from_ref_time(1_000)
+    }
+}
+
+pub struct LiquidityChangeHandler;
+
+impl crate::traits::OnLiquidityChanged<AccountId, AssetId, Balance> for LiquidityChangeHandler {
+    fn on_liquidity_added(
+        who: &AccountId,
+        assets: &[AssetId],
+        amounts: &[Balance],
+        shares: Balance,
+        reserves: &[Balance],
+    ) {
+        LIQUIDITY_CHANGES.with(|v| {
+            v.borrow_mut().push((
+                true,
+                *who,
+                assets.to_vec(),
+                amounts.to_vec(),
+                shares,
+                reserves.to_vec(),
+            ))
+        });
+    }
+
+    fn on_liquidity_removed(
+        who: &AccountId,
+        assets: &[AssetId],
+        amounts: &[Balance],
+        shares: Balance,
+        reserves: &[Balance],
+    ) {
+        LIQUIDITY_CHANGES.with(|v| {
+            v.borrow_mut().push((
+                false,
+                *who,
+                assets.to_vec(),
+                amounts.to_vec(),
+                shares,
+                reserves.to_vec(),
+            ))
+        });
+    }
+
+    fn on_liquidity_changed_weight() -> Weight {
+        Weight::from_ref_time(1_000)
+    }
+}
+
+pub fn recorded_trades() -> Vec<TradeRecord> {
+    TRADES.with(|v| v.borrow().clone())
+}
+
+pub fn recorded_liquidity_changes() -> Vec<LiquidityChangeRecord> {
+    LIQUIDITY_CHANGES.with(|v| v.borrow().clone())
+}
+
+/// Repays flash swaps with amounts set by `set_flash_swap_repayment`.
+pub struct FlashSwapRepayer;
+
+impl crate::traits::FlashSwapHandler<AccountId, AssetId, Balance> for FlashSwapRepayer {
+    fn on_flash_swap(
+        who: &AccountId,
+        pool_account: &AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: Balance,
+    ) -> DispatchResult {
+        FLASH_SWAPS.with(|v| {
+            v.borrow_mut()
+                .push((*who, *pool_account, asset_in, asset_out, amount_out))
+        });
+
+        for (asset, amount) in FLASH_SWAP_REPAYMENT.with(|v| v.borrow().clone()) {
+            <Tokens as Transfer<AccountId>>::transfer(asset, who, pool_account, amount, false)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_flash_swap_weight() -> Weight {
+        Weight::from_ref_time(1_000)
+    }
+}
+
+pub fn set_flash_swap_repayment(repayment: Vec<(AssetId, Balance)>) {
+    FLASH_SWAP_REPAYMENT.with(|v| *v.borrow_mut() = repayment);
+}
+
+pub fn recorded_flash_swaps() -> Vec<FlashSwapRecord> {
+    FLASH_SWAPS.with(|v| v.borrow().clone())
+}
+
+sp_api::impl_runtime_apis! {
+    impl pallet_amm_runtime_api::AmmApi<Block, AssetId, Balance, PoolId> for Test {
+        fn quote_sell(asset_in: AssetId, asset_out: AssetId, amount_in: Balance) -> Option<(Balance, Balance)> {
+            XYK::quote_sell(asset_in, asset_out, amount_in)
+        }
+
+        fn quote_buy(asset_out: AssetId, asset_in: AssetId, amount_out: Balance) -> Option<(Balance, Balance)> {
+            XYK::quote_buy(asset_out, asset_in, amount_out)
+        }
+
+        fn pool_reserves(asset_a: AssetId, asset_b: AssetId) -> Option<(Balance, Balance)> {
+            XYK::pool_reserves(asset_a, asset_b)
+        }
+
+        fn quote_sell_by_pool_id(pool_id: PoolId, asset_in: AssetId, asset_out: AssetId, amount_in: Balance) -> Option<(Balance, Balance)> {
+            XYK::quote_sell_by_pool_id(pool_id, asset_in, asset_out, amount_in)
+        }
+
+        fn quote_buy_by_pool_id(pool_id: PoolId, asset_out: AssetId, asset_in: AssetId, amount_out: Balance) -> Option<(Balance, Balance)> {
+            XYK::quote_buy_by_pool_id(pool_id, asset_out, asset_in, amount_out)
+        }
+
+        fn pool_reserves_by_pool_id(pool_id: PoolId) -> Option<Vec<(AssetId, Balance)>> {
+            XYK::pool_reserves_by_pool_id(pool_id)
+        }
+
+        fn share_asset(asset_a: AssetId, asset_b: AssetId) -> Option<AssetId> {
+            XYK::share_asset(asset_a, asset_b)
+        }
+
+        fn spot_price(asset_a: AssetId, asset_b: AssetId, amount: Balance) -> Option<Balance> {
+            XYK::spot_price(asset_a, asset_b, amount)
+        }
+
+        fn best_sell_route(asset_in: AssetId, asset_out: AssetId, amount_in: Balance, max_hops: u32) -> Option<(Vec<AssetId>, Balance)> {
+            XYK::find_best_sell_route(asset_in, asset_out, amount_in, max_hops)
+        }
+
+        fn best_buy_route(asset_in: AssetId, asset_out: AssetId, amount_out: Balance, max_hops: u32) -> Option<(Vec<AssetId>, Balance)> {
+            XYK::find_best_buy_route(asset_in, asset_out, amount_out, max_hops)
+        }
+
+        fn oracle_price(asset_a: AssetId, asset_b: AssetId, window: u32) -> Option<(FixedU128, Balance)> {
+            <XYK as crate::traits::PriceOracle<AssetId, FixedU128, Balance>>::get_price(asset_a, asset_b, window)
+        }
     }
 }
@@ -0,0 +1,124 @@
+use crate::traits::{AccountIdFor, PriceOracle};
+use crate::types::OracleEntry;
+use crate::{AssetId, Balance, Config, Oracles, PairPools, Pallet, PoolId};
+use frame_support::traits::tokens::fungibles::Inspect;
+use frame_support::traits::Get;
+use primitive_types::U256;
+use sp_runtime::traits::{One, Saturating};
+use sp_runtime::{FixedPointNumber, FixedU128, SaturatedConversion};
+
+impl<T: Config> Pallet<T> {
+    /// Updates oracle entries of all pools with their prices and liquidity at the end of the block.
+    ///
+    /// Entries of windows longer than one block are exponential moving averages with smoothing
+    /// factor `2 / (window + 1)`, applied once per elapsed block.
+    pub(crate) fn update_oracles(now: T::BlockNumber) {
+        Self::update_oracle_windows(now, &T::OracleWindows::get());
+    }
+
+    /// Updates oracle entries of all pools for given windows.
+    pub(crate) fn update_oracle_windows(now: T::BlockNumber, windows: &[u32]) {
+        for (pair, pool_id) in PairPools::<T>::iter() {
+            let (price, liquidity) = match Self::price_and_liquidity(pair, pool_id) {
+                Some(value) => value,
+                None => continue,
+            };
+
+            for window in windows.iter() {
+                Oracles::<T>::mutate(pair, window, |maybe_entry| {
+                    let entry = match maybe_entry {
+                        Some(entry) => {
+                            let elapsed = now.saturating_sub(entry.updated_at).saturated_into();
+                            let weight = Self::new_value_weight(*window, elapsed);
+
+                            OracleEntry {
+                                price: Self::average_price(entry.price, price, weight),
+                                liquidity: Self::average_balance(
+                                    entry.liquidity,
+                                    liquidity,
+                                    weight,
+                                ),
+                                updated_at: now,
+                            }
+                        }
+                        None => OracleEntry {
+                            price,
+                            liquidity,
+                            updated_at: now,
+                        },
+                    };
+
+                    *maybe_entry = Some(entry);
+                });
+            }
+        }
+    }
+
+    /// Returns current price of the first asset of the pair denominated in the second asset
+    /// and liquidity of the pool.
+    fn price_and_liquidity(
+        pair: (AssetId, AssetId),
+        pool_id: PoolId,
+    ) -> Option<(FixedU128, Balance)> {
+        let pool_account = T::Account::create_account_id(pool_id).ok()?;
+
+        let asset_a_reserve = T::Currency::balance(pair.0, &pool_account);
+        let asset_b_reserve = T::Currency::balance(pair.1, &pool_account);
+
+        if asset_a_reserve == 0 || asset_b_reserve == 0 {
+            return None;
+        }
+
+        let price = FixedU128::from_inner(Self::spot_price(pair.0, pair.1, FixedU128::DIV)?);
+
+        let liquidity = (U256::from(asset_a_reserve) * U256::from(asset_b_reserve)).integer_sqrt();
+
+        Some((price, Balance::try_from(liquidity).ok()?))
+    }
+
+    /// Weight of the new value in the average after `elapsed` blocks.
+    fn new_value_weight(window: u32, elapsed: u32) -> FixedU128 {
+        if window <= 1 {
+            return FixedU128::one();
+        }
+
+        let smoothing = FixedU128::saturating_from_rational(2u32, window.saturating_add(1));
+
+        FixedU128::one().saturating_sub(
+            FixedU128::one()
+                .saturating_sub(smoothing)
+                .saturating_pow(elapsed as usize),
+        )
+    }
+
+    fn average_price(previous: FixedU128, current: FixedU128, weight: FixedU128) -> FixedU128 {
+        if current >= previous {
+            previous.saturating_add(weight.saturating_mul(current.saturating_sub(previous)))
+        } else {
+            previous.saturating_sub(weight.saturating_mul(previous.saturating_sub(current)))
+        }
+    }
+
+    fn average_balance(previous: Balance, current: Balance, weight: FixedU128) -> Balance {
+        if current >= previous {
+            previous.saturating_add(weight.saturating_mul_int(current.saturating_sub(previous)))
+        } else {
+            previous.saturating_sub(weight.saturating_mul_int(previous.saturating_sub(current)))
+        }
+    }
+}
+
+impl<T: Config> PriceOracle<AssetId, FixedU128, Balance> for Pallet<T> {
+    fn get_price(asset_a: AssetId, asset_b: AssetId, window: u32) -> Option<(FixedU128, Balance)> {
+        let pair = Self::pair(asset_a, asset_b);
+        let entry = Self::oracle(pair, window)?;
+
+        let price = if asset_a == pair.0 {
+            entry.price
+        } else {
+            entry.price.reciprocal()?
+        };
+
+        Some((price, entry.liquidity))
+    }
+}
@@ -0,0 +1,189 @@
+use crate::types::Trade;
+use crate::{AssetId, Balance, Config, Error, PairPools, Pallet};
+use frame_support::dispatch::DispatchResult;
+use frame_support::ensure;
+use frame_support::traits::Get;
+use sp_runtime::DispatchError;
+use sp_std::collections::btree_map::BTreeMap;
+use sp_std::vec;
+use sp_std::vec::Vec;
+
+impl<T: Config> Pallet<T> {
+    /// Route must contain at least two assets and each of its trades must use a different pool,
+    /// so that trades can be calculated before any of them is executed.
+    fn ensure_valid_route(route: &[AssetId]) -> DispatchResult {
+        ensure!(route.len() >= 2, Error::<T>::InvalidRoute);
+
+        let pairs: Vec<(AssetId, AssetId)> = route
+            .windows(2)
+            .map(|hop| Self::pair(hop[0], hop[1]))
+            .collect();
+
+        for (idx, pair) in pairs.iter().enumerate() {
+            ensure!(
+                pair.0 != pair.1 && !pairs[..idx].contains(pair),
+                Error::<T>::InvalidRoute
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Calculates trades selling `amount_in` of the first asset of the route.
+    pub(crate) fn calculate_sell_route(
+        route: &[AssetId],
+        amount_in: Balance,
+    ) -> Result<Vec<Trade<T::AccountId>>, DispatchError> {
+        Self::ensure_valid_route(route)?;
+
+        let mut trades = Vec::with_capacity(route.len() - 1);
+        let mut amount = amount_in;
+
+        for hop in route.windows(2) {
+            let trade = Self::calculate_sell(hop[0], hop[1], amount)?;
+            amount = trade.amount_out;
+            trades.push(trade);
+        }
+
+        Ok(trades)
+    }
+
+    /// Calculates trades buying `amount_out` of the last asset of the route.
+    pub(crate) fn calculate_buy_route(
+        route: &[AssetId],
+        amount_out: Balance,
+    ) -> Result<Vec<Trade<T::AccountId>>, DispatchError> {
+        Self::ensure_valid_route(route)?;
+
+        let mut trades = Vec::with_capacity(route.len() - 1);
+        let mut amount = amount_out;
+
+        for hop in route.windows(2).rev() {
+            let trade = Self::calculate_buy(hop[1], hop[0], amount)?;
+            amount = trade.amount_in;
+            trades.push(trade);
+        }
+
+        trades.reverse();
+
+        Ok(trades)
+    }
+
+    /// Executes calculated trades of a route on behalf of `who`.
+    ///
+    /// Amounts traded between consecutive trades are transferred directly from one pool to another.
+    pub(crate) fn execute_route(
+        who: &T::AccountId,
+        trades: &[Trade<T::AccountId>],
+    ) -> DispatchResult {
+        for (idx, trade) in trades.iter().enumerate() {
+            let source = if idx == 0 { who } else { &trade.pool_account };
+            let destination = trades
+                .get(idx + 1)
+                .map(|next| &next.pool_account)
+                .unwrap_or(who);
+
+            Self::execute_trade(who, trade, source, destination)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Finds the route selling `amount_in` of `asset_in` which receives the most of `asset_out`,
+    /// trading through at most `max_hops` pools.
+    ///
+    /// Explores every route of the pool graph up to the given length, so it is meant to be used
+    /// off-chain, e.g. via runtime api, not in extrinsics.
+    ///
+    /// Returns the route and the amount received.
+    pub fn find_best_sell_route(
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        max_hops: u32,
+    ) -> Option<(Vec<AssetId>, Balance)> {
+        Self::find_routes(asset_in, asset_out, max_hops)
+            .into_iter()
+            .filter_map(|route| {
+                let trades = Self::calculate_sell_route(&route, amount_in).ok()?;
+                let amount_out = trades.last()?.amount_out;
+                Some((route, amount_out))
+            })
+            .max_by_key(|(_, amount_out)| *amount_out)
+    }
+
+    /// Finds the route buying `amount_out` of `asset_out` which costs the least of `asset_in`,
+    /// trading through at most `max_hops` pools.
+    ///
+    /// Explores every route of the pool graph up to the given length, so it is meant to be used
+    /// off-chain, e.g. via runtime api, not in extrinsics.
+    ///
+    /// Returns the route and the amount to be paid.
+    pub fn find_best_buy_route(
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: Balance,
+        max_hops: u32,
+    ) -> Option<(Vec<AssetId>, Balance)> {
+        Self::find_routes(asset_in, asset_out, max_hops)
+            .into_iter()
+            .filter_map(|route| {
+                let trades = Self::calculate_buy_route(&route, amount_out).ok()?;
+                let amount_in = trades.first()?.amount_in;
+                Some((route, amount_in))
+            })
+            .min_by_key(|(_, amount_in)| *amount_in)
+    }
+
+    /// Returns all routes from `asset_in` to `asset_out` which do not visit any asset twice,
+    /// trading through at most `max_hops` pools.
+    fn find_routes(asset_in: AssetId, asset_out: AssetId, max_hops: u32) -> Vec<Vec<AssetId>> {
+        let max_len = max_hops.saturating_add(1).min(T::MaxRouteLength::get()) as usize;
+
+        let mut graph: BTreeMap<AssetId, Vec<AssetId>> = BTreeMap::new();
+        for (asset_a, asset_b) in PairPools::<T>::iter_keys() {
+            graph.entry(asset_a).or_default().push(asset_b);
+            graph.entry(asset_b).or_default().push(asset_a);
+        }
+
+        let mut routes = Vec::new();
+        let mut route = vec![asset_in];
+        Self::visit(&graph, asset_out, max_len, &mut route, &mut routes);
+
+        routes
+    }
+
+    fn visit(
+        graph: &BTreeMap<AssetId, Vec<AssetId>>,
+        asset_out: AssetId,
+        max_len: usize,
+        route: &mut Vec<AssetId>,
+        routes: &mut Vec<Vec<AssetId>>,
+    ) {
+        let current = match route.last() {
+            Some(asset) => *asset,
+            None => return,
+        };
+
+        if current == asset_out {
+            if route.len() >= 2 {
+                routes.push(route.clone());
+            }
+            return;
+        }
+
+        if route.len() >= max_len {
+            return;
+        }
+
+        for next in graph.get(&current).into_iter().flatten() {
+            if !route.contains(next) {
+                route.push(*next);
+                Self::visit(graph, asset_out, max_len, route, routes);
+                route.pop();
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::traits::PriceOracle;
+use crate::types::{PoolType, Schedule};
+use crate::{
+    AssetId, Balance, Config, Error, Event, Pallet, ScheduleId, ScheduledExecutions, Schedules,
+};
+use frame_support::ensure;
+use frame_support::storage::with_storage_layer;
+use frame_support::traits::tokens::fungibles::MutateHold;
+use frame_support::traits::Get;
+use sp_runtime::traits::{One, Saturating, Zero};
+use sp_runtime::{DispatchError, FixedPointNumber, FixedU128};
+
+/// Number of consecutive blocks tried when the planned block of an execution is full.
+const PLANNING_ATTEMPTS: u32 = 10;
+
+impl<T: Config> Pallet<T> {
+    /// Plans execution of a schedule in `block`, or the first of the following blocks which
+    /// has room for it.
+    ///
+    /// Returns the block of the execution.
+    pub(crate) fn plan_execution(
+        schedule_id: ScheduleId,
+        block: T::BlockNumber,
+    ) -> Result<T::BlockNumber, DispatchError> {
+        let mut block = block;
+
+        for _ in 0..PLANNING_ATTEMPTS {
+            if ScheduledExecutions::<T>::try_mutate(block, |schedule_ids| {
+                schedule_ids.try_push(schedule_id)
+            })
+            .is_ok()
+            {
+                return Ok(block);
+            }
+
+            block = block.saturating_add(One::one());
+        }
+
+        Err(Error::<T>::TooManySchedules.into())
+    }
+
+    /// Executes schedules planned for block `now`.
+    ///
+    /// Returns number of executed schedules.
+    pub(crate) fn execute_schedules(now: T::BlockNumber) -> u32 {
+        let schedule_ids = ScheduledExecutions::<T>::take(now);
+
+        for schedule_id in schedule_ids.iter() {
+            Self::execute_schedule(*schedule_id, now);
+        }
+
+        schedule_ids.len() as u32
+    }
+
+    /// Executes a scheduled sell and plans the next one, unless the budget is used up
+    /// or the schedule is terminated.
+    fn execute_schedule(schedule_id: ScheduleId, now: T::BlockNumber) {
+        let mut schedule = match Self::schedule(schedule_id) {
+            Some(schedule) => schedule,
+            None => return,
+        };
+
+        match with_storage_layer(|| Self::sell_scheduled(&schedule)) {
+            Ok((amount_in, amount_out)) => {
+                schedule.remaining = schedule.remaining.saturating_sub(amount_in);
+                schedule.failures = 0;
+
+                Self::deposit_event(Event::ScheduleExecuted {
+                    who: schedule.owner.clone(),
+                    schedule_id,
+                    amount_in,
+                    amount_out,
+                    remaining: schedule.remaining,
+                });
+
+                if schedule.remaining.is_zero() {
+                    Schedules::<T>::remove(schedule_id);
+                    return;
+                }
+            }
+            Err(error) => {
+                Self::deposit_event(Event::ScheduleExecutionFailed {
+                    who: schedule.owner.clone(),
+                    schedule_id,
+                    error,
+                });
+
+                schedule.failures = schedule.failures.saturating_add(1);
+
+                if schedule.failures >= T::MaxScheduleFailures::get()
+                    || Self::is_unrecoverable(&schedule, error, now)
+                {
+                    Self::terminate_schedule(schedule_id, &schedule, error);
+                    return;
+                }
+            }
+        }
+
+        match Self::plan_execution(schedule_id, now.saturating_add(schedule.period)) {
+            Ok(block) => {
+                schedule.next_execution = block;
+                Schedules::<T>::insert(schedule_id, schedule);
+            }
+            Err(error) => Self::terminate_schedule(schedule_id, &schedule, error),
+        }
+    }
+
+    /// Sells the amount of a schedule, or its remaining budget if it is lower, from the held
+    /// budget.
+    ///
+    /// Returns amounts sold and received.
+    fn sell_scheduled(
+        schedule: &Schedule<T::AccountId, T::BlockNumber>,
+    ) -> Result<(Balance, Balance), DispatchError> {
+        let amount_in = schedule.amount.min(schedule.remaining);
+
+        let trade = Self::calculate_sell(schedule.asset_in, schedule.asset_out, amount_in)?;
+
+        let price = Self::reference_price(schedule.asset_in, schedule.asset_out)
+            .ok_or(Error::<T>::PoolNotFound)?;
+        let min_amount_out = schedule
+            .slippage
+            .left_from_one()
+            .mul_floor(price.saturating_mul_int(amount_in));
+
+        ensure!(trade.amount_out >= min_amount_out, Error::<T>::Limit);
+
+        T::Currency::transfer_held(
+            schedule.asset_in,
+            &schedule.owner,
+            &trade.pool_account,
+            amount_in,
+            false,
+            false,
+        )?;
+        Self::execute_trade(
+            &schedule.owner,
+            &trade,
+            &trade.pool_account,
+            &schedule.owner,
+        )?;
+
+        Ok((amount_in, trade.amount_out))
+    }
+
+    /// Returns price of `asset_a` denominated in `asset_b` averaged over the shortest oracle
+    /// window, or the spot price if the oracle has no price of the pair yet.
+    fn reference_price(asset_a: AssetId, asset_b: AssetId) -> Option<FixedU128> {
+        T::OracleWindows::get()
+            .into_iter()
+            .min()
+            .and_then(|window| Self::get_price(asset_a, asset_b, window))
+            .map(|(price, _)| price)
+            .or_else(|| {
+                Self::spot_price(asset_a, asset_b, FixedU128::DIV).map(FixedU128::from_inner)
+            })
+    }
+
+    /// Returns whether no later execution of a schedule can succeed after it failed with
+    /// `error`, because its pool no longer exists or the sale of its pool has ended.
+    fn is_unrecoverable(
+        schedule: &Schedule<T::AccountId, T::BlockNumber>,
+        error: DispatchError,
+        now: T::BlockNumber,
+    ) -> bool {
+        if error == Error::<T>::PoolNotFound.into() {
+            return true;
+        }
+
+        if error == Error::<T>::SaleNotRunning.into() {
+            let pair = Self::pair(schedule.asset_in, schedule.asset_out);
+
+            return match Self::pair_pool(pair) {
+                Some((_, pool)) => match pool.pool_type {
+                    PoolType::LBP(params) => params.end < now,
+                    _ => false,
+                },
+                None => true,
+            };
+        }
+
+        false
+    }
+
+    /// Removes a schedule and releases its remaining budget.
+    fn terminate_schedule(
+        schedule_id: ScheduleId,
+        schedule: &Schedule<T::AccountId, T::BlockNumber>,
+        error: DispatchError,
+    ) {
+        let _ = T::Currency::release(schedule.asset_in, &schedule.owner, schedule.remaining, true);
+
+        Schedules::<T>::remove(schedule_id);
+
+        Self::deposit_event(Event::ScheduleTerminated {
+            who: schedule.owner.clone(),
+            schedule_id,
+            remaining: schedule.remaining,
+            error,
+        });
+    }
+}
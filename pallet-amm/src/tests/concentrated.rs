@@ -0,0 +1,804 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
+
+use crate::traits::{AccountIdFor, OnTrade};
+use crate::types::{ConcentratedParams, ConcentratedState, PoolInfo, PoolType};
+use crate::weights::WeightInfo;
+use crate::{Balance, ConcentratedPools, Error, Event, Pools, Positions, Ticks};
+use frame_support::sp_runtime::Permill;
+use frame_support::weights::GetDispatchInfo;
+use frame_support::{assert_noop, assert_ok};
+use math::concentrated::{self, SqrtPrice, SwapState, Tick};
+use sp_runtime::traits::BadOrigin;
+
+const ONE: Balance = 1_000_000_000_000;
+
+/// Square root of price of one.
+const PRICE_ONE: SqrtPrice = 1 << 64;
+
+fn create_concentrated_pool() {
+    assert_ok!(XYK::create_concentrated_pool(
+        Origin::signed(ALICE),
+        HDX,
+        DOT,
+        10,
+        PRICE_ONE,
+        TradeFee::get(),
+    ));
+}
+
+fn mint_position(who: AccountId, tick_lower: Tick, tick_upper: Tick, amount: Balance) {
+    assert_ok!(XYK::mint_position(
+        Origin::signed(who),
+        0,
+        tick_lower,
+        tick_upper,
+        amount,
+        amount,
+        0,
+        None,
+    ));
+}
+
+fn pool_state() -> ConcentratedState {
+    ConcentratedPools::<Test>::get(0).unwrap()
+}
+
+fn sqrt_price(tick: Tick) -> SqrtPrice {
+    concentrated::sqrt_price_at_tick(tick).unwrap()
+}
+
+#[test]
+fn create_concentrated_pool_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+
+        assert_eq!(
+            Pools::<Test>::get(0),
+            Some(PoolInfo {
+                assets: vec![HDX, DOT].try_into().unwrap(),
+                share_asset: None,
+                pool_type: PoolType::Concentrated(ConcentratedParams { tick_spacing: 10 }),
+                fee: TradeFee::get(),
+            })
+        );
+        assert_eq!(
+            pool_state(),
+            ConcentratedState {
+                sqrt_price: PRICE_ONE,
+                tick: 0,
+                liquidity: 0,
+                fee_growth_a: 0,
+                fee_growth_b: 0,
+                ticks: 0,
+            }
+        );
+        assert_eq!(XYK::pair_pool_id((HDX, DOT)), Some(0));
+        assert_eq!(XYK::share_asset(HDX, DOT), None);
+
+        expect_events(vec![Event::<Test>::ConcentratedPoolCreated {
+            who: ALICE,
+            pool_id: 0,
+            asset_a: HDX,
+            asset_b: DOT,
+            tick_spacing: 10,
+            sqrt_price: PRICE_ONE,
+            fee: TradeFee::get(),
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn create_concentrated_pool_should_store_price_of_first_asset_of_pair() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_concentrated_pool(
+            Origin::signed(ALICE),
+            DOT,
+            HDX,
+            10,
+            2 * PRICE_ONE,
+            TradeFee::get(),
+        ));
+
+        assert_eq!(pool_state().sqrt_price, PRICE_ONE / 2);
+        assert_eq!(
+            pool_state().tick,
+            concentrated::tick_at_sqrt_price(PRICE_ONE / 2).unwrap()
+        );
+        assert_eq!(XYK::spot_price(DOT, HDX, ONE), Some(4 * ONE));
+    });
+}
+
+#[test]
+fn create_concentrated_pool_should_fail_when_params_are_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let cases = vec![
+            (0, PRICE_ONE),
+            (-10, PRICE_ONE),
+            (10, 0),
+            (10, concentrated::MAX_SQRT_PRICE + 1),
+        ];
+
+        for (tick_spacing, sqrt_price) in cases {
+            assert_noop!(
+                XYK::create_concentrated_pool(
+                    Origin::signed(ALICE),
+                    HDX,
+                    DOT,
+                    tick_spacing,
+                    sqrt_price,
+                    TradeFee::get(),
+                ),
+                Error::<Test>::InvalidTick
+            );
+        }
+
+        assert_noop!(
+            XYK::create_concentrated_pool(
+                Origin::signed(BOB),
+                HDX,
+                DOT,
+                10,
+                PRICE_ONE,
+                TradeFee::get(),
+            ),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn create_concentrated_pool_should_fail_when_pool_of_pair_exists() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_noop!(
+            XYK::create_concentrated_pool(
+                Origin::signed(ALICE),
+                DOT,
+                HDX,
+                10,
+                PRICE_ONE,
+                TradeFee::get(),
+            ),
+            Error::<Test>::PoolAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn mint_position_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+
+        mint_position(ALICE, -1000, 1000, 100 * ONE);
+
+        let liquidity = concentrated::calculate_liquidity_for_amounts(
+            PRICE_ONE,
+            sqrt_price(-1000),
+            sqrt_price(1000),
+            100 * ONE,
+            100 * ONE,
+        )
+        .unwrap();
+        let (amount_a, amount_b) = concentrated::calculate_position_amounts(
+            PRICE_ONE,
+            sqrt_price(-1000),
+            sqrt_price(1000),
+            liquidity,
+            true,
+        )
+        .unwrap();
+
+        // Price is in the middle of the range, so both amounts are used up.
+        assert!(amount_a <= 100 * ONE && amount_a > 100 * ONE - 10);
+        assert!(amount_b <= 100 * ONE && amount_b > 100 * ONE - 10);
+
+        let pool_account = AccountIdConstructor::create_account_id(0).unwrap();
+        assert_eq!(Tokens::balance(HDX, &pool_account), amount_a);
+        assert_eq!(Tokens::balance(DOT, &pool_account), amount_b);
+
+        assert_eq!(pool_state().liquidity, liquidity);
+
+        let position = XYK::position(0).unwrap();
+        assert_eq!(position.owner, ALICE);
+        assert_eq!(position.liquidity, liquidity);
+        assert_eq!(
+            XYK::tick(0, -1000).map(|tick| tick.liquidity_net),
+            Some(liquidity as i128)
+        );
+        assert_eq!(
+            XYK::tick(0, 1000).map(|tick| tick.liquidity_net),
+            Some(-(liquidity as i128))
+        );
+
+        expect_events(vec![Event::<Test>::PositionMinted {
+            who: ALICE,
+            pool_id: 0,
+            position_id: 0,
+            tick_lower: -1000,
+            tick_upper: 1000,
+            liquidity,
+            amount_a,
+            amount_b,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn mint_position_should_use_single_asset_when_range_does_not_contain_price() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+
+        mint_position(CHARLIE, 1000, 2000, 5 * ONE);
+        mint_position(CHARLIE, -2000, -1000, 5 * ONE);
+
+        // Range above the price is provided in the first asset only and range below in the second.
+        assert!(Tokens::balance(HDX, &CHARLIE) <= 5 * ONE + 10);
+        assert!(Tokens::balance(DOT, &CHARLIE) <= 5 * ONE + 10);
+
+        let pool_account = AccountIdConstructor::create_account_id(0).unwrap();
+        assert_eq!(
+            Tokens::balance(HDX, &pool_account),
+            10 * ONE - Tokens::balance(HDX, &CHARLIE)
+        );
+
+        // Liquidity of the pool is provided only by ranges containing the price.
+        assert_eq!(pool_state().liquidity, 0);
+        assert_eq!(Positions::<Test>::iter().count(), 2);
+    });
+}
+
+#[test]
+fn mint_position_should_fail_when_ticks_are_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+
+        let max_tick = concentrated::MAX_TICK - concentrated::MAX_TICK % 10;
+        let cases = vec![
+            (-1005, 1000),
+            (-1000, 1005),
+            (1000, 1000),
+            (1000, -1000),
+            (-max_tick - 10, 1000),
+            (-1000, max_tick + 10),
+        ];
+
+        for (tick_lower, tick_upper) in cases {
+            assert_noop!(
+                XYK::mint_position(
+                    Origin::signed(ALICE),
+                    0,
+                    tick_lower,
+                    tick_upper,
+                    ONE,
+                    ONE,
+                    0,
+                    None,
+                ),
+                Error::<Test>::InvalidTick
+            );
+        }
+    });
+}
+
+#[test]
+fn mint_position_should_fail_when_liquidity_is_below_limit() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+
+        assert_noop!(
+            XYK::mint_position(Origin::signed(ALICE), 0, -1000, 1000, 0, ONE, 0, None),
+            Error::<Test>::InsufficientSharesOut
+        );
+        assert_noop!(
+            XYK::mint_position(
+                Origin::signed(ALICE),
+                0,
+                -1000,
+                1000,
+                ONE,
+                ONE,
+                Balance::MAX,
+                None
+            ),
+            Error::<Test>::InsufficientSharesOut
+        );
+    });
+}
+
+#[test]
+fn mint_position_should_fail_when_pool_has_too_many_ticks() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+        mint_position(ALICE, -100, 100, ONE);
+        mint_position(ALICE, -200, 200, ONE);
+        mint_position(ALICE, -300, 300, ONE);
+
+        assert_eq!(pool_state().ticks, MaxTicksPerPool::get());
+
+        assert_noop!(
+            XYK::mint_position(Origin::signed(ALICE), 0, -400, 400, ONE, ONE, 0, None),
+            Error::<Test>::TooManyTicks
+        );
+        assert_noop!(
+            XYK::mint_position(Origin::signed(ALICE), 0, -100, 400, ONE, ONE, 0, None),
+            Error::<Test>::TooManyTicks
+        );
+
+        // Positions can still end at initialized ticks.
+        mint_position(CHARLIE, -300, 100, ONE);
+
+        // Tick is released when the last position ending at it is burned.
+        let liquidity = XYK::position(2).unwrap().liquidity;
+        assert_ok!(XYK::burn_position(
+            Origin::signed(ALICE),
+            2,
+            liquidity,
+            0,
+            0,
+            None
+        ));
+        assert_eq!(pool_state().ticks, MaxTicksPerPool::get() - 1);
+        assert_eq!(XYK::tick(0, 300), None);
+
+        mint_position(ALICE, -100, 400, ONE);
+        assert_eq!(pool_state().ticks, MaxTicksPerPool::get());
+    });
+}
+
+#[test]
+fn mint_position_should_fail_when_pool_is_not_concentrated() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_noop!(
+            XYK::mint_position(Origin::signed(ALICE), 0, -1000, 1000, ONE, ONE, 0, None),
+            Error::<Test>::NotSupportedByPoolType
+        );
+    });
+}
+
+#[test]
+fn sell_should_work_within_range() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_percent(1))
+        .build()
+        .execute_with(|| {
+            create_concentrated_pool();
+            mint_position(ALICE, -1000, 1000, 100 * ONE);
+
+            let state = pool_state();
+            let expected = concentrated::calculate_swap(
+                SwapState {
+                    sqrt_price: state.sqrt_price,
+                    tick: state.tick,
+                    liquidity: state.liquidity,
+                    fee_growth: 0,
+                },
+                &[
+                    (-1000, state.liquidity as i128),
+                    (1000, -(state.liquidity as i128)),
+                ],
+                5 * ONE,
+                Permill::from_percent(1),
+                Permill::zero(),
+                true,
+            )
+            .unwrap();
+
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+            assert_eq!(Tokens::balance(HDX, &BOB), 5 * ONE);
+            assert_eq!(Tokens::balance(DOT, &BOB), expected.amount_out);
+            // Price of the range is close to one.
+            assert!(expected.amount_out > 4_900_000_000_000);
+
+            assert_eq!(pool_state().sqrt_price, expected.state.sqrt_price);
+            assert_eq!(pool_state().tick, expected.state.tick);
+            assert_eq!(pool_state().fee_growth_a, expected.state.fee_growth);
+            assert_eq!(pool_state().fee_growth_b, 0);
+
+            expect_events(vec![Event::<Test>::SwapExecuted {
+                who: BOB,
+                asset_in: HDX,
+                asset_out: DOT,
+                amount_in: 5 * ONE,
+                amount_out: expected.amount_out,
+                lp_fee: expected.fee,
+                protocol_fee: 0,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn sell_should_cross_ticks() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+        mint_position(ALICE, -100, 100, ONE);
+        mint_position(ALICE, -1000, 1000, 10 * ONE);
+
+        let narrow = XYK::position(0).unwrap().liquidity;
+        let wide = XYK::position(1).unwrap().liquidity;
+        assert_eq!(pool_state().liquidity, narrow + wide);
+
+        assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+        // Price moved below the narrow range, which no longer provides liquidity.
+        assert!(pool_state().tick < -100 && pool_state().tick >= -1000);
+        assert_eq!(pool_state().liquidity, wide);
+
+        assert_ok!(XYK::sell(
+            Origin::signed(ALICE),
+            DOT,
+            HDX,
+            10 * ONE,
+            0,
+            None
+        ));
+
+        // Price moved back above the narrow range.
+        assert!(pool_state().tick >= 100 && pool_state().tick < 1000);
+        assert_eq!(pool_state().liquidity, wide);
+    });
+}
+
+#[test]
+fn sell_should_fail_when_liquidity_is_not_sufficient() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+
+        assert_noop!(
+            XYK::sell(Origin::signed(BOB), HDX, DOT, ONE, 0, None),
+            Error::<Test>::InsufficientLiquidity
+        );
+
+        mint_position(ALICE, -100, 100, ONE);
+
+        assert_noop!(
+            XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None),
+            Error::<Test>::InsufficientLiquidity
+        );
+    });
+}
+
+#[test]
+fn sell_should_transfer_protocol_fee_in_asset_in() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_percent(1))
+        .with_protocol_fee(Permill::from_percent(20))
+        .build()
+        .execute_with(|| {
+            create_concentrated_pool();
+            mint_position(ALICE, -1000, 1000, 100 * ONE);
+
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+            assert_eq!(Tokens::balance(HDX, &TREASURY), ONE / 100);
+            assert_eq!(Tokens::balance(DOT, &TREASURY), 0);
+        });
+}
+
+#[test]
+fn sell_should_charge_weight_of_ticks_read() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+        mint_position(ALICE, -1000, 1000, 100 * ONE);
+
+        let call = crate::Call::<Test>::sell {
+            asset_in: HDX,
+            asset_out: DOT,
+            amount_in: ONE,
+            min_limit: 0,
+            deadline: None,
+        };
+        assert_eq!(
+            call.get_dispatch_info().weight,
+            <() as WeightInfo>::sell()
+                .saturating_add(<() as WeightInfo>::swap_ticks(MaxTicksPerPool::get()))
+                .saturating_add(TradeHandler::on_trade_weight())
+        );
+
+        let post_info = XYK::sell(Origin::signed(BOB), HDX, DOT, ONE, 0, None).unwrap();
+
+        // Only the two ticks of the pool were read.
+        assert_eq!(
+            post_info.actual_weight,
+            Some(
+                <() as WeightInfo>::sell()
+                    .saturating_add(<() as WeightInfo>::swap_ticks(2))
+                    .saturating_add(TradeHandler::on_trade_weight())
+            )
+        );
+    });
+}
+
+#[test]
+fn buy_should_cross_ticks() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_percent(1))
+        .with_protocol_fee(Permill::from_percent(20))
+        .build()
+        .execute_with(|| {
+            create_concentrated_pool();
+            mint_position(ALICE, -100, 100, ONE);
+            mint_position(ALICE, -1000, 1000, 10 * ONE);
+
+            let narrow = XYK::position(0).unwrap().liquidity;
+            let wide = XYK::position(1).unwrap().liquidity;
+
+            let state = pool_state();
+            let expected = concentrated::calculate_swap_exact_out(
+                SwapState {
+                    sqrt_price: state.sqrt_price,
+                    tick: state.tick,
+                    liquidity: state.liquidity,
+                    fee_growth: 0,
+                },
+                &[
+                    (-1000, wide as i128),
+                    (-100, narrow as i128),
+                    (100, -(narrow as i128)),
+                    (1000, -(wide as i128)),
+                ],
+                4 * ONE,
+                Permill::from_percent(1),
+                Permill::from_percent(20),
+                true,
+            )
+            .unwrap();
+
+            assert_eq!(
+                XYK::quote_buy(DOT, HDX, 4 * ONE),
+                Some((expected.amount_in, expected.fee))
+            );
+
+            assert_ok!(XYK::buy(
+                Origin::signed(BOB),
+                DOT,
+                HDX,
+                4 * ONE,
+                Balance::MAX,
+                None
+            ));
+
+            assert_eq!(Tokens::balance(DOT, &BOB), 4 * ONE);
+            assert_eq!(Tokens::balance(HDX, &BOB), 10 * ONE - expected.amount_in);
+            assert_eq!(Tokens::balance(HDX, &TREASURY), expected.protocol_fee);
+
+            // Price moved below the narrow range, which no longer provides liquidity.
+            assert_eq!(pool_state().sqrt_price, expected.state.sqrt_price);
+            assert!(pool_state().tick < -100 && pool_state().tick >= -1000);
+            assert_eq!(pool_state().liquidity, wide);
+            assert_eq!(pool_state().fee_growth_a, expected.state.fee_growth);
+        });
+}
+
+#[test]
+fn buy_should_fail_when_liquidity_is_not_sufficient() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+        mint_position(ALICE, -100, 100, ONE);
+
+        assert_noop!(
+            XYK::buy(Origin::signed(BOB), DOT, HDX, 2 * ONE, Balance::MAX, None),
+            Error::<Test>::InsufficientLiquidity
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_should_not_be_supported() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+        mint_position(ALICE, -1000, 1000, 100 * ONE);
+
+        assert_noop!(
+            XYK::add_liquidity(Origin::signed(ALICE), HDX, DOT, ONE, ONE, 0, None),
+            Error::<Test>::NotSupportedByPoolType
+        );
+    });
+}
+
+#[test]
+fn fees_should_be_earned_by_positions_containing_price() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_percent(1))
+        .build()
+        .execute_with(|| {
+            create_concentrated_pool();
+            mint_position(ALICE, -1000, 1000, 3 * ONE);
+            mint_position(CHARLIE, -1000, 1000, ONE);
+            mint_position(ALICE, 1000, 2000, ONE);
+
+            let liquidity_alice = XYK::position(0).unwrap().liquidity;
+            let liquidity_charlie = XYK::position(1).unwrap().liquidity;
+
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, ONE / 10, 0, None));
+
+            let fee = ONE / 1000;
+            let expected_alice = fee * liquidity_alice / (liquidity_alice + liquidity_charlie);
+            let expected_charlie = fee * liquidity_charlie / (liquidity_alice + liquidity_charlie);
+
+            let alice_before = Tokens::balance(HDX, &ALICE);
+            let charlie_before = Tokens::balance(HDX, &CHARLIE);
+
+            // Range above the price did not earn anything.
+            assert_ok!(XYK::collect_fees(Origin::signed(ALICE), 2));
+            assert_eq!(Tokens::balance(HDX, &ALICE), alice_before);
+
+            assert_ok!(XYK::collect_fees(Origin::signed(ALICE), 0));
+            assert_ok!(XYK::collect_fees(Origin::signed(CHARLIE), 1));
+
+            // Fees are rounded down in favour of the pool.
+            let alice_fee = Tokens::balance(HDX, &ALICE) - alice_before;
+            let charlie_fee = Tokens::balance(HDX, &CHARLIE) - charlie_before;
+            assert!(alice_fee <= expected_alice && alice_fee + 2 >= expected_alice);
+            assert!(charlie_fee <= expected_charlie && charlie_fee + 2 >= expected_charlie);
+
+            assert_eq!(
+                XYK::position(0).map(|position| position.fees_owed_a),
+                Some(0)
+            );
+            expect_events(vec![Event::<Test>::FeesCollected {
+                who: CHARLIE,
+                position_id: 1,
+                amount_a: charlie_fee,
+                amount_b: 0,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn collect_fees_should_fail_when_caller_is_not_owner() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+        mint_position(ALICE, -1000, 1000, ONE);
+
+        assert_noop!(
+            XYK::collect_fees(Origin::signed(BOB), 0),
+            Error::<Test>::NotPositionOwner
+        );
+        assert_noop!(
+            XYK::collect_fees(Origin::signed(ALICE), 1),
+            Error::<Test>::PositionNotFound
+        );
+    });
+}
+
+#[test]
+fn burn_position_should_withdraw_liquidity_and_fees() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_percent(1))
+        .build()
+        .execute_with(|| {
+            create_concentrated_pool();
+            mint_position(CHARLIE, -1000, 1000, 5 * ONE);
+
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, ONE, 0, None));
+
+            let position = XYK::position(0).unwrap();
+            let state = pool_state();
+            let (amount_a, amount_b) = concentrated::calculate_position_amounts(
+                state.sqrt_price,
+                sqrt_price(-1000),
+                sqrt_price(1000),
+                position.liquidity,
+                false,
+            )
+            .unwrap();
+
+            let hdx_before = Tokens::balance(HDX, &CHARLIE);
+            let dot_before = Tokens::balance(DOT, &CHARLIE);
+
+            assert_ok!(XYK::burn_position(
+                Origin::signed(CHARLIE),
+                0,
+                position.liquidity,
+                0,
+                0,
+                None,
+            ));
+
+            let fee_a = Tokens::balance(HDX, &CHARLIE) - hdx_before - amount_a;
+            assert_eq!(Tokens::balance(DOT, &CHARLIE) - dot_before, amount_b);
+            // Only provider earned whole fee, except rounding.
+            assert!(fee_a <= ONE / 100 && fee_a + 2 >= ONE / 100);
+
+            assert_eq!(XYK::position(0), None);
+            assert_eq!(Ticks::<Test>::iter_prefix(0).count(), 0);
+            assert_eq!(pool_state().liquidity, 0);
+
+            expect_events(vec![
+                Event::<Test>::PositionBurned {
+                    who: CHARLIE,
+                    position_id: 0,
+                    liquidity: position.liquidity,
+                    amount_a,
+                    amount_b,
+                }
+                .into(),
+                Event::<Test>::FeesCollected {
+                    who: CHARLIE,
+                    position_id: 0,
+                    amount_a: fee_a,
+                    amount_b: 0,
+                }
+                .into(),
+            ]);
+        });
+}
+
+#[test]
+fn burn_position_should_keep_position_when_liquidity_is_left() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+        mint_position(ALICE, -1000, 1000, 10 * ONE);
+
+        let liquidity = XYK::position(0).unwrap().liquidity;
+
+        assert_ok!(XYK::burn_position(
+            Origin::signed(ALICE),
+            0,
+            liquidity / 2,
+            0,
+            0,
+            None,
+        ));
+
+        assert_eq!(
+            XYK::position(0).map(|position| position.liquidity),
+            Some(liquidity - liquidity / 2)
+        );
+        assert_eq!(pool_state().liquidity, liquidity - liquidity / 2);
+        assert_eq!(
+            XYK::tick(0, -1000).map(|tick| tick.liquidity_gross),
+            Some(liquidity - liquidity / 2)
+        );
+    });
+}
+
+#[test]
+fn burn_position_should_fail_when_params_are_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_concentrated_pool();
+        mint_position(ALICE, -1000, 1000, 10 * ONE);
+
+        let liquidity = XYK::position(0).unwrap().liquidity;
+
+        assert_noop!(
+            XYK::burn_position(Origin::signed(BOB), 0, liquidity, 0, 0, None),
+            Error::<Test>::NotPositionOwner
+        );
+        assert_noop!(
+            XYK::burn_position(Origin::signed(ALICE), 1, liquidity, 0, 0, None),
+            Error::<Test>::PositionNotFound
+        );
+        assert_noop!(
+            XYK::burn_position(Origin::signed(ALICE), 0, liquidity + 1, 0, 0, None),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_noop!(
+            XYK::burn_position(Origin::signed(ALICE), 0, liquidity, 10 * ONE, 0, None),
+            Error::<Test>::InsufficientAmountOut
+        );
+    });
+}
@@ -1,6 +1,14 @@
 use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
 
-use frame_support::assert_ok;
+use crate::traits::AccountIdFor;
+use crate::{AssetId, Balance, Error, Event, Pools};
+use frame_support::sp_runtime::Permill;
+use frame_support::traits::Get;
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use sp_runtime::traits::BadOrigin;
+
+const ONE: Balance = 1_000_000_000_000;
 
 #[test]
 fn create_pool_should_work() {
@@ -13,6 +21,356 @@ fn create_pool_should_work() {
             100_000_000_000_000,
             asset_b,
             100_000_000_000_000,
+            TradeFee::get(),
+        ));
+    });
+}
+
+#[test]
+fn create_pool_should_lock_minimum_liquidity_in_pool_account() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+
+        let pool_account = AccountIdConstructor::create_account_id(0).unwrap();
+
+        assert_eq!(Tokens::total_issuance(POOL_SHARE_ASSET), 14_142_135_623_730);
+        assert_eq!(
+            Tokens::balance(POOL_SHARE_ASSET, &pool_account),
+            MinimumLiquidity::get()
+        );
+        assert_eq!(
+            Tokens::balance(POOL_SHARE_ASSET, &ALICE),
+            14_142_135_623_730 - MinimumLiquidity::get()
+        );
+    });
+}
+
+#[test]
+fn create_pool_should_issue_same_shares_when_assets_are_inverted() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            DOT,
+            10 * ONE,
+            HDX,
+            20 * ONE,
+            TradeFee::get(),
         ));
+
+        assert_eq!(XYK::pool_reserves(HDX, DOT), Some((20 * ONE, 10 * ONE)));
+        assert_eq!(
+            Tokens::balance(POOL_SHARE_ASSET, &ALICE),
+            14_142_135_623_730 - MinimumLiquidity::get()
+        );
+    });
+}
+
+#[test]
+fn create_pool_should_fail_when_initial_liquidity_is_below_minimum() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                1_000,
+                DOT,
+                1_000,
+                TradeFee::get()
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+    });
+}
+
+#[test]
+fn genesis_pool_should_be_created() {
+    ExtBuilder::default()
+        .with_pool(HDX, DOT, 100 * ONE, 200 * ONE, ALICE)
+        .build()
+        .execute_with(|| {
+            assert_eq!(XYK::share_asset(HDX, DOT), Some(POOL_SHARE_ASSET));
+            assert_eq!(XYK::pool_reserves(HDX, DOT), Some((100 * ONE, 200 * ONE)));
+            assert_eq!(
+                Tokens::balance(POOL_SHARE_ASSET, &ALICE),
+                141_421_356_237_309 - MinimumLiquidity::get()
+            );
+            assert_eq!(
+                Tokens::total_issuance(POOL_SHARE_ASSET),
+                141_421_356_237_309
+            );
+        });
+}
+
+#[test]
+fn genesis_pool_should_keep_reserves_of_given_assets_when_assets_are_inverted() {
+    ExtBuilder::default()
+        .with_pool(DOT, HDX, 200 * ONE, 100 * ONE, ALICE)
+        .build()
+        .execute_with(|| {
+            assert_eq!(XYK::pool_reserves(HDX, DOT), Some((100 * ONE, 200 * ONE)));
+            assert_eq!(
+                Tokens::balance(POOL_SHARE_ASSET, &ALICE),
+                141_421_356_237_309 - MinimumLiquidity::get()
+            );
+        });
+}
+
+#[test]
+fn genesis_pool_should_be_tradable() {
+    ExtBuilder::default()
+        .with_pool(HDX, DOT, 100 * ONE, 100 * ONE, ALICE)
+        .build()
+        .execute_with(|| {
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+            assert_eq!(Tokens::balance(DOT, &BOB), 4_761_904_761_904);
+        });
+}
+
+#[test]
+fn create_pool_should_fail_when_genesis_pool_exists() {
+    ExtBuilder::default()
+        .with_pool(HDX, DOT, 100 * ONE, 100 * ONE, ALICE)
+        .build()
+        .execute_with(|| {
+            assert_noop!(
+                XYK::create_pool(
+                    Origin::signed(ALICE),
+                    HDX,
+                    10 * ONE,
+                    DOT,
+                    10 * ONE,
+                    TradeFee::get()
+                ),
+                Error::<Test>::PoolAlreadyExists
+            );
+        });
+}
+
+#[test]
+fn create_pool_should_fail_when_max_pools_is_reached() {
+    ExtBuilder::default().build().execute_with(|| {
+        for (asset_a, asset_b) in [(HDX, DOT), (HDX, USDT), (DOT, USDT)] {
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                asset_a,
+                10 * ONE,
+                asset_b,
+                10 * ONE,
+                TradeFee::get()
+            ));
+        }
+
+        let assets: BoundedVec<(AssetId, Balance), MaxAssetsInPool> =
+            vec![(HDX, 10 * ONE), (DOT, 10 * ONE), (USDT, 10 * ONE)]
+                .try_into()
+                .unwrap();
+
+        assert_ok!(XYK::create_multi_asset_pool(
+            Origin::signed(ALICE),
+            assets.clone(),
+            100
+        ));
+        assert_ok!(XYK::create_multi_asset_pool(
+            Origin::signed(ALICE),
+            assets.clone(),
+            100
+        ));
+
+        assert_eq!(Pools::<Test>::count(), MaxPools::get());
+
+        assert_noop!(
+            XYK::create_multi_asset_pool(Origin::signed(ALICE), assets, 100),
+            Error::<Test>::TooManyPools
+        );
+    });
+}
+
+#[test]
+fn create_pool_should_fail_when_origin_is_not_authority() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            XYK::create_pool(
+                Origin::signed(CHARLIE),
+                HDX,
+                5 * ONE,
+                DOT,
+                5 * ONE,
+                TradeFee::get()
+            ),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn create_pool_should_transfer_creation_fee_to_treasury() {
+    ExtBuilder::default()
+        .with_creation_fee(10 * ONE)
+        .build()
+        .execute_with(|| {
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                100 * ONE,
+                DOT,
+                100 * ONE,
+                TradeFee::get(),
+            ));
+
+            assert_eq!(Tokens::balance(HDX, &TREASURY), 10 * ONE);
+            assert_eq!(Tokens::balance(HDX, &ALICE), 890 * ONE);
+            assert_eq!(XYK::pool_reserves(HDX, DOT), Some((100 * ONE, 100 * ONE)));
+        });
+}
+
+#[test]
+fn create_pool_should_fail_when_creation_fee_cannot_be_paid() {
+    ExtBuilder::default()
+        .with_creation_fee(1_001 * ONE)
+        .build()
+        .execute_with(|| {
+            assert_noop!(
+                XYK::create_pool(
+                    Origin::signed(ALICE),
+                    HDX,
+                    100 * ONE,
+                    DOT,
+                    100 * ONE,
+                    TradeFee::get()
+                ),
+                orml_tokens::Error::<Test>::BalanceTooLow
+            );
+        });
+}
+
+#[test]
+fn create_pool_should_store_fee_of_pool() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            Permill::from_percent(1),
+        ));
+
+        assert_eq!(
+            Pools::<Test>::get(0).map(|pool| pool.fee),
+            Some(Permill::from_percent(1))
+        );
+
+        expect_events(vec![Event::<Test>::PoolCreated {
+            who: ALICE,
+            asset_a: HDX,
+            asset_b: DOT,
+            shares: 100 * ONE - MinimumLiquidity::get(),
+            share_asset_id: POOL_SHARE_ASSET,
+            fee: Permill::from_percent(1),
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn create_pool_should_fail_when_fee_exceeds_maximum() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                100 * ONE,
+                DOT,
+                100 * ONE,
+                MaxTradeFee::get() + Permill::from_parts(1),
+            ),
+            Error::<Test>::InvalidFee
+        );
+    });
+}
+
+#[test]
+fn update_pool_fee_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            Permill::from_percent(1),
+        ));
+
+        assert_ok!(XYK::update_pool_fee(
+            Origin::root(),
+            0,
+            Permill::from_percent(5)
+        ));
+
+        assert_eq!(
+            Pools::<Test>::get(0).map(|pool| pool.fee),
+            Some(Permill::from_percent(5))
+        );
+
+        expect_events(vec![Event::<Test>::PoolFeeUpdated {
+            pool_id: 0,
+            fee: Permill::from_percent(5),
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn update_pool_fee_should_fail_when_origin_is_not_authority() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            Permill::from_percent(1),
+        ));
+
+        assert_noop!(
+            XYK::update_pool_fee(Origin::signed(BOB), 0, Permill::from_percent(5)),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn update_pool_fee_should_fail_when_fee_is_invalid_or_pool_does_not_exist() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            XYK::update_pool_fee(Origin::root(), 0, Permill::from_percent(5)),
+            Error::<Test>::PoolNotFound
+        );
+
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            Permill::from_percent(1),
+        ));
+
+        assert_noop!(
+            XYK::update_pool_fee(
+                Origin::root(),
+                0,
+                MaxTradeFee::get() + Permill::from_parts(1)
+            ),
+            Error::<Test>::InvalidFee
+        );
     });
 }
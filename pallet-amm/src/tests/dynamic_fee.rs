@@ -0,0 +1,256 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
+
+use crate::traits::FeeCurve;
+use crate::types::FeeEntry;
+use crate::{Balance, VolatilityFeeCurve};
+use frame_support::assert_ok;
+use frame_support::sp_runtime::traits::Saturating;
+use frame_support::sp_runtime::Permill;
+
+const ONE: Balance = 1_000_000_000_000;
+
+const POOL_FEE: Permill = Permill::from_parts(3_000);
+
+fn create_pool(amount: Balance) {
+    assert_ok!(XYK::create_pool(
+        Origin::signed(ALICE),
+        HDX,
+        amount,
+        DOT,
+        amount,
+        POOL_FEE,
+    ));
+}
+
+#[test]
+fn volatility_fee_curve_should_work() {
+    type Curve = VolatilityFeeCurve<FeeDecay, FeeImpactFactor>;
+
+    ExtBuilder::default()
+        .with_fee_impact_factor(Permill::from_percent(50))
+        .build()
+        .execute_with(|| {
+            let cases = vec![
+                (
+                    Permill::from_parts(3_000),
+                    Permill::zero(),
+                    Permill::from_parts(3_000),
+                ),
+                (
+                    Permill::from_parts(3_000),
+                    Permill::from_percent(5),
+                    Permill::from_parts(28_000),
+                ),
+                (
+                    Permill::from_percent(60),
+                    Permill::from_percent(100),
+                    Permill::one(),
+                ),
+            ];
+            for (fee, price_impact, expected) in cases {
+                assert_eq!(Curve::raise(fee, price_impact), expected);
+            }
+
+            let cases = vec![
+                (0, Permill::from_parts(28_000)),
+                (1, Permill::from_parts(15_500)),
+                (2, Permill::from_parts(9_250)),
+                (100, POOL_FEE),
+            ];
+            for (elapsed, expected) in cases {
+                assert_eq!(
+                    Curve::decay(Permill::from_parts(28_000), POOL_FEE, elapsed),
+                    expected
+                );
+            }
+        });
+}
+
+#[test]
+fn sell_should_raise_fee_of_pool_by_price_impact() {
+    ExtBuilder::default()
+        .with_fee_impact_factor(Permill::from_percent(50))
+        .build()
+        .execute_with(|| {
+            create_pool(100 * ONE);
+
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+            assert_eq!(
+                XYK::dynamic_fee(0),
+                Some(FeeEntry {
+                    fee: Permill::from_parts(28_000),
+                    updated_at: 1,
+                })
+            );
+            assert_eq!(XYK::current_fee(0, POOL_FEE), Permill::from_parts(28_000));
+        });
+}
+
+#[test]
+fn fee_of_pool_should_decay_to_its_floor() {
+    ExtBuilder::default()
+        .with_fee_impact_factor(Permill::from_percent(50))
+        .build()
+        .execute_with(|| {
+            create_pool(100 * ONE);
+
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+            System::set_block_number(2);
+            assert_eq!(XYK::current_fee(0, POOL_FEE), Permill::from_parts(15_500));
+
+            System::set_block_number(100);
+            assert_eq!(XYK::current_fee(0, POOL_FEE), POOL_FEE);
+
+            // Next trade raises the fee from the floor.
+            assert_ok!(XYK::sell(
+                Origin::signed(BOB),
+                HDX,
+                DOT,
+                105 * ONE / 10_000,
+                0,
+                None
+            ));
+            assert_eq!(XYK::current_fee(0, POOL_FEE), Permill::from_parts(3_050));
+        });
+}
+
+#[test]
+fn buy_should_pay_raised_fee() {
+    ExtBuilder::default()
+        .with_fee_impact_factor(Permill::from_percent(50))
+        .build()
+        .execute_with(|| {
+            create_pool(100 * ONE);
+
+            let (_, floor_fee) = XYK::quote_buy(DOT, HDX, ONE).unwrap();
+
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+            let (_, raised_fee) = XYK::quote_buy(DOT, HDX, ONE).unwrap();
+            assert!(raised_fee > 9 * floor_fee);
+        });
+}
+
+#[test]
+fn fee_of_pool_should_not_exceed_maximum() {
+    ExtBuilder::default()
+        .with_fee_impact_factor(Permill::from_percent(50))
+        .build()
+        .execute_with(|| {
+            create_pool(10 * ONE);
+
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 9 * ONE, 0, None));
+
+            assert_eq!(XYK::current_fee(0, POOL_FEE), MaxTradeFee::get());
+        });
+}
+
+#[test]
+fn fee_of_pool_should_stay_constant_without_fee_curve_impact() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool(100 * ONE);
+
+        assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+        assert_eq!(XYK::dynamic_fee(0), None);
+        assert_eq!(XYK::current_fee(0, POOL_FEE), POOL_FEE);
+    });
+}
+
+#[test]
+fn add_liquidity_single_asset_should_pay_and_raise_fee() {
+    ExtBuilder::default()
+        .with_fee_impact_factor(Permill::from_percent(50))
+        .build()
+        .execute_with(|| {
+            create_pool(100 * ONE);
+
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+            let (hdx_reserve, dot_reserve) = XYK::pool_reserves(HDX, DOT).unwrap();
+            let (shares, swap_amount, _) = math::xyk::calculate_shares_for_single_asset(
+                hdx_reserve,
+                dot_reserve,
+                5 * ONE,
+                Tokens::total_issuance(POOL_SHARE_ASSET),
+                Permill::from_parts(28_000),
+            )
+            .unwrap();
+
+            assert_ok!(XYK::add_liquidity_single_asset(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                5 * ONE,
+                0,
+                None,
+            ));
+
+            assert_eq!(Tokens::balance(POOL_SHARE_ASSET, &CHARLIE), shares);
+            assert_eq!(
+                XYK::current_fee(0, POOL_FEE),
+                Permill::from_parts(28_000).saturating_add(
+                    Permill::from_percent(50)
+                        .saturating_mul(Permill::from_rational(swap_amount, hdx_reserve))
+                )
+            );
+        });
+}
+
+#[test]
+fn remove_liquidity_single_asset_should_pay_and_raise_fee() {
+    ExtBuilder::default()
+        .with_fee_impact_factor(Permill::from_percent(50))
+        .build()
+        .execute_with(|| {
+            create_pool(100 * ONE);
+
+            assert_ok!(XYK::add_liquidity(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                5 * ONE,
+                u128::MAX,
+                0,
+                None,
+            ));
+
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+            let shares = Tokens::balance(POOL_SHARE_ASSET, &CHARLIE);
+            let hdx_balance = Tokens::balance(HDX, &CHARLIE);
+            let (hdx_reserve, dot_reserve) = XYK::pool_reserves(HDX, DOT).unwrap();
+            let (amount, swap_amount, _) = math::xyk::calculate_single_asset_liquidity_out(
+                hdx_reserve,
+                dot_reserve,
+                shares,
+                Tokens::total_issuance(POOL_SHARE_ASSET),
+                WithdrawFee::get(),
+                Permill::from_parts(28_000),
+            )
+            .unwrap();
+
+            assert_ok!(XYK::remove_liquidity_single_asset(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                shares,
+                0,
+                None,
+            ));
+
+            assert_eq!(Tokens::balance(HDX, &CHARLIE), hdx_balance + amount);
+            assert_eq!(
+                XYK::current_fee(0, POOL_FEE),
+                Permill::from_parts(28_000).saturating_add(
+                    Permill::from_percent(50).saturating_mul(Permill::from_rational(
+                        swap_amount,
+                        dot_reserve - swap_amount
+                    ))
+                )
+            );
+        });
+}
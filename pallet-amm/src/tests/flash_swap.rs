@@ -0,0 +1,247 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
+
+use crate::traits::{AccountIdFor, FlashSwapHandler, OnTrade};
+use crate::types::FeeEntry;
+use crate::weights::WeightInfo;
+use crate::{Balance, Error, Event};
+use frame_support::sp_runtime::Permill;
+use frame_support::weights::GetDispatchInfo;
+use frame_support::{assert_noop, assert_ok};
+
+const ONE: Balance = 1_000_000_000_000;
+
+fn create_pool() {
+    assert_ok!(XYK::create_pool(
+        Origin::signed(ALICE),
+        HDX,
+        100 * ONE,
+        DOT,
+        100 * ONE,
+        Permill::from_parts(3_000),
+    ));
+}
+
+#[test]
+fn flash_swap_should_work_when_repaid_in_borrowed_asset() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+        set_flash_swap_repayment(vec![(DOT, 5 * ONE + ONE / 50)]);
+
+        assert_ok!(XYK::flash_swap(
+            Origin::signed(CHARLIE),
+            DOT,
+            HDX,
+            5 * ONE,
+            None
+        ));
+
+        let pool_account = AccountIdConstructor::create_account_id(0).unwrap();
+        assert_eq!(
+            recorded_flash_swaps(),
+            vec![(CHARLIE, pool_account, HDX, DOT, 5 * ONE)]
+        );
+
+        assert_eq!(Tokens::balance(DOT, &CHARLIE), 10 * ONE - ONE / 50);
+        assert_eq!(
+            XYK::pool_reserves(HDX, DOT),
+            Some((100 * ONE, 100 * ONE + ONE / 50))
+        );
+
+        expect_events(vec![Event::<Test>::FlashSwapExecuted {
+            who: CHARLIE,
+            asset_in: HDX,
+            asset_out: DOT,
+            amount_in: 0,
+            amount_out: 0,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn flash_swap_should_transfer_protocol_fee() {
+    ExtBuilder::default()
+        .with_protocol_fee(Permill::from_percent(50))
+        .build()
+        .execute_with(|| {
+            create_pool();
+            set_flash_swap_repayment(vec![(DOT, 5 * ONE + ONE / 50)]);
+
+            assert_ok!(XYK::flash_swap(
+                Origin::signed(CHARLIE),
+                DOT,
+                HDX,
+                5 * ONE,
+                None
+            ));
+
+            // Fee of 0.3% is paid from 5.02 DOT repaid, half of it goes to the protocol.
+            assert_eq!(Tokens::balance(DOT, &TREASURY), 7_530_000_000);
+            assert_eq!(
+                XYK::pool_reserves(HDX, DOT),
+                Some((100 * ONE, 100 * ONE + ONE / 50 - 7_530_000_000))
+            );
+        });
+}
+
+#[test]
+fn flash_swap_should_raise_fee_of_pool_by_price_impact() {
+    ExtBuilder::default()
+        .with_fee_impact_factor(Permill::from_percent(50))
+        .build()
+        .execute_with(|| {
+            create_pool();
+            set_flash_swap_repayment(vec![(HDX, 5_300_000_000_000)]);
+
+            assert_ok!(XYK::flash_swap(
+                Origin::signed(CHARLIE),
+                DOT,
+                HDX,
+                5 * ONE,
+                None
+            ));
+
+            assert_eq!(
+                XYK::dynamic_fee(0),
+                Some(FeeEntry {
+                    fee: Permill::from_parts(29_500),
+                    updated_at: 1,
+                })
+            );
+        });
+}
+
+#[test]
+fn flash_swap_should_work_when_repaid_in_other_asset() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+        set_flash_swap_repayment(vec![(HDX, 5_300_000_000_000)]);
+
+        assert_ok!(XYK::flash_swap(
+            Origin::signed(CHARLIE),
+            DOT,
+            HDX,
+            5 * ONE,
+            None
+        ));
+
+        assert_eq!(Tokens::balance(HDX, &CHARLIE), 4_700_000_000_000);
+        assert_eq!(Tokens::balance(DOT, &CHARLIE), 15 * ONE);
+        assert_eq!(
+            XYK::pool_reserves(HDX, DOT),
+            Some((105_300_000_000_000, 95 * ONE))
+        );
+
+        expect_events(vec![Event::<Test>::FlashSwapExecuted {
+            who: CHARLIE,
+            asset_in: HDX,
+            asset_out: DOT,
+            amount_in: 5_300_000_000_000,
+            amount_out: 5 * ONE,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn flash_swap_should_call_trade_handler() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+        set_flash_swap_repayment(vec![(HDX, 5_300_000_000_000)]);
+
+        assert_ok!(XYK::flash_swap(
+            Origin::signed(CHARLIE),
+            DOT,
+            HDX,
+            5 * ONE,
+            None
+        ));
+
+        // Fee of 0.3% is paid from 5.3 HDX repaid.
+        assert_eq!(
+            recorded_trades(),
+            vec![(
+                CHARLIE,
+                HDX,
+                DOT,
+                5_300_000_000_000,
+                5 * ONE,
+                15_900_000_000,
+                (105_300_000_000_000, 95 * ONE)
+            )]
+        );
+    });
+}
+
+#[test]
+fn flash_swap_weight_should_include_trade_handler_weight() {
+    let call = crate::Call::<Test>::flash_swap {
+        asset_out: DOT,
+        asset_in: HDX,
+        amount_out: 5 * ONE,
+        deadline: None,
+    };
+
+    assert_eq!(
+        call.get_dispatch_info().weight,
+        <() as WeightInfo>::flash_swap()
+            .saturating_add(FlashSwapRepayer::on_flash_swap_weight())
+            .saturating_add(TradeHandler::on_trade_weight())
+    );
+}
+
+#[test]
+fn flash_swap_should_fail_when_fee_is_not_repaid() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        let repayments = vec![vec![], vec![(DOT, 5 * ONE)], vec![(HDX, 5_270_000_000_000)]];
+
+        for repayment in repayments {
+            set_flash_swap_repayment(repayment);
+
+            assert_noop!(
+                XYK::flash_swap(Origin::signed(CHARLIE), DOT, HDX, 5 * ONE, None),
+                Error::<Test>::FlashSwapNotRepaid
+            );
+        }
+    });
+}
+
+#[test]
+fn flash_swap_should_fail_when_whole_reserve_is_borrowed() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+        set_flash_swap_repayment(vec![(DOT, 10 * ONE)]);
+
+        assert_noop!(
+            XYK::flash_swap(Origin::signed(CHARLIE), DOT, HDX, 100 * ONE, None),
+            Error::<Test>::InsufficientLiquidity
+        );
+    });
+}
+
+#[test]
+fn flash_swap_should_fail_when_pool_is_not_constant_product() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            XYK::flash_swap(Origin::signed(CHARLIE), DOT, HDX, ONE, None),
+            Error::<Test>::PoolNotFound
+        );
+
+        assert_ok!(XYK::create_stable_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            100,
+        ));
+
+        assert_noop!(
+            XYK::flash_swap(Origin::signed(CHARLIE), DOT, HDX, ONE, None),
+            Error::<Test>::NotSupportedByPoolType
+        );
+    });
+}
@@ -0,0 +1,263 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::Inspect;
+
+use crate::traits::OnTrade;
+use crate::weights::WeightInfo;
+use crate::Balance;
+use frame_support::assert_ok;
+use frame_support::sp_runtime::Permill;
+use frame_support::weights::GetDispatchInfo;
+
+const ONE: Balance = 1_000_000_000_000;
+
+#[test]
+fn trade_handler_should_be_called_with_reserves_after_sell() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .build()
+        .execute_with(|| {
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                100 * ONE,
+                DOT,
+                100 * ONE,
+                TradeFee::get(),
+            ));
+
+            assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+            assert_eq!(
+                recorded_trades(),
+                vec![(
+                    BOB,
+                    HDX,
+                    DOT,
+                    5 * ONE,
+                    4_747_619_047_618,
+                    14_285_714_286,
+                    (105 * ONE, 95_252_380_952_382)
+                )]
+            );
+        });
+}
+
+#[test]
+fn trade_handler_should_be_called_with_reserves_after_buy() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .build()
+        .execute_with(|| {
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                100 * ONE,
+                DOT,
+                100 * ONE,
+                TradeFee::get(),
+            ));
+
+            assert_ok!(XYK::buy(
+                Origin::signed(BOB),
+                DOT,
+                HDX,
+                5 * ONE,
+                u128::MAX,
+                None
+            ));
+
+            assert_eq!(
+                recorded_trades(),
+                vec![(
+                    BOB,
+                    HDX,
+                    DOT,
+                    5_278_947_368_422,
+                    5 * ONE,
+                    15_789_473_685,
+                    (105_278_947_368_422, 95 * ONE)
+                )]
+            );
+        });
+}
+
+#[test]
+fn trade_handler_should_be_called_for_every_trade_of_route() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            DOT,
+            100 * ONE,
+            USDT,
+            200 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_ok!(XYK::router_sell(
+            Origin::signed(BOB),
+            vec![HDX, DOT, USDT].try_into().unwrap(),
+            5 * ONE,
+            0,
+            None,
+        ));
+
+        assert_eq!(
+            recorded_trades(),
+            vec![
+                (
+                    BOB,
+                    HDX,
+                    DOT,
+                    5 * ONE,
+                    4_761_904_761_904,
+                    0,
+                    (105 * ONE, 95_238_095_238_096)
+                ),
+                (
+                    BOB,
+                    DOT,
+                    USDT,
+                    4_761_904_761_904,
+                    9_090_909_090_907,
+                    0,
+                    (104_761_904_761_904, 190_909_090_909_093)
+                ),
+            ]
+        );
+    });
+}
+
+#[test]
+fn trade_handler_weight_should_be_charged_for_every_trade_of_route() {
+    let call = crate::Call::<Test>::router_sell {
+        route: vec![HDX, DOT, USDT].try_into().unwrap(),
+        amount_in: 5 * ONE,
+        min_limit: 0,
+        deadline: None,
+    };
+
+    assert_eq!(
+        call.get_dispatch_info().weight,
+        <() as WeightInfo>::router_sell(3).saturating_add(
+            <() as WeightInfo>::swap_ticks(MaxTicksPerPool::get())
+                .saturating_add(TradeHandler::on_trade_weight())
+                .saturating_mul(2)
+        )
+    );
+}
+
+#[test]
+fn liquidity_handler_should_be_called_when_liquidity_changes() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_ok!(XYK::add_liquidity(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            5 * ONE,
+            u128::MAX,
+            0,
+            None,
+        ));
+
+        assert_ok!(XYK::remove_liquidity(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            3_535_533_905_932,
+            0,
+            0,
+            None,
+        ));
+
+        assert_eq!(
+            recorded_liquidity_changes(),
+            vec![
+                (
+                    true,
+                    ALICE,
+                    vec![HDX, DOT],
+                    vec![20 * ONE, 10 * ONE],
+                    14_142_135_622_730,
+                    vec![20 * ONE, 10 * ONE]
+                ),
+                (
+                    true,
+                    CHARLIE,
+                    vec![HDX, DOT],
+                    vec![5 * ONE, 2_500_000_000_001],
+                    3_535_533_905_932,
+                    vec![25 * ONE, 12_500_000_000_001]
+                ),
+                (
+                    false,
+                    CHARLIE,
+                    vec![HDX, DOT],
+                    vec![4_999_999_999_999, 2_499_999_999_999],
+                    3_535_533_905_932,
+                    vec![20_000_000_000_001, 10_000_000_000_002]
+                ),
+            ]
+        );
+    });
+}
+
+#[test]
+fn liquidity_handler_should_be_called_when_liquidity_of_multi_asset_pool_changes() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_multi_asset_pool(
+            Origin::signed(ALICE),
+            vec![(USDT, 100 * ONE), (HDX, 100 * ONE), (DOT, 100 * ONE)]
+                .try_into()
+                .unwrap(),
+            100,
+        ));
+        let shares = Tokens::balance(POOL_SHARE_ASSET, &ALICE);
+
+        assert_ok!(XYK::remove_liquidity_multi_asset(
+            Origin::signed(ALICE),
+            0,
+            30 * ONE,
+            vec![].try_into().unwrap(),
+            None,
+        ));
+
+        assert_eq!(
+            recorded_liquidity_changes(),
+            vec![
+                (
+                    true,
+                    ALICE,
+                    vec![HDX, DOT, USDT],
+                    vec![100 * ONE, 100 * ONE, 100 * ONE],
+                    shares,
+                    vec![100 * ONE, 100 * ONE, 100 * ONE]
+                ),
+                (
+                    false,
+                    ALICE,
+                    vec![HDX, DOT, USDT],
+                    vec![10 * ONE, 10 * ONE, 10 * ONE],
+                    30 * ONE,
+                    vec![90 * ONE, 90 * ONE, 90 * ONE]
+                ),
+            ]
+        );
+    });
+}
@@ -0,0 +1,343 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
+
+use crate::types::{LBPParams, PoolInfo, PoolType, MAX_WEIGHT};
+use crate::{Balance, Error, Pools};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::traits::BadOrigin;
+
+const ONE: Balance = 1_000_000_000_000;
+
+const SALE_START: u64 = 10;
+const SALE_END: u64 = 20;
+
+/// Creates HDX/DOT liquidity bootstrapping pool in which weight of HDX decreases from 80% to 20%.
+fn create_lbp_pool() {
+    assert_ok!(XYK::create_lbp_pool(
+        Origin::signed(ALICE),
+        HDX,
+        100 * ONE,
+        DOT,
+        100 * ONE,
+        SALE_START,
+        SALE_END,
+        80_000_000,
+        20_000_000,
+    ));
+}
+
+#[test]
+fn create_lbp_pool_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        assert_eq!(
+            Pools::<Test>::get(0),
+            Some(PoolInfo {
+                assets: vec![HDX, DOT].try_into().unwrap(),
+                share_asset: Some(POOL_SHARE_ASSET),
+                pool_type: PoolType::LBP(LBPParams {
+                    owner: ALICE,
+                    start: SALE_START,
+                    end: SALE_END,
+                    initial_weight: 80_000_000,
+                    final_weight: 20_000_000,
+                }),
+                fee: TradeFee::get(),
+            })
+        );
+    });
+}
+
+#[test]
+fn create_lbp_pool_should_not_lock_minimum_liquidity() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        assert_eq!(Tokens::total_issuance(POOL_SHARE_ASSET), 100 * ONE);
+        assert_eq!(Tokens::balance(POOL_SHARE_ASSET, &ALICE), 100 * ONE);
+    });
+}
+
+#[test]
+fn create_lbp_pool_should_store_weights_of_first_asset_when_assets_are_inverted() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_lbp_pool(
+            Origin::signed(ALICE),
+            DOT,
+            100 * ONE,
+            HDX,
+            100 * ONE,
+            SALE_START,
+            SALE_END,
+            80_000_000,
+            20_000_000,
+        ));
+
+        assert_eq!(
+            Pools::<Test>::get(0).map(|pool| pool.pool_type),
+            Some(PoolType::LBP(LBPParams {
+                owner: ALICE,
+                start: SALE_START,
+                end: SALE_END,
+                initial_weight: 20_000_000,
+                final_weight: 80_000_000,
+            }))
+        );
+    });
+}
+
+#[test]
+fn create_lbp_pool_should_fail_when_origin_is_not_authority() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            XYK::create_lbp_pool(
+                Origin::signed(CHARLIE),
+                HDX,
+                ONE,
+                DOT,
+                ONE,
+                SALE_START,
+                SALE_END,
+                80_000_000,
+                20_000_000,
+            ),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn create_lbp_pool_should_fail_when_params_are_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(SALE_START + 1);
+
+        let cases = vec![
+            (SALE_START, SALE_END, 80_000_000, 20_000_000),
+            (SALE_END, SALE_END, 80_000_000, 20_000_000),
+            (SALE_END, SALE_START + 1, 80_000_000, 20_000_000),
+            (SALE_START + 1, SALE_END, 0, 20_000_000),
+            (SALE_START + 1, SALE_END, 80_000_000, MAX_WEIGHT),
+        ];
+
+        for (start, end, initial_weight, final_weight) in cases {
+            assert_noop!(
+                XYK::create_lbp_pool(
+                    Origin::signed(ALICE),
+                    HDX,
+                    100 * ONE,
+                    DOT,
+                    100 * ONE,
+                    start,
+                    end,
+                    initial_weight,
+                    final_weight,
+                ),
+                Error::<Test>::InvalidLBPParams
+            );
+        }
+    });
+}
+
+#[test]
+fn sell_should_fail_when_sale_is_not_running() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        for block in [SALE_START - 1, SALE_END + 1] {
+            System::set_block_number(block);
+
+            assert_noop!(
+                XYK::sell(Origin::signed(BOB), HDX, DOT, ONE, 0, None),
+                Error::<Test>::SaleNotRunning
+            );
+            assert_noop!(
+                XYK::buy(Origin::signed(BOB), DOT, HDX, ONE, u128::MAX, None),
+                Error::<Test>::SaleNotRunning
+            );
+        }
+    });
+}
+
+#[test]
+fn sell_should_use_current_weights() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        System::set_block_number(15);
+
+        assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+        let expected = math::lbp::calculate_out_given_in(
+            100 * ONE,
+            100 * ONE,
+            50_000_000,
+            50_000_000,
+            5 * ONE,
+        )
+        .unwrap();
+
+        assert_eq!(Tokens::balance(HDX, &BOB), 5 * ONE);
+        assert_eq!(Tokens::balance(DOT, &BOB), expected);
+    });
+}
+
+#[test]
+fn buy_should_use_current_weights() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        System::set_block_number(SALE_END);
+
+        assert_ok!(XYK::buy(
+            Origin::signed(BOB),
+            DOT,
+            HDX,
+            ONE,
+            u128::MAX,
+            None
+        ));
+
+        let expected =
+            math::lbp::calculate_in_given_out(100 * ONE, 100 * ONE, 80_000_000, 20_000_000, ONE)
+                .unwrap();
+
+        assert_eq!(Tokens::balance(HDX, &BOB), 10 * ONE - expected);
+        assert_eq!(Tokens::balance(DOT, &BOB), ONE);
+    });
+}
+
+#[test]
+fn spot_price_should_change_linearly_during_sale() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        assert_eq!(XYK::spot_price(HDX, DOT, ONE), None);
+
+        System::set_block_number(SALE_START);
+        assert_eq!(XYK::spot_price(HDX, DOT, ONE), Some(4 * ONE));
+
+        System::set_block_number(15);
+        assert_eq!(XYK::spot_price(HDX, DOT, ONE), Some(ONE));
+
+        System::set_block_number(SALE_END);
+        assert_eq!(XYK::spot_price(HDX, DOT, ONE), Some(ONE / 4));
+        assert_eq!(XYK::spot_price(DOT, HDX, ONE), Some(4 * ONE));
+    });
+}
+
+#[test]
+fn add_liquidity_should_work_when_owner_adds_before_sale() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        assert_ok!(XYK::add_liquidity(
+            Origin::signed(ALICE),
+            HDX,
+            DOT,
+            10 * ONE,
+            u128::MAX,
+            0,
+            None,
+        ));
+
+        assert_eq!(Tokens::balance(POOL_SHARE_ASSET, &ALICE), 110 * ONE);
+    });
+}
+
+#[test]
+fn add_liquidity_should_fail_when_not_owner() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        assert_noop!(
+            XYK::add_liquidity(Origin::signed(CHARLIE), HDX, DOT, ONE, u128::MAX, 0, None),
+            Error::<Test>::NotPoolOwner
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_should_fail_when_sale_started() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        System::set_block_number(SALE_START);
+
+        assert_noop!(
+            XYK::add_liquidity(Origin::signed(ALICE), HDX, DOT, ONE, u128::MAX, 0, None),
+            Error::<Test>::SaleAlreadyStarted
+        );
+    });
+}
+
+#[test]
+fn remove_liquidity_should_fail_when_sale_not_ended() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        System::set_block_number(SALE_END);
+
+        assert_noop!(
+            XYK::remove_liquidity(Origin::signed(ALICE), HDX, DOT, 100 * ONE, 0, 0, None),
+            Error::<Test>::SaleNotEnded
+        );
+    });
+}
+
+#[test]
+fn remove_liquidity_should_fail_when_not_owner() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        System::set_block_number(SALE_END + 1);
+
+        assert_noop!(
+            XYK::remove_liquidity(Origin::signed(CHARLIE), HDX, DOT, ONE, 0, 0, None),
+            Error::<Test>::NotPoolOwner
+        );
+    });
+}
+
+#[test]
+fn remove_liquidity_should_withdraw_whole_pool_when_sale_ended() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        System::set_block_number(15);
+        assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+        let sold = Tokens::balance(DOT, &BOB);
+
+        System::set_block_number(SALE_END + 1);
+        assert_ok!(XYK::remove_liquidity(
+            Origin::signed(ALICE),
+            HDX,
+            DOT,
+            100 * ONE,
+            0,
+            0,
+            None
+        ));
+
+        assert_eq!(Tokens::balance(HDX, &ALICE), 1000 * ONE + 5 * ONE);
+        assert_eq!(Tokens::balance(DOT, &ALICE), 1000 * ONE - sold);
+        assert_eq!(Tokens::total_issuance(POOL_SHARE_ASSET), 0);
+        assert_eq!(XYK::share_asset(HDX, DOT), None);
+    });
+}
+
+#[test]
+fn single_asset_liquidity_should_not_be_supported() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        assert_noop!(
+            XYK::add_liquidity_single_asset(Origin::signed(ALICE), HDX, DOT, ONE, 0, None),
+            Error::<Test>::NotSupportedByPoolType
+        );
+        assert_noop!(
+            XYK::remove_liquidity_single_asset(Origin::signed(ALICE), HDX, DOT, ONE, 0, None),
+            Error::<Test>::NotSupportedByPoolType
+        );
+    });
+}
@@ -0,0 +1,371 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
+
+use crate::limit_order::FILL_ATTEMPTS;
+use crate::traits::OnTrade;
+use crate::types::LimitOrder;
+use crate::weights::WeightInfo;
+use crate::{Balance, Error, Event, LimitOrders};
+use frame_support::traits::OnIdle;
+use frame_support::weights::Weight;
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::traits::{One, Zero};
+use sp_runtime::{FixedPointNumber, FixedU128};
+
+const ONE: Balance = 1_000_000_000_000;
+
+fn create_pool() {
+    assert_ok!(XYK::create_pool(
+        Origin::signed(ALICE),
+        HDX,
+        100 * ONE,
+        DOT,
+        100 * ONE,
+        TradeFee::get(),
+    ));
+}
+
+fn execute_limit_orders() {
+    XYK::on_idle(1, Weight::from_ref_time(u64::MAX));
+}
+
+/// Raises price of HDX in DOT to 1.44.
+fn raise_price() {
+    assert_ok!(XYK::sell(
+        Origin::signed(ALICE),
+        DOT,
+        HDX,
+        20 * ONE,
+        0,
+        None
+    ));
+}
+
+#[test]
+fn place_limit_order_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        assert_ok!(XYK::place_limit_order(
+            Origin::signed(BOB),
+            HDX,
+            DOT,
+            5 * ONE,
+            FixedU128::from_rational(6, 5),
+            None,
+        ));
+
+        assert_eq!(
+            XYK::limit_order(0, 0),
+            Some(LimitOrder {
+                owner: BOB,
+                asset_in: HDX,
+                asset_out: DOT,
+                amount: 5 * ONE,
+                price: FixedU128::from_rational(6, 5),
+            })
+        );
+        assert_eq!(Tokens::accounts(BOB, HDX).free, 5 * ONE);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 5 * ONE);
+
+        expect_events(vec![Event::<Test>::LimitOrderPlaced {
+            who: BOB,
+            pool_id: 0,
+            order_id: 0,
+            asset_in: HDX,
+            asset_out: DOT,
+            amount: 5 * ONE,
+            price: FixedU128::from_rational(6, 5),
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn place_limit_order_should_fail_when_params_are_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        assert_noop!(
+            XYK::place_limit_order(Origin::signed(BOB), HDX, DOT, 0, FixedU128::one(), None),
+            Error::<Test>::InvalidLimitOrder
+        );
+        assert_noop!(
+            XYK::place_limit_order(Origin::signed(BOB), HDX, DOT, ONE, FixedU128::zero(), None),
+            Error::<Test>::InvalidLimitOrder
+        );
+        assert_noop!(
+            XYK::place_limit_order(Origin::signed(BOB), HDX, USDT, ONE, FixedU128::one(), None),
+            Error::<Test>::PoolNotFound
+        );
+        assert_noop!(
+            XYK::place_limit_order(
+                Origin::signed(BOB),
+                HDX,
+                DOT,
+                20 * ONE,
+                FixedU128::one(),
+                None
+            ),
+            orml_tokens::Error::<Test>::BalanceTooLow
+        );
+    });
+}
+
+#[test]
+fn limit_order_should_be_filled_when_price_is_reached() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        assert_ok!(XYK::place_limit_order(
+            Origin::signed(BOB),
+            HDX,
+            DOT,
+            ONE,
+            FixedU128::from_rational(6, 5),
+            None,
+        ));
+
+        execute_limit_orders();
+
+        assert!(XYK::limit_order(0, 0).is_some());
+        assert_eq!(Tokens::balance(DOT, &BOB), 0);
+
+        raise_price();
+
+        let (expected_out, _) = XYK::quote_sell(HDX, DOT, ONE).unwrap();
+
+        execute_limit_orders();
+
+        assert_eq!(XYK::limit_order(0, 0), None);
+        assert_eq!(Tokens::balance(HDX, &BOB), 9 * ONE);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 0);
+        assert_eq!(Tokens::balance(DOT, &BOB), expected_out);
+
+        expect_events(vec![Event::<Test>::LimitOrderFilled {
+            who: BOB,
+            pool_id: 0,
+            order_id: 0,
+            amount_in: ONE,
+            amount_out: expected_out,
+            remaining: 0,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn limit_order_should_be_filled_partially_when_price_impact_is_too_high() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+        raise_price();
+
+        assert_ok!(XYK::place_limit_order(
+            Origin::signed(BOB),
+            HDX,
+            DOT,
+            10 * ONE,
+            FixedU128::from_rational(7, 5),
+            None,
+        ));
+
+        execute_limit_orders();
+
+        // Largest halved amount which keeps the price of the fill at least 1.4.
+        let filled = 10 * ONE / 8;
+
+        assert_eq!(
+            XYK::limit_order(0, 0).map(|order| order.amount),
+            Some(10 * ONE - filled)
+        );
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 10 * ONE - filled);
+        assert_eq!(Tokens::balance(DOT, &BOB), 1_773_399_014_778);
+
+        expect_events(vec![Event::<Test>::LimitOrderFilled {
+            who: BOB,
+            pool_id: 0,
+            order_id: 0,
+            amount_in: filled,
+            amount_out: 1_773_399_014_778,
+            remaining: 10 * ONE - filled,
+        }
+        .into()]);
+
+        // The fill moved the price below the price of the order.
+        execute_limit_orders();
+
+        assert_eq!(
+            XYK::limit_order(0, 0).map(|order| order.amount),
+            Some(10 * ONE - filled)
+        );
+    });
+}
+
+#[test]
+fn limit_orders_checked_in_block_should_be_bounded() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        for _ in 0..3 {
+            assert_ok!(XYK::place_limit_order(
+                Origin::signed(BOB),
+                HDX,
+                DOT,
+                ONE,
+                FixedU128::from_rational(1, 2),
+                None,
+            ));
+        }
+
+        XYK::on_idle(1, Weight::zero());
+
+        assert_eq!(LimitOrders::<Test>::iter().count(), 3);
+
+        execute_limit_orders();
+
+        assert_eq!(LimitOrders::<Test>::iter().count(), 1);
+
+        execute_limit_orders();
+
+        assert_eq!(LimitOrders::<Test>::iter().count(), 0);
+        assert_eq!(Tokens::balance(HDX, &BOB), 7 * ONE);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 0);
+    });
+}
+
+#[test]
+fn limit_orders_should_be_charged_for_every_fill_attempt() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        for _ in 0..2 {
+            assert_ok!(XYK::place_limit_order(
+                Origin::signed(BOB),
+                HDX,
+                DOT,
+                ONE,
+                FixedU128::from_rational(1, 2),
+                None,
+            ));
+        }
+
+        let weight = XYK::on_idle(1, Weight::from_ref_time(u64::MAX));
+
+        assert_eq!(
+            weight,
+            <() as WeightInfo>::execute_limit_orders(2).saturating_add(
+                <() as WeightInfo>::swap_ticks(MaxTicksPerPool::get())
+                    .saturating_mul(FILL_ATTEMPTS as u64)
+                    .saturating_add(TradeHandler::on_trade_weight())
+                    .saturating_mul(2)
+            )
+        );
+    });
+}
+
+#[test]
+fn limit_order_should_be_cancelled_when_pool_is_destroyed() {
+    ExtBuilder::default().build().execute_with(|| {
+        // Liquidity bootstrapping pool locks no liquidity, so that its owner can destroy it.
+        assert_ok!(XYK::create_lbp_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            10,
+            20,
+            80_000_000,
+            20_000_000,
+        ));
+
+        assert_ok!(XYK::place_limit_order(
+            Origin::signed(BOB),
+            HDX,
+            DOT,
+            5 * ONE,
+            FixedU128::from(2),
+            None,
+        ));
+
+        System::set_block_number(21);
+        assert_ok!(XYK::remove_liquidity(
+            Origin::signed(ALICE),
+            HDX,
+            DOT,
+            100 * ONE,
+            0,
+            0,
+            None,
+        ));
+
+        execute_limit_orders();
+
+        assert_eq!(XYK::limit_order(0, 0), None);
+        assert_eq!(Tokens::balance(HDX, &BOB), 10 * ONE);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 0);
+
+        expect_events(vec![Event::<Test>::LimitOrderCancelled {
+            who: BOB,
+            pool_id: 0,
+            order_id: 0,
+            amount: 5 * ONE,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn cancel_limit_order_should_release_remaining_amount() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        assert_ok!(XYK::place_limit_order(
+            Origin::signed(BOB),
+            HDX,
+            DOT,
+            5 * ONE,
+            FixedU128::from(2),
+            None,
+        ));
+
+        assert_ok!(XYK::cancel_limit_order(Origin::signed(BOB), 0, 0));
+
+        assert_eq!(XYK::limit_order(0, 0), None);
+        assert_eq!(Tokens::balance(HDX, &BOB), 10 * ONE);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 0);
+
+        expect_events(vec![Event::<Test>::LimitOrderCancelled {
+            who: BOB,
+            pool_id: 0,
+            order_id: 0,
+            amount: 5 * ONE,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn cancel_limit_order_should_fail_when_caller_is_not_owner() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        assert_ok!(XYK::place_limit_order(
+            Origin::signed(BOB),
+            HDX,
+            DOT,
+            5 * ONE,
+            FixedU128::from(2),
+            None,
+        ));
+
+        assert_noop!(
+            XYK::cancel_limit_order(Origin::signed(CHARLIE), 0, 0),
+            Error::<Test>::NotLimitOrderOwner
+        );
+        assert_noop!(
+            XYK::cancel_limit_order(Origin::signed(BOB), 0, 1),
+            Error::<Test>::LimitOrderNotFound
+        );
+    });
+}
@@ -1,8 +1,10 @@
 use crate::mock::*;
 use frame_support::traits::tokens::fungibles::*;
 
-use crate::Balance;
-use frame_support::assert_ok;
+use crate::traits::AccountIdFor;
+use crate::{Balance, Error, Event};
+use frame_support::sp_runtime::Permill;
+use frame_support::{assert_noop, assert_ok};
 
 const ONE: Balance = 1_000_000_000_000;
 
@@ -17,6 +19,7 @@ fn add_liquidity_should_work() {
             20_000_000_000_000,
             asset_b,
             10_000_000_000_000,
+            TradeFee::get(),
         ));
 
         assert_eq!(Tokens::balance(asset_a, &CHARLIE), 10 * ONE);
@@ -27,13 +30,15 @@ fn add_liquidity_should_work() {
             asset_b,
             5 * ONE,
             u128::MAX,
+            0,
+            None,
         ));
 
         assert_eq!(Tokens::balance(asset_a, &CHARLIE), 5 * ONE);
         assert_eq!(Tokens::balance(asset_b, &CHARLIE), 7499_999_999_999);
         assert_eq!(
             Tokens::balance(POOL_SHARE_ASSET, &CHARLIE),
-            5000_000_000_000
+            3_535_533_905_932
         );
     });
 }
@@ -49,6 +54,7 @@ fn add_liquidity_should_work_when_assets_are_inverted() {
             20_000_000_000_000,
             asset_b,
             10_000_000_000_000,
+            TradeFee::get(),
         ));
 
         assert_eq!(Tokens::balance(asset_a, &CHARLIE), 10 * ONE);
@@ -59,13 +65,674 @@ fn add_liquidity_should_work_when_assets_are_inverted() {
             asset_a,
             2500_000_000_001,
             u128::MAX,
+            0,
+            None,
         ));
 
         assert_eq!(Tokens::balance(asset_a, &CHARLIE), 4999_999_999_997);
         assert_eq!(Tokens::balance(asset_b, &CHARLIE), 7499_999_999_999);
         assert_eq!(
             Tokens::balance(POOL_SHARE_ASSET, &CHARLIE),
-            5000_000_000_002
+            3_535_533_905_933
+        );
+    });
+}
+
+#[test]
+fn remove_liquidity_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+        assert_ok!(XYK::add_liquidity(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            5 * ONE,
+            u128::MAX,
+            0,
+            None,
+        ));
+
+        assert_ok!(XYK::remove_liquidity(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            3_535_533_905_932,
+            0,
+            0,
+            None,
+        ));
+
+        assert_eq!(Tokens::balance(HDX, &CHARLIE), 9_999_999_999_999);
+        assert_eq!(Tokens::balance(DOT, &CHARLIE), 9_999_999_999_998);
+        assert_eq!(Tokens::balance(POOL_SHARE_ASSET, &CHARLIE), 0);
+
+        expect_events(vec![Event::<Test>::LiquidityRemoved {
+            who: CHARLIE,
+            asset_a: HDX,
+            asset_b: DOT,
+            shares: 3_535_533_905_932,
+            amount_a: 4_999_999_999_999,
+            amount_b: 2_499_999_999_999,
+            fee_a: 0,
+            fee_b: 0,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn remove_liquidity_should_leave_withdraw_fee_in_pool() {
+    ExtBuilder::default()
+        .with_withdraw_fee(Permill::from_percent(1))
+        .build()
+        .execute_with(|| {
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                20 * ONE,
+                DOT,
+                10 * ONE,
+                TradeFee::get(),
+            ));
+            assert_ok!(XYK::add_liquidity(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                5 * ONE,
+                u128::MAX,
+                0,
+                None,
+            ));
+
+            assert_ok!(XYK::remove_liquidity(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                3_535_533_905_932,
+                0,
+                0,
+                None,
+            ));
+
+            assert_eq!(Tokens::balance(HDX, &CHARLIE), 9_949_999_999_999);
+            assert_eq!(Tokens::balance(DOT, &CHARLIE), 9_974_999_999_998);
+            assert_eq!(
+                XYK::pool_reserves(HDX, DOT),
+                Some((20_050_000_000_001, 10_025_000_000_002))
+            );
+
+            expect_events(vec![Event::<Test>::LiquidityRemoved {
+                who: CHARLIE,
+                asset_a: HDX,
+                asset_b: DOT,
+                shares: 3_535_533_905_932,
+                amount_a: 4_949_999_999_999,
+                amount_b: 2_474_999_999_999,
+                fee_a: 50_000_000_000,
+                fee_b: 25_000_000_000,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn remove_liquidity_should_not_destroy_pool_while_other_providers_remain() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+        assert_ok!(XYK::add_liquidity(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            5 * ONE,
+            u128::MAX,
+            0,
+            None,
+        ));
+
+        let shares = Tokens::balance(POOL_SHARE_ASSET, &ALICE);
+
+        assert_ok!(XYK::remove_liquidity(
+            Origin::signed(ALICE),
+            HDX,
+            DOT,
+            shares,
+            0,
+            0,
+            None,
+        ));
+
+        assert_eq!(XYK::pair_pool_id((HDX, DOT)), Some(0));
+        assert_eq!(
+            Tokens::total_issuance(POOL_SHARE_ASSET),
+            3_535_533_905_932 + MinimumLiquidity::get()
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_single_asset_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_ok!(XYK::add_liquidity_single_asset(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            5 * ONE,
+            0,
+            None,
+        ));
+
+        assert_eq!(Tokens::balance(HDX, &CHARLIE), 5 * ONE);
+        assert_eq!(Tokens::balance(DOT, &CHARLIE), 10 * ONE);
+        assert_eq!(
+            Tokens::balance(POOL_SHARE_ASSET, &CHARLIE),
+            1_669_252_677_109
+        );
+        assert_eq!(XYK::pool_reserves(HDX, DOT), Some((25 * ONE, 10 * ONE)));
+
+        expect_events(vec![Event::<Test>::LiquidityAddedSingleAsset {
+            who: CHARLIE,
+            asset_a: HDX,
+            asset_b: DOT,
+            amount_a: 5 * ONE,
+            shares: 1_669_252_677_109,
+            protocol_fee: 0,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn add_liquidity_single_asset_should_pay_trade_fee_on_swapped_part() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .build()
+        .execute_with(|| {
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                20 * ONE,
+                DOT,
+                10 * ONE,
+                TradeFee::get(),
+            ));
+
+            assert_ok!(XYK::add_liquidity_single_asset(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                5 * ONE,
+                0,
+                None,
+            ));
+
+            assert_eq!(
+                Tokens::balance(POOL_SHARE_ASSET, &CHARLIE),
+                1_666_449_298_764
+            );
+        });
+}
+
+#[test]
+fn add_liquidity_single_asset_should_fail_when_shares_are_below_limit() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_noop!(
+            XYK::add_liquidity_single_asset(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                5 * ONE,
+                1_669_252_677_110,
+                None,
+            ),
+            Error::<Test>::InsufficientSharesOut
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_single_asset_should_transfer_protocol_fee_to_receiver() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .with_protocol_fee(Permill::from_percent(20))
+        .build()
+        .execute_with(|| {
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                20 * ONE,
+                DOT,
+                10 * ONE,
+                TradeFee::get(),
+            ));
+
+            let (shares, _, swap_fee) = math::xyk::calculate_shares_for_single_asset(
+                20 * ONE,
+                10 * ONE,
+                5 * ONE,
+                Tokens::total_issuance(POOL_SHARE_ASSET),
+                TradeFee::get(),
+            )
+            .unwrap();
+            let protocol_fee = Permill::from_percent(20).mul_floor(swap_fee);
+            let receiver_balance = Tokens::balance(DOT, &ProtocolFeeReceiver::get());
+
+            assert_ok!(XYK::add_liquidity_single_asset(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                5 * ONE,
+                0,
+                None,
+            ));
+
+            assert!(protocol_fee > 0);
+            assert_eq!(
+                Tokens::balance(DOT, &ProtocolFeeReceiver::get()),
+                receiver_balance + protocol_fee
+            );
+            assert_eq!(
+                XYK::pool_reserves(HDX, DOT),
+                Some((25 * ONE, 10 * ONE - protocol_fee))
+            );
+
+            expect_events(vec![Event::<Test>::LiquidityAddedSingleAsset {
+                who: CHARLIE,
+                asset_a: HDX,
+                asset_b: DOT,
+                amount_a: 5 * ONE,
+                shares,
+                protocol_fee,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn remove_liquidity_single_asset_should_transfer_protocol_fee_to_receiver() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .with_protocol_fee(Permill::from_percent(20))
+        .build()
+        .execute_with(|| {
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                20 * ONE,
+                DOT,
+                10 * ONE,
+                TradeFee::get(),
+            ));
+            assert_ok!(XYK::add_liquidity(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                5 * ONE,
+                u128::MAX,
+                0,
+                None,
+            ));
+
+            let shares = Tokens::balance(POOL_SHARE_ASSET, &CHARLIE);
+            let (hdx_reserve, dot_reserve) = XYK::pool_reserves(HDX, DOT).unwrap();
+            let (amount_a, _, swap_fee) = math::xyk::calculate_single_asset_liquidity_out(
+                hdx_reserve,
+                dot_reserve,
+                shares,
+                Tokens::total_issuance(POOL_SHARE_ASSET),
+                WithdrawFee::get(),
+                TradeFee::get(),
+            )
+            .unwrap();
+            let protocol_fee = Permill::from_percent(20).mul_floor(swap_fee);
+            let receiver_balance = Tokens::balance(HDX, &ProtocolFeeReceiver::get());
+
+            assert_ok!(XYK::remove_liquidity_single_asset(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                shares,
+                0,
+                None,
+            ));
+
+            assert!(protocol_fee > 0);
+            assert_eq!(
+                Tokens::balance(HDX, &ProtocolFeeReceiver::get()),
+                receiver_balance + protocol_fee
+            );
+            assert_eq!(
+                XYK::pool_reserves(HDX, DOT),
+                Some((hdx_reserve - amount_a - protocol_fee, dot_reserve))
+            );
+
+            expect_events(vec![Event::<Test>::LiquidityRemovedSingleAsset {
+                who: CHARLIE,
+                asset_a: HDX,
+                asset_b: DOT,
+                shares,
+                amount_a,
+                protocol_fee,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn remove_liquidity_single_asset_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+        assert_ok!(XYK::add_liquidity(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            5 * ONE,
+            u128::MAX,
+            0,
+            None,
+        ));
+
+        assert_ok!(XYK::remove_liquidity_single_asset(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            3_535_533_905_932,
+            0,
+            None,
+        ));
+
+        assert_eq!(Tokens::balance(HDX, &CHARLIE), 13_999_999_999_997);
+        assert_eq!(Tokens::balance(DOT, &CHARLIE), 7_499_999_999_999);
+        assert_eq!(Tokens::balance(POOL_SHARE_ASSET, &CHARLIE), 0);
+        assert_eq!(
+            XYK::pool_reserves(HDX, DOT),
+            Some((16_000_000_000_003, 12_500_000_000_001))
+        );
+
+        expect_events(vec![Event::<Test>::LiquidityRemovedSingleAsset {
+            who: CHARLIE,
+            asset_a: HDX,
+            asset_b: DOT,
+            shares: 3_535_533_905_932,
+            amount_a: 8_999_999_999_997,
+            protocol_fee: 0,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn remove_liquidity_single_asset_should_fail_when_amount_is_below_limit() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+        assert_ok!(XYK::add_liquidity(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            5 * ONE,
+            u128::MAX,
+            0,
+            None,
+        ));
+
+        assert_noop!(
+            XYK::remove_liquidity_single_asset(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                3_535_533_905_932,
+                8_999_999_999_998,
+                None,
+            ),
+            Error::<Test>::InsufficientAmountOut
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_should_fail_when_shares_are_below_minimum() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_noop!(
+            XYK::add_liquidity(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                5 * ONE,
+                u128::MAX,
+                3_535_533_905_933,
+                None,
+            ),
+            Error::<Test>::InsufficientSharesOut
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_should_fail_when_no_shares_are_minted() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            2_000,
+            DOT,
+            2_000,
+            TradeFee::get(),
+        ));
+
+        // Donation inflates price of a share, so that small deposits round down to no shares.
+        let pool_account = AccountIdConstructor::create_account_id(0).unwrap();
+        for asset in [HDX, DOT] {
+            assert_ok!(<Tokens as Transfer<AccountId>>::transfer(
+                asset,
+                &ALICE,
+                &pool_account,
+                100 * ONE,
+                false
+            ));
+        }
+
+        assert_noop!(
+            XYK::add_liquidity(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                ONE / 100,
+                u128::MAX,
+                0,
+                None,
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_noop!(
+            XYK::add_liquidity_single_asset(Origin::signed(CHARLIE), HDX, DOT, ONE / 100, 0, None),
+            Error::<Test>::InsufficientLiquidity
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_should_fail_when_amount_b_is_above_limit() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_noop!(
+            XYK::add_liquidity(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                5 * ONE,
+                2_500_000_000_000,
+                0,
+                None,
+            ),
+            Error::<Test>::Limit
+        );
+    });
+}
+
+#[test]
+fn remove_liquidity_should_fail_when_amounts_are_below_minimum() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+        assert_ok!(XYK::add_liquidity(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            5 * ONE,
+            u128::MAX,
+            0,
+            None,
+        ));
+
+        assert_noop!(
+            XYK::remove_liquidity(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                3_535_533_905_932,
+                5 * ONE,
+                0,
+                None,
+            ),
+            Error::<Test>::InsufficientAmountOut
+        );
+        assert_noop!(
+            XYK::remove_liquidity(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                3_535_533_905_932,
+                0,
+                2_500_000_000_000,
+                None,
+            ),
+            Error::<Test>::InsufficientAmountOut
+        );
+
+        assert_ok!(XYK::remove_liquidity(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            3_535_533_905_932,
+            4_999_999_999_999,
+            2_499_999_999_999,
+            None,
+        ));
+    });
+}
+
+#[test]
+fn liquidity_operations_should_fail_when_deadline_expired() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            20 * ONE,
+            DOT,
+            10 * ONE,
+            TradeFee::get(),
+        ));
+
+        System::set_block_number(11);
+
+        assert_noop!(
+            XYK::add_liquidity(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                5 * ONE,
+                u128::MAX,
+                0,
+                Some(10),
+            ),
+            Error::<Test>::DeadlineExpired
+        );
+        assert_noop!(
+            XYK::remove_liquidity(Origin::signed(ALICE), HDX, DOT, ONE, 0, 0, Some(10)),
+            Error::<Test>::DeadlineExpired
+        );
+        assert_noop!(
+            XYK::add_liquidity_single_asset(
+                Origin::signed(CHARLIE),
+                HDX,
+                DOT,
+                5 * ONE,
+                0,
+                Some(10),
+            ),
+            Error::<Test>::DeadlineExpired
+        );
+        assert_noop!(
+            XYK::remove_liquidity_single_asset(Origin::signed(ALICE), HDX, DOT, ONE, 0, Some(10)),
+            Error::<Test>::DeadlineExpired
         );
     });
 }
@@ -1,4 +1,17 @@
+mod concentrated;
 mod create;
+mod dynamic_fee;
+mod flash_swap;
+mod hooks;
+mod lbp;
+mod limit_order;
 mod liquidity;
 mod math;
+mod multi_asset;
+mod oracle;
+mod quote;
+mod router;
+mod runtime_api;
+mod schedule;
+mod stableswap;
 mod swap;
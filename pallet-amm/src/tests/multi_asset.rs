@@ -0,0 +1,523 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
+
+use crate::traits::AccountIdFor;
+use crate::types::{PoolInfo, PoolType, StableParams};
+use crate::{AssetId, Balance, Error, Event, Pools};
+use frame_support::sp_runtime::Permill;
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+
+const ONE: Balance = 1_000_000_000_000;
+
+fn assets(amounts: Vec<(AssetId, Balance)>) -> BoundedVec<(AssetId, Balance), MaxAssetsInPool> {
+    amounts.try_into().unwrap()
+}
+
+fn create_multi_asset_pool() {
+    assert_ok!(XYK::create_multi_asset_pool(
+        Origin::signed(ALICE),
+        assets(vec![(USDT, 100 * ONE), (HDX, 100 * ONE), (DOT, 100 * ONE)]),
+        100,
+    ));
+}
+
+#[test]
+fn create_multi_asset_pool_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_eq!(
+            Pools::<Test>::get(0),
+            Some(PoolInfo {
+                assets: vec![HDX, DOT, USDT].try_into().unwrap(),
+                share_asset: Some(POOL_SHARE_ASSET),
+                pool_type: PoolType::Stable(StableParams {
+                    initial_amplification: 100,
+                    final_amplification: 100,
+                    start: 1,
+                    end: 1,
+                }),
+                fee: TradeFee::get(),
+            })
+        );
+
+        let pool_account = AccountIdConstructor::create_account_id(0).unwrap();
+        for asset in [HDX, DOT, USDT] {
+            assert_eq!(Tokens::balance(asset, &pool_account), 100 * ONE);
+        }
+
+        assert_eq!(Tokens::total_issuance(POOL_SHARE_ASSET), 300 * ONE);
+        assert_eq!(
+            Tokens::balance(POOL_SHARE_ASSET, &ALICE),
+            300 * ONE - MinimumLiquidity::get()
+        );
+
+        expect_events(vec![Event::<Test>::MultiAssetPoolCreated {
+            who: ALICE,
+            pool_id: 0,
+            assets: vec![HDX, DOT, USDT],
+            shares: 300 * ONE - MinimumLiquidity::get(),
+            share_asset_id: POOL_SHARE_ASSET,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn create_multi_asset_pool_should_fail_when_assets_are_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let cases = vec![
+            vec![(HDX, 100 * ONE), (DOT, 100 * ONE)],
+            vec![(HDX, 100 * ONE), (DOT, 100 * ONE), (HDX, 100 * ONE)],
+        ];
+
+        for case in cases {
+            assert_noop!(
+                XYK::create_multi_asset_pool(Origin::signed(ALICE), assets(case), 100),
+                Error::<Test>::InvalidAssets
+            );
+        }
+    });
+}
+
+#[test]
+fn multi_asset_pool_should_not_be_found_by_pair() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_eq!(XYK::share_asset(HDX, DOT), None);
+        assert_eq!(XYK::pool_reserves(HDX, DOT), None);
+
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_eq!(XYK::share_asset(HDX, DOT), Some(POOL_SHARE_ASSET + 1));
+        assert_eq!(XYK::pool_reserves(DOT, HDX), Some((100 * ONE, 100 * ONE)));
+        assert_eq!(
+            Pools::<Test>::get(1).map(|pool| pool.pool_type),
+            Some(PoolType::XYK)
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_multi_asset_should_work_with_subset_of_assets() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_ok!(XYK::add_liquidity_multi_asset(
+            Origin::signed(CHARLIE),
+            0,
+            assets(vec![(HDX, 5 * ONE)]),
+            0,
+            None,
+        ));
+
+        let shares = Tokens::balance(POOL_SHARE_ASSET, &CHARLIE);
+        // Deposit of a single asset unbalances the pool, so it is worth less than its amount.
+        assert!(shares > 4 * ONE && shares < 5 * ONE);
+
+        let pool_account = AccountIdConstructor::create_account_id(0).unwrap();
+        assert_eq!(Tokens::balance(HDX, &pool_account), 105 * ONE);
+        assert_eq!(Tokens::balance(DOT, &pool_account), 100 * ONE);
+        assert_eq!(Tokens::balance(HDX, &CHARLIE), 5 * ONE);
+
+        expect_events(vec![Event::<Test>::LiquidityAddedMultiAsset {
+            who: CHARLIE,
+            pool_id: 0,
+            amounts: vec![(HDX, 5 * ONE)],
+            shares,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn add_liquidity_multi_asset_should_charge_fee_on_imbalance() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_percent(1))
+        .build()
+        .execute_with(|| {
+            create_multi_asset_pool();
+
+            assert_ok!(XYK::add_liquidity_multi_asset(
+                Origin::signed(ALICE),
+                0,
+                assets(vec![(HDX, 10 * ONE), (DOT, 10 * ONE), (USDT, 10 * ONE)]),
+                0,
+                None,
+            ));
+            let balanced_shares =
+                Tokens::balance(POOL_SHARE_ASSET, &ALICE) - (300 * ONE - MinimumLiquidity::get());
+
+            assert_ok!(XYK::add_liquidity_multi_asset(
+                Origin::signed(CHARLIE),
+                0,
+                assets(vec![(HDX, 3 * ONE), (DOT, 3 * ONE)]),
+                0,
+                None,
+            ));
+            let imbalanced_shares = Tokens::balance(POOL_SHARE_ASSET, &CHARLIE);
+
+            // Balanced deposit pays no fee.
+            assert_eq!(balanced_shares, 30 * ONE);
+            assert!(imbalanced_shares < 6 * ONE);
+        });
+}
+
+#[test]
+fn add_liquidity_multi_asset_should_fail_when_asset_is_not_in_pool() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_noop!(
+            XYK::add_liquidity_multi_asset(
+                Origin::signed(CHARLIE),
+                0,
+                assets(vec![(HDX, ONE), (4000, ONE)]),
+                0,
+                None,
+            ),
+            Error::<Test>::AssetNotInPool
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_multi_asset_should_fail_when_shares_are_below_limit() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_noop!(
+            XYK::add_liquidity_multi_asset(
+                Origin::signed(CHARLIE),
+                0,
+                assets(vec![(HDX, 5 * ONE)]),
+                5 * ONE,
+                None,
+            ),
+            Error::<Test>::InsufficientSharesOut
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_multi_asset_should_fail_when_no_shares_are_minted() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_multi_asset_pool(
+            Origin::signed(ALICE),
+            assets(vec![(USDT, 1_000), (HDX, 1_000), (DOT, 1_000)]),
+            100,
+        ));
+
+        // Donation inflates price of a share, so that small deposits round down to no shares.
+        let pool_account = AccountIdConstructor::create_account_id(0).unwrap();
+        for asset in [HDX, DOT, USDT] {
+            assert_ok!(<Tokens as Transfer<AccountId>>::transfer(
+                asset,
+                &ALICE,
+                &pool_account,
+                100 * ONE,
+                false
+            ));
+        }
+
+        assert_noop!(
+            XYK::add_liquidity_multi_asset(
+                Origin::signed(CHARLIE),
+                0,
+                assets(vec![(HDX, ONE / 100)]),
+                0,
+                None,
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_multi_asset_should_fail_when_pool_is_not_stable() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            XYK::add_liquidity_multi_asset(
+                Origin::signed(CHARLIE),
+                0,
+                assets(vec![(HDX, ONE)]),
+                0,
+                None,
+            ),
+            Error::<Test>::PoolNotFound
+        );
+
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_noop!(
+            XYK::add_liquidity_multi_asset(
+                Origin::signed(CHARLIE),
+                0,
+                assets(vec![(HDX, ONE), (DOT, ONE)]),
+                0,
+                None,
+            ),
+            Error::<Test>::NotSupportedByPoolType
+        );
+    });
+}
+
+#[test]
+fn remove_liquidity_multi_asset_should_withdraw_all_assets_in_ratio_of_reserves() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_ok!(XYK::add_liquidity_multi_asset(
+            Origin::signed(CHARLIE),
+            0,
+            assets(vec![(HDX, 5 * ONE), (DOT, 5 * ONE)]),
+            0,
+            None,
+        ));
+
+        let shares = Tokens::balance(POOL_SHARE_ASSET, &CHARLIE);
+
+        assert_ok!(XYK::remove_liquidity_multi_asset(
+            Origin::signed(CHARLIE),
+            0,
+            shares,
+            assets(vec![]),
+            None,
+        ));
+
+        assert_eq!(Tokens::balance(POOL_SHARE_ASSET, &CHARLIE), 0);
+
+        let hdx = Tokens::balance(HDX, &CHARLIE) - 5 * ONE;
+        let dot = Tokens::balance(DOT, &CHARLIE) - 5 * ONE;
+        let usdt = Tokens::balance(USDT, &CHARLIE);
+
+        // Reserves of HDX and DOT are equal, so the same amounts are withdrawn.
+        assert_eq!(hdx, dot);
+        assert!(usdt > 0 && usdt < hdx);
+        assert!(hdx + dot + usdt <= 10 * ONE);
+
+        expect_events(vec![Event::<Test>::LiquidityRemovedMultiAsset {
+            who: CHARLIE,
+            pool_id: 0,
+            shares,
+            amounts: vec![(HDX, hdx), (DOT, dot), (USDT, usdt)],
+            fees: vec![(HDX, 0), (DOT, 0), (USDT, 0)],
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn remove_liquidity_multi_asset_should_keep_withdraw_fee_in_pool() {
+    ExtBuilder::default()
+        .with_withdraw_fee(Permill::from_percent(1))
+        .build()
+        .execute_with(|| {
+            create_multi_asset_pool();
+
+            assert_ok!(XYK::remove_liquidity_multi_asset(
+                Origin::signed(ALICE),
+                0,
+                30 * ONE,
+                assets(vec![]),
+                None,
+            ));
+
+            let pool_account = AccountIdConstructor::create_account_id(0).unwrap();
+            for asset in [HDX, DOT, USDT] {
+                assert_eq!(Tokens::balance(asset, &pool_account), 90 * ONE + ONE / 10);
+            }
+
+            expect_events(vec![Event::<Test>::LiquidityRemovedMultiAsset {
+                who: ALICE,
+                pool_id: 0,
+                shares: 30 * ONE,
+                amounts: vec![
+                    (HDX, 10 * ONE - ONE / 10),
+                    (DOT, 10 * ONE - ONE / 10),
+                    (USDT, 10 * ONE - ONE / 10),
+                ],
+                fees: vec![(HDX, ONE / 10), (DOT, ONE / 10), (USDT, ONE / 10)],
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn remove_liquidity_multi_asset_should_fail_when_amount_is_below_limit() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_noop!(
+            XYK::remove_liquidity_multi_asset(
+                Origin::signed(ALICE),
+                0,
+                30 * ONE,
+                assets(vec![(DOT, 10 * ONE), (USDT, 10 * ONE + 1)]),
+                None,
+            ),
+            Error::<Test>::InsufficientAmountOut
+        );
+    });
+}
+
+#[test]
+fn sell_multi_asset_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_ok!(XYK::sell_multi_asset(
+            Origin::signed(CHARLIE),
+            0,
+            HDX,
+            USDT,
+            5 * ONE,
+            0,
+            None,
+        ));
+
+        let (amount_out, fee) = math::stableswap::calculate_out_given_in_with_fee(
+            &[100 * ONE, 100 * ONE, 100 * ONE],
+            0,
+            2,
+            5 * ONE,
+            100,
+            TradeFee::get(),
+        )
+        .unwrap();
+
+        let pool_account = AccountIdConstructor::create_account_id(0).unwrap();
+        assert_eq!(Tokens::balance(HDX, &CHARLIE), 5 * ONE);
+        assert_eq!(Tokens::balance(USDT, &CHARLIE), amount_out);
+        assert_eq!(Tokens::balance(HDX, &pool_account), 105 * ONE);
+        assert_eq!(Tokens::balance(USDT, &pool_account), 100 * ONE - amount_out);
+        assert_eq!(Tokens::balance(DOT, &pool_account), 100 * ONE);
+
+        expect_events(vec![Event::<Test>::SwapExecuted {
+            who: CHARLIE,
+            asset_in: HDX,
+            asset_out: USDT,
+            amount_in: 5 * ONE,
+            amount_out,
+            lp_fee: fee,
+            protocol_fee: 0,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn buy_multi_asset_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_ok!(XYK::buy_multi_asset(
+            Origin::signed(CHARLIE),
+            0,
+            USDT,
+            DOT,
+            5 * ONE,
+            10 * ONE,
+            None,
+        ));
+
+        let (amount_in, _) = math::stableswap::calculate_in_given_out_with_fee(
+            &[100 * ONE, 100 * ONE, 100 * ONE],
+            1,
+            2,
+            5 * ONE,
+            100,
+            TradeFee::get(),
+        )
+        .unwrap();
+
+        assert_eq!(Tokens::balance(DOT, &CHARLIE), 10 * ONE - amount_in);
+        assert_eq!(Tokens::balance(USDT, &CHARLIE), 5 * ONE);
+    });
+}
+
+#[test]
+fn sell_multi_asset_should_fail_when_limit_is_not_met() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_noop!(
+            XYK::sell_multi_asset(
+                Origin::signed(CHARLIE),
+                0,
+                HDX,
+                USDT,
+                5 * ONE,
+                5 * ONE,
+                None
+            ),
+            Error::<Test>::Limit
+        );
+        assert_noop!(
+            XYK::buy_multi_asset(
+                Origin::signed(CHARLIE),
+                0,
+                USDT,
+                HDX,
+                5 * ONE,
+                5 * ONE,
+                None
+            ),
+            Error::<Test>::Limit
+        );
+    });
+}
+
+#[test]
+fn trade_multi_asset_should_fail_when_assets_are_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_noop!(
+            XYK::sell_multi_asset(Origin::signed(CHARLIE), 0, HDX, HDX, ONE, 0, None),
+            Error::<Test>::InvalidAssets
+        );
+        assert_noop!(
+            XYK::sell_multi_asset(Origin::signed(CHARLIE), 0, HDX, 4000, ONE, 0, None),
+            Error::<Test>::AssetNotInPool
+        );
+        assert_noop!(
+            XYK::buy_multi_asset(Origin::signed(CHARLIE), 1, HDX, DOT, ONE, u128::MAX, None),
+            Error::<Test>::PoolNotFound
+        );
+    });
+}
+
+#[test]
+fn trade_multi_asset_should_fail_when_pool_is_not_stable() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_noop!(
+            XYK::sell_multi_asset(Origin::signed(CHARLIE), 0, HDX, DOT, ONE, 0, None),
+            Error::<Test>::NotSupportedByPoolType
+        );
+    });
+}
@@ -0,0 +1,141 @@
+use crate::mock::*;
+
+use crate::traits::PriceOracle;
+use crate::Balance;
+use frame_support::assert_ok;
+use frame_support::traits::OnFinalize;
+use math::assert_eq_approx;
+use sp_runtime::FixedU128;
+
+const ONE: Balance = 1_000_000_000_000;
+
+fn finalize_block(n: u64) {
+    XYK::on_finalize(n);
+    System::set_block_number(n + 1);
+}
+
+#[test]
+fn oracle_should_track_spot_price_and_liquidity_at_end_of_block() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            200 * ONE,
+            TradeFee::get(),
+        ));
+
+        finalize_block(1);
+
+        for window in [1, 10, 14_400] {
+            assert_eq!(
+                XYK::get_price(HDX, DOT, window),
+                Some((FixedU128::from(2u128), 141_421_356_237_309))
+            );
+            assert_eq!(
+                XYK::get_price(DOT, HDX, window),
+                Some((FixedU128::from_float(0.5), 141_421_356_237_309))
+            );
+        }
+    });
+}
+
+#[test]
+fn oracle_should_average_price_over_window() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            200 * ONE,
+            TradeFee::get(),
+        ));
+
+        finalize_block(1);
+
+        assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 10 * ONE, 0, None));
+
+        finalize_block(2);
+
+        let tolerance = FixedU128::from_float(0.000_000_001);
+
+        let (last_block, _) = XYK::get_price(HDX, DOT, 1).unwrap();
+        assert_eq_approx!(
+            last_block,
+            FixedU128::from_float(1.652_892_561_983_472),
+            tolerance,
+            "Last block price"
+        );
+
+        let (ten_blocks, _) = XYK::get_price(HDX, DOT, 10).unwrap();
+        assert_eq_approx!(
+            ten_blocks,
+            FixedU128::from_float(1.936_889_556_724_267),
+            tolerance,
+            "Ten blocks price"
+        );
+
+        let (day, _) = XYK::get_price(HDX, DOT, 14_400).unwrap();
+        assert_eq_approx!(
+            day,
+            FixedU128::from_float(1.999_951_793_981_249),
+            tolerance,
+            "Day price"
+        );
+    });
+}
+
+#[test]
+fn oracle_should_average_liquidity_over_window() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            200 * ONE,
+            TradeFee::get(),
+        ));
+
+        finalize_block(1);
+
+        assert_ok!(XYK::add_liquidity(
+            Origin::signed(CHARLIE),
+            HDX,
+            DOT,
+            10 * ONE,
+            u128::MAX,
+            0,
+            None,
+        ));
+
+        finalize_block(2);
+
+        let (_, last_block) = XYK::get_price(HDX, DOT, 1).unwrap();
+        assert_eq!(last_block, 155_563_491_861_040);
+
+        let (_, ten_blocks) = XYK::get_price(HDX, DOT, 10).unwrap();
+        assert!(ten_blocks > 141_421_356_237_309 && ten_blocks < last_block);
+    });
+}
+
+#[test]
+fn oracle_should_not_provide_price_for_unsupported_window() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            200 * ONE,
+            TradeFee::get(),
+        ));
+
+        finalize_block(1);
+
+        assert_eq!(XYK::get_price(HDX, DOT, 5), None);
+        assert_eq!(XYK::get_price(HDX, USDT, 1), None);
+    });
+}
@@ -0,0 +1,105 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
+
+use crate::Balance;
+use frame_support::assert_ok;
+use frame_support::sp_runtime::Permill;
+
+const ONE: Balance = 1_000_000_000_000;
+
+#[test]
+fn quote_sell_should_match_sell() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .build()
+        .execute_with(|| {
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                100 * ONE,
+                DOT,
+                100 * ONE,
+                TradeFee::get(),
+            ));
+
+            let (amount_out, fee) = XYK::quote_sell(HDX, DOT, 5 * ONE).unwrap();
+
+            assert_eq!(amount_out, 4_747_619_047_618);
+            assert_eq!(fee, 14_285_714_286);
+
+            assert_ok!(XYK::sell(
+                Origin::signed(BOB),
+                HDX,
+                DOT,
+                5 * ONE,
+                amount_out,
+                None,
+            ));
+
+            assert_eq!(Tokens::balance(DOT, &BOB), amount_out);
+        });
+}
+
+#[test]
+fn quote_buy_should_match_buy() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .build()
+        .execute_with(|| {
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                100 * ONE,
+                DOT,
+                100 * ONE,
+                TradeFee::get(),
+            ));
+
+            let (amount_in, fee) = XYK::quote_buy(DOT, HDX, 5 * ONE).unwrap();
+
+            assert_eq!(amount_in, 5_278_947_368_422);
+            assert_eq!(fee, 15_789_473_685);
+
+            assert_ok!(XYK::buy(
+                Origin::signed(BOB),
+                DOT,
+                HDX,
+                5 * ONE,
+                amount_in,
+                None
+            ));
+
+            assert_eq!(Tokens::balance(HDX, &BOB), 10 * ONE - amount_in);
+        });
+}
+
+#[test]
+fn pool_state_should_be_queryable() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            200 * ONE,
+            DOT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_eq!(XYK::pool_reserves(HDX, DOT), Some((200 * ONE, 100 * ONE)));
+        assert_eq!(XYK::pool_reserves(DOT, HDX), Some((100 * ONE, 200 * ONE)));
+        assert_eq!(XYK::share_asset(DOT, HDX), Some(POOL_SHARE_ASSET));
+        assert_eq!(XYK::spot_price(HDX, DOT, ONE), Some(ONE / 2));
+        assert_eq!(XYK::spot_price(DOT, HDX, ONE), Some(2 * ONE));
+    });
+}
+
+#[test]
+fn queries_should_return_none_when_pool_does_not_exist() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(XYK::quote_sell(HDX, DOT, ONE), None);
+        assert_eq!(XYK::quote_buy(DOT, HDX, ONE), None);
+        assert_eq!(XYK::pool_reserves(HDX, DOT), None);
+        assert_eq!(XYK::share_asset(HDX, DOT), None);
+        assert_eq!(XYK::spot_price(HDX, DOT, ONE), None);
+    });
+}
@@ -0,0 +1,332 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
+
+use crate::{Balance, Error, Event};
+use frame_support::sp_runtime::Permill;
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+
+const ONE: Balance = 1_000_000_000_000;
+
+fn route(assets: Vec<crate::AssetId>) -> BoundedVec<crate::AssetId, MaxRouteLength> {
+    assets.try_into().unwrap()
+}
+
+fn create_pools() {
+    assert_ok!(XYK::create_pool(
+        Origin::signed(ALICE),
+        HDX,
+        100 * ONE,
+        DOT,
+        100 * ONE,
+        TradeFee::get(),
+    ));
+    assert_ok!(XYK::create_pool(
+        Origin::signed(ALICE),
+        DOT,
+        100 * ONE,
+        USDT,
+        200 * ONE,
+        TradeFee::get(),
+    ));
+}
+
+#[test]
+fn router_sell_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pools();
+
+        assert_ok!(XYK::router_sell(
+            Origin::signed(BOB),
+            route(vec![HDX, DOT, USDT]),
+            5 * ONE,
+            9_090_909_090_907,
+            None,
+        ));
+
+        assert_eq!(Tokens::balance(HDX, &BOB), 5 * ONE);
+        assert_eq!(Tokens::balance(DOT, &BOB), 0);
+        assert_eq!(Tokens::balance(USDT, &BOB), 9_090_909_090_907);
+
+        assert_eq!(
+            XYK::pool_reserves(HDX, DOT),
+            Some((105 * ONE, 95_238_095_238_096))
+        );
+        assert_eq!(
+            XYK::pool_reserves(DOT, USDT),
+            Some((104_761_904_761_904, 190_909_090_909_093))
+        );
+
+        expect_events(vec![
+            Event::<Test>::SwapExecuted {
+                who: BOB,
+                asset_in: HDX,
+                asset_out: DOT,
+                amount_in: 5 * ONE,
+                amount_out: 4_761_904_761_904,
+                lp_fee: 0,
+                protocol_fee: 0,
+            }
+            .into(),
+            Event::<Test>::SwapExecuted {
+                who: BOB,
+                asset_in: DOT,
+                asset_out: USDT,
+                amount_in: 4_761_904_761_904,
+                amount_out: 9_090_909_090_907,
+                lp_fee: 0,
+                protocol_fee: 0,
+            }
+            .into(),
+            Event::<Test>::RouteExecuted {
+                who: BOB,
+                asset_in: HDX,
+                asset_out: USDT,
+                amount_in: 5 * ONE,
+                amount_out: 9_090_909_090_907,
+            }
+            .into(),
+        ]);
+    });
+}
+
+#[test]
+fn router_buy_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pools();
+
+        assert_ok!(XYK::router_buy(
+            Origin::signed(BOB),
+            route(vec![HDX, DOT, USDT]),
+            5 * ONE,
+            2_631_578_947_369,
+            None,
+        ));
+
+        assert_eq!(Tokens::balance(HDX, &BOB), 7_368_421_052_631);
+        assert_eq!(Tokens::balance(DOT, &BOB), 0);
+        assert_eq!(Tokens::balance(USDT, &BOB), 5 * ONE);
+
+        expect_events(vec![
+            Event::<Test>::SwapExecuted {
+                who: BOB,
+                asset_in: HDX,
+                asset_out: DOT,
+                amount_in: 2_631_578_947_369,
+                amount_out: 2_564_102_564_103,
+                lp_fee: 0,
+                protocol_fee: 0,
+            }
+            .into(),
+            Event::<Test>::SwapExecuted {
+                who: BOB,
+                asset_in: DOT,
+                asset_out: USDT,
+                amount_in: 2_564_102_564_103,
+                amount_out: 5 * ONE,
+                lp_fee: 0,
+                protocol_fee: 0,
+            }
+            .into(),
+            Event::<Test>::RouteExecuted {
+                who: BOB,
+                asset_in: HDX,
+                asset_out: USDT,
+                amount_in: 2_631_578_947_369,
+                amount_out: 5 * ONE,
+            }
+            .into(),
+        ]);
+    });
+}
+
+#[test]
+fn router_sell_should_fail_when_amount_out_is_below_limit() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pools();
+
+        assert_noop!(
+            XYK::router_sell(
+                Origin::signed(BOB),
+                route(vec![HDX, DOT, USDT]),
+                5 * ONE,
+                9_090_909_090_908,
+                None,
+            ),
+            Error::<Test>::Limit
+        );
+    });
+}
+
+#[test]
+fn router_buy_should_fail_when_amount_in_is_above_limit() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pools();
+
+        assert_noop!(
+            XYK::router_buy(
+                Origin::signed(BOB),
+                route(vec![HDX, DOT, USDT]),
+                5 * ONE,
+                2_631_578_947_368,
+                None,
+            ),
+            Error::<Test>::Limit
+        );
+    });
+}
+
+#[test]
+fn router_should_fail_when_route_is_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pools();
+
+        assert_noop!(
+            XYK::router_sell(Origin::signed(BOB), route(vec![HDX]), 5 * ONE, 0, None),
+            Error::<Test>::InvalidRoute
+        );
+        assert_noop!(
+            XYK::router_sell(Origin::signed(BOB), route(vec![HDX, HDX]), 5 * ONE, 0, None),
+            Error::<Test>::InvalidRoute
+        );
+        assert_noop!(
+            XYK::router_buy(
+                Origin::signed(BOB),
+                route(vec![HDX, DOT, HDX]),
+                ONE,
+                u128::MAX,
+                None,
+            ),
+            Error::<Test>::InvalidRoute
+        );
+    });
+}
+
+#[test]
+fn router_should_fail_when_pool_in_route_does_not_exist() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pools();
+
+        assert_noop!(
+            XYK::router_sell(
+                Origin::signed(BOB),
+                route(vec![HDX, DOT, USDT, HDX]),
+                5 * ONE,
+                0,
+                None,
+            ),
+            Error::<Test>::PoolNotFound
+        );
+    });
+}
+
+#[test]
+fn best_sell_route_should_prefer_route_with_most_amount_out() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pools();
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            USDT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_eq!(
+            XYK::find_best_sell_route(HDX, USDT, 5 * ONE, 3),
+            Some((vec![HDX, DOT, USDT], 9_090_909_090_907))
+        );
+        assert_eq!(
+            XYK::find_best_sell_route(HDX, USDT, 5 * ONE, 1),
+            Some((vec![HDX, USDT], 4_761_904_761_904))
+        );
+    });
+}
+
+#[test]
+fn best_buy_route_should_prefer_route_with_least_amount_in() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pools();
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            USDT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_eq!(
+            XYK::find_best_buy_route(HDX, USDT, 5 * ONE, 3),
+            Some((vec![HDX, DOT, USDT], 2_631_578_947_369))
+        );
+        assert_eq!(
+            XYK::find_best_buy_route(HDX, USDT, 5 * ONE, 1),
+            Some((vec![HDX, USDT], 5_263_157_894_737))
+        );
+    });
+}
+
+#[test]
+fn best_route_should_match_executed_route() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .build()
+        .execute_with(|| {
+            create_pools();
+
+            let (best_route, amount_out) =
+                XYK::find_best_sell_route(HDX, USDT, 5 * ONE, 3).unwrap();
+
+            assert_ok!(XYK::router_sell(
+                Origin::signed(BOB),
+                route(best_route),
+                5 * ONE,
+                amount_out,
+                None,
+            ));
+
+            assert_eq!(Tokens::balance(USDT, &BOB), amount_out);
+        });
+}
+
+#[test]
+fn best_route_should_be_none_when_assets_are_not_connected() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pools();
+
+        assert_eq!(XYK::find_best_sell_route(HDX, 4000, ONE, 3), None);
+        assert_eq!(XYK::find_best_buy_route(HDX, 4000, ONE, 3), None);
+        assert_eq!(XYK::find_best_sell_route(HDX, USDT, ONE, 1), None);
+    });
+}
+
+#[test]
+fn router_trades_should_fail_when_deadline_expired() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pools();
+
+        System::set_block_number(2);
+
+        assert_noop!(
+            XYK::router_sell(
+                Origin::signed(BOB),
+                route(vec![HDX, DOT, USDT]),
+                5 * ONE,
+                0,
+                Some(1),
+            ),
+            Error::<Test>::DeadlineExpired
+        );
+        assert_noop!(
+            XYK::router_buy(
+                Origin::signed(BOB),
+                route(vec![HDX, DOT, USDT]),
+                ONE,
+                u128::MAX,
+                Some(1),
+            ),
+            Error::<Test>::DeadlineExpired
+        );
+    });
+}
@@ -0,0 +1,160 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
+
+use crate::{AssetId, Balance, PoolId};
+use frame_support::sp_runtime::Permill;
+use frame_support::{assert_ok, BoundedVec};
+use pallet_amm_runtime_api::runtime_decl_for_AmmApi::AmmApi;
+
+const ONE: Balance = 1_000_000_000_000;
+
+fn create_multi_asset_pool() {
+    let assets: BoundedVec<(AssetId, Balance), MaxAssetsInPool> =
+        vec![(USDT, 100 * ONE), (HDX, 100 * ONE), (DOT, 100 * ONE)]
+            .try_into()
+            .unwrap();
+
+    assert_ok!(XYK::create_multi_asset_pool(
+        Origin::signed(ALICE),
+        assets,
+        100
+    ));
+}
+
+#[test]
+fn runtime_api_should_quote_pool_of_pair() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .build()
+        .execute_with(|| {
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                HDX,
+                100 * ONE,
+                DOT,
+                100 * ONE,
+                TradeFee::get(),
+            ));
+
+            assert_eq!(
+                <Test as AmmApi<Block, AssetId, Balance, PoolId>>::pool_reserves(DOT, HDX),
+                Some((100 * ONE, 100 * ONE))
+            );
+            assert_eq!(
+                <Test as AmmApi<Block, AssetId, Balance, PoolId>>::share_asset(HDX, DOT),
+                Some(POOL_SHARE_ASSET)
+            );
+            assert_eq!(
+                <Test as AmmApi<Block, AssetId, Balance, PoolId>>::pool_reserves_by_pool_id(0),
+                Some(vec![(HDX, 100 * ONE), (DOT, 100 * ONE)])
+            );
+
+            let quote =
+                <Test as AmmApi<Block, AssetId, Balance, PoolId>>::quote_sell(HDX, DOT, 5 * ONE);
+            assert_eq!(quote, Some((4_747_619_047_618, 14_285_714_286)));
+            assert_eq!(
+                <Test as AmmApi<Block, AssetId, Balance, PoolId>>::quote_sell_by_pool_id(
+                    0,
+                    HDX,
+                    DOT,
+                    5 * ONE
+                ),
+                quote
+            );
+            assert_eq!(
+                <Test as AmmApi<Block, AssetId, Balance, PoolId>>::quote_buy_by_pool_id(
+                    0,
+                    DOT,
+                    HDX,
+                    5 * ONE
+                ),
+                XYK::quote_buy(DOT, HDX, 5 * ONE)
+            );
+
+            let (amount_out, _) = quote.unwrap();
+            assert_ok!(XYK::sell(
+                Origin::signed(BOB),
+                HDX,
+                DOT,
+                5 * ONE,
+                amount_out,
+                None,
+            ));
+
+            assert_eq!(Tokens::balance(DOT, &BOB), amount_out);
+        });
+}
+
+#[test]
+fn runtime_api_should_quote_multi_asset_pool_by_pool_id() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_eq!(
+            <Test as AmmApi<Block, AssetId, Balance, PoolId>>::pool_reserves_by_pool_id(0),
+            Some(vec![(HDX, 100 * ONE), (DOT, 100 * ONE), (USDT, 100 * ONE)])
+        );
+
+        let (amount_out, _) =
+            <Test as AmmApi<Block, AssetId, Balance, PoolId>>::quote_sell_by_pool_id(
+                0,
+                HDX,
+                USDT,
+                5 * ONE,
+            )
+            .unwrap();
+
+        assert_ok!(XYK::sell_multi_asset(
+            Origin::signed(CHARLIE),
+            0,
+            HDX,
+            USDT,
+            5 * ONE,
+            amount_out,
+            None,
+        ));
+
+        assert_eq!(Tokens::balance(USDT, &CHARLIE), amount_out);
+
+        let (amount_in, _) =
+            <Test as AmmApi<Block, AssetId, Balance, PoolId>>::quote_buy_by_pool_id(
+                0, HDX, DOT, ONE,
+            )
+            .unwrap();
+
+        assert_ok!(XYK::buy_multi_asset(
+            Origin::signed(CHARLIE),
+            0,
+            HDX,
+            DOT,
+            ONE,
+            amount_in,
+            None,
+        ));
+
+        assert_eq!(Tokens::balance(DOT, &CHARLIE), 10 * ONE - amount_in);
+        assert_eq!(Tokens::balance(HDX, &CHARLIE), 6 * ONE);
+    });
+}
+
+#[test]
+fn runtime_api_should_return_none_when_pool_does_not_exist() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_multi_asset_pool();
+
+        assert_eq!(
+            <Test as AmmApi<Block, AssetId, Balance, PoolId>>::pool_reserves_by_pool_id(1),
+            None
+        );
+        assert_eq!(
+            <Test as AmmApi<Block, AssetId, Balance, PoolId>>::quote_sell_by_pool_id(
+                1, HDX, USDT, ONE
+            ),
+            None
+        );
+        assert_eq!(
+            <Test as AmmApi<Block, AssetId, Balance, PoolId>>::quote_sell(HDX, USDT, ONE),
+            None
+        );
+    });
+}
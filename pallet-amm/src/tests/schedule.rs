@@ -0,0 +1,413 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
+
+use crate::types::Schedule;
+use crate::{Balance, Error, Event};
+use frame_support::sp_runtime::Permill;
+use frame_support::traits::{OnFinalize, OnInitialize};
+use frame_support::{assert_noop, assert_ok};
+
+const ONE: Balance = 1_000_000_000_000;
+
+fn create_pool() {
+    assert_ok!(XYK::create_pool(
+        Origin::signed(ALICE),
+        HDX,
+        100 * ONE,
+        DOT,
+        100 * ONE,
+        TradeFee::get(),
+    ));
+}
+
+/// Creates HDX/DOT liquidity bootstrapping pool with sale from block 10 to block 20.
+fn create_lbp_pool() {
+    assert_ok!(XYK::create_lbp_pool(
+        Origin::signed(ALICE),
+        HDX,
+        100 * ONE,
+        DOT,
+        100 * ONE,
+        10,
+        20,
+        80_000_000,
+        20_000_000,
+    ));
+}
+
+fn schedule_sell(who: AccountId, budget: Balance, slippage: Permill) {
+    assert_ok!(XYK::schedule_sell(
+        Origin::signed(who),
+        HDX,
+        DOT,
+        ONE,
+        5,
+        budget,
+        slippage,
+        None,
+    ));
+}
+
+#[test]
+fn schedule_sell_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        schedule_sell(BOB, 3 * ONE, Permill::from_percent(10));
+
+        assert_eq!(
+            XYK::schedule(0),
+            Some(Schedule {
+                owner: BOB,
+                asset_in: HDX,
+                asset_out: DOT,
+                amount: ONE,
+                period: 5,
+                remaining: 3 * ONE,
+                slippage: Permill::from_percent(10),
+                next_execution: 2,
+                failures: 0,
+            })
+        );
+        assert_eq!(XYK::scheduled_executions(2).into_inner(), vec![0]);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 3 * ONE);
+
+        expect_events(vec![Event::<Test>::SellScheduled {
+            who: BOB,
+            schedule_id: 0,
+            asset_in: HDX,
+            asset_out: DOT,
+            amount: ONE,
+            period: 5,
+            budget: 3 * ONE,
+            slippage: Permill::from_percent(10),
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn schedule_sell_should_fail_when_params_are_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        let cases = vec![(0, 5, ONE), (ONE, 0, ONE), (ONE, 5, ONE - 1)];
+
+        for (amount, period, budget) in cases {
+            assert_noop!(
+                XYK::schedule_sell(
+                    Origin::signed(BOB),
+                    HDX,
+                    DOT,
+                    amount,
+                    period,
+                    budget,
+                    Permill::from_percent(10),
+                    None,
+                ),
+                Error::<Test>::InvalidSchedule
+            );
+        }
+
+        assert_noop!(
+            XYK::schedule_sell(
+                Origin::signed(BOB),
+                HDX,
+                USDT,
+                ONE,
+                5,
+                ONE,
+                Permill::from_percent(10),
+                None,
+            ),
+            Error::<Test>::PoolNotFound
+        );
+        assert_noop!(
+            XYK::schedule_sell(
+                Origin::signed(BOB),
+                HDX,
+                DOT,
+                ONE,
+                5,
+                20 * ONE,
+                Permill::from_percent(10),
+                None,
+            ),
+            orml_tokens::Error::<Test>::BalanceTooLow
+        );
+    });
+}
+
+#[test]
+fn schedules_in_block_should_be_bounded() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        schedule_sell(BOB, ONE, Permill::from_percent(10));
+        schedule_sell(BOB, ONE, Permill::from_percent(10));
+        schedule_sell(CHARLIE, ONE, Permill::from_percent(10));
+
+        assert_eq!(XYK::scheduled_executions(2).into_inner(), vec![0, 1]);
+        assert_eq!(XYK::scheduled_executions(3).into_inner(), vec![2]);
+        assert_eq!(
+            XYK::schedule(2).map(|schedule| schedule.next_execution),
+            Some(3)
+        );
+    });
+}
+
+#[test]
+fn schedule_should_be_executed_every_period_until_budget_is_sold() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        schedule_sell(BOB, 5 * ONE / 2, Permill::from_percent(10));
+
+        let (amount_out, _) = XYK::quote_sell(HDX, DOT, ONE).unwrap();
+
+        XYK::on_initialize(2);
+
+        assert_eq!(Tokens::balance(DOT, &BOB), amount_out);
+        assert_eq!(
+            XYK::schedule(0).map(|schedule| (schedule.remaining, schedule.next_execution)),
+            Some((3 * ONE / 2, 7))
+        );
+        assert_eq!(XYK::scheduled_executions(7).into_inner(), vec![0]);
+
+        expect_events(vec![Event::<Test>::ScheduleExecuted {
+            who: BOB,
+            schedule_id: 0,
+            amount_in: ONE,
+            amount_out,
+            remaining: 3 * ONE / 2,
+        }
+        .into()]);
+
+        XYK::on_initialize(7);
+
+        // Last execution sells the rest of the budget.
+        let (amount_out, _) = XYK::quote_sell(HDX, DOT, ONE / 2).unwrap();
+
+        XYK::on_initialize(12);
+
+        assert_eq!(XYK::schedule(0), None);
+        assert!(XYK::scheduled_executions(17).is_empty());
+        assert_eq!(Tokens::balance(HDX, &BOB), 10 * ONE - 5 * ONE / 2);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 0);
+
+        expect_events(vec![Event::<Test>::ScheduleExecuted {
+            who: BOB,
+            schedule_id: 0,
+            amount_in: ONE / 2,
+            amount_out,
+            remaining: 0,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn schedule_execution_should_fail_when_price_is_below_oracle_price() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        XYK::on_finalize(1);
+
+        // Spot price of HDX drops to 0.69 DOT, while the oracle price is still 1 DOT.
+        assert_ok!(XYK::sell(
+            Origin::signed(ALICE),
+            HDX,
+            DOT,
+            20 * ONE,
+            0,
+            None
+        ));
+
+        schedule_sell(BOB, 3 * ONE, Permill::from_percent(10));
+
+        XYK::on_initialize(2);
+
+        assert_eq!(Tokens::balance(DOT, &BOB), 0);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 3 * ONE);
+        assert_eq!(
+            XYK::schedule(0).map(|schedule| (schedule.failures, schedule.next_execution)),
+            Some((1, 7))
+        );
+
+        expect_events(vec![Event::<Test>::ScheduleExecutionFailed {
+            who: BOB,
+            schedule_id: 0,
+            error: Error::<Test>::Limit.into(),
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn schedule_should_be_terminated_after_repeated_failures() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        // Price impact of every execution exceeds zero slippage.
+        schedule_sell(BOB, 3 * ONE, Permill::zero());
+
+        XYK::on_initialize(2);
+        XYK::on_initialize(7);
+
+        assert_eq!(XYK::schedule(0).map(|schedule| schedule.failures), Some(2));
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 3 * ONE);
+
+        XYK::on_initialize(12);
+
+        assert_eq!(XYK::schedule(0), None);
+        assert!(XYK::scheduled_executions(17).is_empty());
+        assert_eq!(Tokens::balance(HDX, &BOB), 10 * ONE);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 0);
+
+        expect_events(vec![Event::<Test>::ScheduleTerminated {
+            who: BOB,
+            schedule_id: 0,
+            remaining: 3 * ONE,
+            error: Error::<Test>::Limit.into(),
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn schedule_should_be_terminated_when_pool_is_destroyed() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        schedule_sell(BOB, 3 * ONE, Permill::from_percent(10));
+
+        // Liquidity bootstrapping pool locks no liquidity, so that its owner can destroy it.
+        System::set_block_number(21);
+        assert_ok!(XYK::remove_liquidity(
+            Origin::signed(ALICE),
+            HDX,
+            DOT,
+            100 * ONE,
+            0,
+            0,
+            None,
+        ));
+
+        XYK::on_initialize(2);
+
+        assert_eq!(XYK::schedule(0), None);
+        assert!(XYK::scheduled_executions(7).is_empty());
+        assert_eq!(Tokens::balance(HDX, &BOB), 10 * ONE);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 0);
+
+        expect_events(vec![
+            Event::<Test>::ScheduleExecutionFailed {
+                who: BOB,
+                schedule_id: 0,
+                error: Error::<Test>::PoolNotFound.into(),
+            }
+            .into(),
+            Event::<Test>::ScheduleTerminated {
+                who: BOB,
+                schedule_id: 0,
+                remaining: 3 * ONE,
+                error: Error::<Test>::PoolNotFound.into(),
+            }
+            .into(),
+        ]);
+    });
+}
+
+#[test]
+fn schedule_should_be_terminated_when_sale_has_ended() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_lbp_pool();
+
+        assert_ok!(XYK::schedule_sell(
+            Origin::signed(BOB),
+            HDX,
+            DOT,
+            ONE,
+            20,
+            3 * ONE,
+            Permill::from_percent(10),
+            None,
+        ));
+
+        // Execution before the sale fails, but the schedule waits for the sale.
+        System::set_block_number(2);
+        XYK::on_initialize(2);
+
+        assert_eq!(
+            XYK::schedule(0).map(|schedule| (schedule.failures, schedule.next_execution)),
+            Some((1, 22))
+        );
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 3 * ONE);
+
+        System::set_block_number(22);
+        XYK::on_initialize(22);
+
+        assert_eq!(XYK::schedule(0), None);
+        assert_eq!(Tokens::balance(HDX, &BOB), 10 * ONE);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 0);
+
+        expect_events(vec![
+            Event::<Test>::ScheduleExecutionFailed {
+                who: BOB,
+                schedule_id: 0,
+                error: Error::<Test>::SaleNotRunning.into(),
+            }
+            .into(),
+            Event::<Test>::ScheduleTerminated {
+                who: BOB,
+                schedule_id: 0,
+                remaining: 3 * ONE,
+                error: Error::<Test>::SaleNotRunning.into(),
+            }
+            .into(),
+        ]);
+    });
+}
+
+#[test]
+fn cancel_schedule_should_release_remaining_budget() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        schedule_sell(BOB, 3 * ONE, Permill::from_percent(10));
+
+        XYK::on_initialize(2);
+
+        assert_ok!(XYK::cancel_schedule(Origin::signed(BOB), 0));
+
+        assert_eq!(XYK::schedule(0), None);
+        assert!(XYK::scheduled_executions(7).is_empty());
+        assert_eq!(Tokens::balance(HDX, &BOB), 9 * ONE);
+        assert_eq!(Tokens::balance_on_hold(HDX, &BOB), 0);
+
+        expect_events(vec![Event::<Test>::ScheduleCancelled {
+            who: BOB,
+            schedule_id: 0,
+            remaining: 2 * ONE,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn cancel_schedule_should_fail_when_caller_is_not_owner() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_pool();
+
+        schedule_sell(BOB, 3 * ONE, Permill::from_percent(10));
+
+        assert_noop!(
+            XYK::cancel_schedule(Origin::signed(CHARLIE), 0),
+            Error::<Test>::NotScheduleOwner
+        );
+        assert_noop!(
+            XYK::cancel_schedule(Origin::signed(BOB), 1),
+            Error::<Test>::ScheduleNotFound
+        );
+    });
+}
@@ -0,0 +1,224 @@
+use crate::mock::*;
+use frame_support::traits::tokens::fungibles::*;
+
+use crate::types::{PoolType, StableParams, MAX_AMPLIFICATION};
+use crate::{Balance, Error, Event, Pools};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::traits::BadOrigin;
+
+const ONE: Balance = 1_000_000_000_000;
+
+fn create_stable_pool() {
+    assert_ok!(XYK::create_stable_pool(
+        Origin::signed(ALICE),
+        HDX,
+        100 * ONE,
+        DOT,
+        100 * ONE,
+        100,
+    ));
+}
+
+fn stable_params() -> StableParams<u64> {
+    match Pools::<Test>::get(0).map(|pool| pool.pool_type) {
+        Some(PoolType::Stable(params)) => params,
+        _ => panic!("Stableswap pool does not exist."),
+    }
+}
+
+#[test]
+fn create_stable_pool_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_stable_pool();
+
+        assert_eq!(
+            stable_params(),
+            StableParams {
+                initial_amplification: 100,
+                final_amplification: 100,
+                start: 1,
+                end: 1,
+            }
+        );
+        assert_eq!(Tokens::total_issuance(POOL_SHARE_ASSET), 200 * ONE);
+        assert_eq!(
+            Tokens::balance(POOL_SHARE_ASSET, &ALICE),
+            200 * ONE - MinimumLiquidity::get()
+        );
+    });
+}
+
+#[test]
+fn create_stable_pool_should_fail_when_amplification_is_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        for amplification in [0, MAX_AMPLIFICATION + 1] {
+            assert_noop!(
+                XYK::create_stable_pool(
+                    Origin::signed(ALICE),
+                    HDX,
+                    100 * ONE,
+                    DOT,
+                    100 * ONE,
+                    amplification,
+                ),
+                Error::<Test>::InvalidAmplification
+            );
+        }
+    });
+}
+
+#[test]
+fn sell_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_stable_pool();
+
+        assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+
+        let expected =
+            math::stableswap::calculate_out_given_in(&[100 * ONE, 100 * ONE], 0, 1, 5 * ONE, 100)
+                .unwrap();
+
+        assert_eq!(Tokens::balance(HDX, &BOB), 5 * ONE);
+        assert_eq!(Tokens::balance(DOT, &BOB), expected);
+        // Constant product pool of the same reserves gives 4_761_904_761_904
+        assert!(expected > 4_990_000_000_000);
+    });
+}
+
+#[test]
+fn buy_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_stable_pool();
+
+        assert_ok!(XYK::buy(
+            Origin::signed(BOB),
+            DOT,
+            HDX,
+            5 * ONE,
+            u128::MAX,
+            None
+        ));
+
+        let expected =
+            math::stableswap::calculate_in_given_out(&[100 * ONE, 100 * ONE], 0, 1, 5 * ONE, 100)
+                .unwrap();
+
+        assert_eq!(Tokens::balance(HDX, &BOB), 10 * ONE - expected);
+        assert_eq!(Tokens::balance(DOT, &BOB), 5 * ONE);
+    });
+}
+
+#[test]
+fn spot_price_should_be_one_when_pool_is_balanced() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_stable_pool();
+
+        assert_eq!(XYK::spot_price(HDX, DOT, ONE), Some(ONE));
+    });
+}
+
+#[test]
+fn update_amplification_should_change_amplification_linearly() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_stable_pool();
+
+        assert_ok!(XYK::update_amplification(Origin::root(), 0, 200, 10, 20));
+
+        expect_events(vec![Event::<Test>::AmplificationUpdated {
+            pool_id: 0,
+            initial_amplification: 100,
+            final_amplification: 200,
+            start: 10,
+            end: 20,
+        }
+        .into()]);
+
+        let params = stable_params();
+        for (block, amplification) in [(5, 100), (10, 100), (15, 150), (20, 200), (25, 200)] {
+            System::set_block_number(block);
+            assert_eq!(XYK::amplification(&params), Ok(amplification));
+        }
+    });
+}
+
+#[test]
+fn update_amplification_should_start_from_current_amplification() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_stable_pool();
+
+        assert_ok!(XYK::update_amplification(Origin::root(), 0, 200, 10, 20));
+
+        System::set_block_number(15);
+
+        assert_ok!(XYK::update_amplification(Origin::root(), 0, 50, 15, 25));
+
+        assert_eq!(
+            stable_params(),
+            StableParams {
+                initial_amplification: 150,
+                final_amplification: 50,
+                start: 15,
+                end: 25,
+            }
+        );
+    });
+}
+
+#[test]
+fn update_amplification_should_fail_when_origin_is_not_authority() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_stable_pool();
+
+        assert_noop!(
+            XYK::update_amplification(Origin::signed(BOB), 0, 200, 10, 20),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn update_amplification_should_fail_when_params_are_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_stable_pool();
+
+        System::set_block_number(10);
+
+        let cases = vec![
+            (0, 10, 20),
+            (MAX_AMPLIFICATION + 1, 10, 20),
+            (200, 9, 20),
+            (200, 20, 20),
+        ];
+
+        for (amplification, start, end) in cases {
+            assert_noop!(
+                XYK::update_amplification(Origin::root(), 0, amplification, start, end),
+                Error::<Test>::InvalidAmplification
+            );
+        }
+    });
+}
+
+#[test]
+fn update_amplification_should_fail_when_pool_is_not_stable() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            XYK::update_amplification(Origin::root(), 0, 200, 10, 20),
+            Error::<Test>::PoolNotFound
+        );
+
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_noop!(
+            XYK::update_amplification(Origin::root(), 0, 200, 10, 20),
+            Error::<Test>::NotSupportedByPoolType
+        );
+    });
+}
@@ -1,8 +1,9 @@
 use crate::mock::*;
 use frame_support::traits::tokens::fungibles::*;
 
-use crate::Balance;
-use frame_support::assert_ok;
+use crate::{Balance, Error, Event};
+use frame_support::sp_runtime::Permill;
+use frame_support::{assert_noop, assert_ok};
 
 const ONE: Balance = 1_000_000_000_000;
 
@@ -17,6 +18,7 @@ fn sell_should_work() {
             100_000_000_000_000,
             asset_out,
             100_000_000_000_000,
+            TradeFee::get(),
         ));
 
         assert_ok!(XYK::sell(
@@ -25,6 +27,7 @@ fn sell_should_work() {
             asset_out,
             5 * ONE,
             0u128,
+            None,
         ));
 
         assert_eq!(Tokens::balance(asset_in, &BOB), 5 * ONE);
@@ -43,6 +46,7 @@ fn buy_should_work() {
             100_000_000_000_000,
             asset_out,
             100_000_000_000_000,
+            TradeFee::get(),
         ));
 
         assert_ok!(XYK::buy(
@@ -51,9 +55,375 @@ fn buy_should_work() {
             asset_in,
             4_761_904_761_904,
             u128::MAX,
+            None,
         ));
 
-        assert_eq!(Tokens::balance(asset_in, &BOB), 5_238_095_238_096);
+        assert_eq!(Tokens::balance(asset_in, &BOB), 5 * ONE);
         assert_eq!(Tokens::balance(asset_out, &BOB), 4_761_904_761_904);
     });
 }
+
+#[test]
+fn sell_should_deduct_fee_from_amount_out() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .build()
+        .execute_with(|| {
+            let asset_in = HDX;
+            let asset_out = DOT;
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                asset_in,
+                100_000_000_000_000,
+                asset_out,
+                100_000_000_000_000,
+                TradeFee::get(),
+            ));
+
+            assert_ok!(XYK::sell(
+                Origin::signed(BOB),
+                asset_in,
+                asset_out,
+                5 * ONE,
+                0u128,
+                None,
+            ));
+
+            assert_eq!(Tokens::balance(asset_in, &BOB), 5 * ONE);
+            assert_eq!(Tokens::balance(asset_out, &BOB), 4_747_619_047_618);
+
+            expect_events(vec![Event::<Test>::SwapExecuted {
+                who: BOB,
+                asset_in,
+                asset_out,
+                amount_in: 5 * ONE,
+                amount_out: 4_747_619_047_618,
+                lp_fee: 14_285_714_286,
+                protocol_fee: 0,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn buy_should_add_fee_to_amount_in() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .build()
+        .execute_with(|| {
+            let asset_in = HDX;
+            let asset_out = DOT;
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                asset_in,
+                100_000_000_000_000,
+                asset_out,
+                100_000_000_000_000,
+                TradeFee::get(),
+            ));
+
+            assert_ok!(XYK::buy(
+                Origin::signed(BOB),
+                asset_out,
+                asset_in,
+                5 * ONE,
+                u128::MAX,
+                None,
+            ));
+
+            assert_eq!(Tokens::balance(asset_in, &BOB), 4_721_052_631_578);
+            assert_eq!(Tokens::balance(asset_out, &BOB), 5 * ONE);
+
+            expect_events(vec![Event::<Test>::SwapExecuted {
+                who: BOB,
+                asset_in,
+                asset_out,
+                amount_in: 5_278_947_368_422,
+                amount_out: 5 * ONE,
+                lp_fee: 15_789_473_685,
+                protocol_fee: 0,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn buy_should_fail_when_amount_in_with_fee_exceeds_limit() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .build()
+        .execute_with(|| {
+            let asset_in = HDX;
+            let asset_out = DOT;
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                asset_in,
+                100_000_000_000_000,
+                asset_out,
+                100_000_000_000_000,
+                TradeFee::get(),
+            ));
+
+            assert_noop!(
+                XYK::buy(
+                    Origin::signed(BOB),
+                    asset_out,
+                    asset_in,
+                    5 * ONE,
+                    5_278_947_368_421,
+                    None,
+                ),
+                Error::<Test>::Limit
+            );
+        });
+}
+
+#[test]
+fn sell_should_work_when_deadline_is_current_block() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        assert_ok!(XYK::sell(
+            Origin::signed(BOB),
+            HDX,
+            DOT,
+            5 * ONE,
+            0u128,
+            Some(1),
+        ));
+    });
+}
+
+#[test]
+fn sell_should_fail_when_deadline_expired() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        System::set_block_number(2);
+
+        assert_noop!(
+            XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0u128, Some(1)),
+            Error::<Test>::DeadlineExpired
+        );
+    });
+}
+
+#[test]
+fn buy_should_fail_when_deadline_expired() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100 * ONE,
+            DOT,
+            100 * ONE,
+            TradeFee::get(),
+        ));
+
+        System::set_block_number(2);
+
+        assert_noop!(
+            XYK::buy(Origin::signed(BOB), DOT, HDX, ONE, u128::MAX, Some(1)),
+            Error::<Test>::DeadlineExpired
+        );
+    });
+}
+
+#[test]
+fn sell_should_transfer_protocol_fee_to_receiver() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .with_protocol_fee(Permill::from_percent(20))
+        .build()
+        .execute_with(|| {
+            let asset_in = HDX;
+            let asset_out = DOT;
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                asset_in,
+                100_000_000_000_000,
+                asset_out,
+                100_000_000_000_000,
+                TradeFee::get(),
+            ));
+
+            assert_ok!(XYK::sell(
+                Origin::signed(BOB),
+                asset_in,
+                asset_out,
+                5 * ONE,
+                0u128,
+                None,
+            ));
+
+            assert_eq!(Tokens::balance(asset_out, &BOB), 4_747_619_047_618);
+            assert_eq!(
+                Tokens::balance(asset_out, &ProtocolFeeReceiver::get()),
+                2_857_142_857
+            );
+            assert_eq!(
+                XYK::pool_reserves(asset_in, asset_out),
+                Some((105 * ONE, 95_249_523_809_525))
+            );
+
+            expect_events(vec![Event::<Test>::SwapExecuted {
+                who: BOB,
+                asset_in,
+                asset_out,
+                amount_in: 5 * ONE,
+                amount_out: 4_747_619_047_618,
+                lp_fee: 11_428_571_429,
+                protocol_fee: 2_857_142_857,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn buy_should_transfer_protocol_fee_to_receiver() {
+    ExtBuilder::default()
+        .with_trade_fee(Permill::from_parts(3_000))
+        .with_protocol_fee(Permill::from_percent(20))
+        .build()
+        .execute_with(|| {
+            let asset_in = HDX;
+            let asset_out = DOT;
+            assert_ok!(XYK::create_pool(
+                Origin::signed(ALICE),
+                asset_in,
+                100_000_000_000_000,
+                asset_out,
+                100_000_000_000_000,
+                TradeFee::get(),
+            ));
+
+            assert_ok!(XYK::buy(
+                Origin::signed(BOB),
+                asset_out,
+                asset_in,
+                5 * ONE,
+                u128::MAX,
+                None,
+            ));
+
+            assert_eq!(Tokens::balance(asset_in, &BOB), 4_721_052_631_578);
+            assert_eq!(
+                Tokens::balance(asset_in, &ProtocolFeeReceiver::get()),
+                3_157_894_737
+            );
+            assert_eq!(
+                XYK::pool_reserves(asset_in, asset_out),
+                Some((105_275_789_473_685, 95 * ONE))
+            );
+
+            expect_events(vec![Event::<Test>::SwapExecuted {
+                who: BOB,
+                asset_in,
+                asset_out,
+                amount_in: 5_278_947_368_422,
+                amount_out: 5 * ONE,
+                lp_fee: 12_631_578_948,
+                protocol_fee: 3_157_894_737,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn trades_should_not_decrease_product_of_reserves_when_protocol_fee_is_taken() {
+    let protocol_fees = [
+        Permill::zero(),
+        Permill::from_percent(20),
+        Permill::from_percent(50),
+        Permill::one(),
+    ];
+
+    for protocol_fee in protocol_fees {
+        ExtBuilder::default()
+            .with_trade_fee(Permill::from_parts(3_000))
+            .with_protocol_fee(protocol_fee)
+            .build()
+            .execute_with(|| {
+                assert_ok!(XYK::create_pool(
+                    Origin::signed(ALICE),
+                    HDX,
+                    100 * ONE,
+                    DOT,
+                    100 * ONE,
+                    TradeFee::get(),
+                ));
+
+                let product = || {
+                    let (a, b) = XYK::pool_reserves(HDX, DOT).unwrap();
+                    sp_core::U256::from(a) * sp_core::U256::from(b)
+                };
+
+                let initial = product();
+
+                assert_ok!(XYK::sell(Origin::signed(BOB), HDX, DOT, 5 * ONE, 0, None));
+                let after_sell = product();
+                assert!(after_sell >= initial);
+
+                assert_ok!(XYK::buy(
+                    Origin::signed(CHARLIE),
+                    HDX,
+                    DOT,
+                    2 * ONE,
+                    u128::MAX,
+                    None
+                ));
+                assert!(product() >= after_sell);
+            });
+    }
+}
+
+#[test]
+fn sell_should_use_fee_of_pool() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XYK::create_pool(
+            Origin::signed(ALICE),
+            HDX,
+            100_000_000_000_000,
+            DOT,
+            100_000_000_000_000,
+            Permill::from_parts(3_000),
+        ));
+
+        assert_eq!(
+            XYK::quote_sell(HDX, DOT, 5 * ONE),
+            Some((4_747_619_047_618, 14_285_714_286))
+        );
+
+        assert_ok!(XYK::update_pool_fee(Origin::root(), 0, Permill::zero()));
+
+        assert_eq!(
+            XYK::quote_sell(HDX, DOT, 5 * ONE),
+            Some((4_761_904_761_904, 0))
+        );
+
+        assert_ok!(XYK::sell(
+            Origin::signed(BOB),
+            HDX,
+            DOT,
+            5 * ONE,
+            0u128,
+            None,
+        ));
+
+        assert_eq!(Tokens::balance(DOT, &BOB), 4_761_904_761_904);
+    });
+}
@@ -1,3 +1,6 @@
+use frame_support::weights::Weight;
+use sp_runtime::{DispatchResult, Permill};
+
 pub trait Create<Assets> {
     type AssetId;
     type Error;
@@ -10,3 +13,149 @@ pub trait AccountIdFor<Assets> {
 
     fn create_account_id(assets: Assets) -> Result<Self::AccountId, Self::Error>;
 }
+
+/// Provides prices and liquidity averaged by the oracle.
+pub trait PriceOracle<AssetId, Price, Balance> {
+    /// Returns price of `asset_a` denominated in `asset_b` and liquidity of their pool,
+    /// both averaged over the last `window` blocks.
+    fn get_price(asset_a: AssetId, asset_b: AssetId, window: u32) -> Option<(Price, Balance)>;
+}
+
+/// Handler of trades executed in pools.
+pub trait OnTrade<AccountId, AssetId, Balance> {
+    /// Called after `amount_in` of `asset_in` is traded for `amount_out` of `asset_out` by `who`.
+    /// `reserves` are reserves of `asset_in` and `asset_out` in the pool after the trade.
+    fn on_trade(
+        who: &AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        amount_out: Balance,
+        fee: Balance,
+        reserves: (Balance, Balance),
+    );
+
+    /// Weight of `on_trade`.
+    fn on_trade_weight() -> Weight;
+}
+
+impl<AccountId, AssetId, Balance> OnTrade<AccountId, AssetId, Balance> for () {
+    fn on_trade(
+        _who: &AccountId,
+        _asset_in: AssetId,
+        _asset_out: AssetId,
+        _amount_in: Balance,
+        _amount_out: Balance,
+        _fee: Balance,
+        _reserves: (Balance, Balance),
+    ) {
+    }
+
+    fn on_trade_weight() -> Weight {
+        Weight::zero()
+    }
+}
+
+/// Handler of liquidity added to or removed from pools.
+pub trait OnLiquidityChanged<AccountId, AssetId, Balance> {
+    /// Called after `amounts` of `assets` are added to the pool by `who` for `shares`.
+    /// `reserves` are reserves of `assets` in the pool after the change.
+    fn on_liquidity_added(
+        who: &AccountId,
+        assets: &[AssetId],
+        amounts: &[Balance],
+        shares: Balance,
+        reserves: &[Balance],
+    );
+
+    /// Called after `amounts` of `assets` are removed from the pool by `who` for `shares`.
+    /// `reserves` are reserves of `assets` in the pool after the change.
+    fn on_liquidity_removed(
+        who: &AccountId,
+        assets: &[AssetId],
+        amounts: &[Balance],
+        shares: Balance,
+        reserves: &[Balance],
+    );
+
+    /// Weight of `on_liquidity_added` and `on_liquidity_removed`.
+    fn on_liquidity_changed_weight() -> Weight;
+}
+
+impl<AccountId, AssetId, Balance> OnLiquidityChanged<AccountId, AssetId, Balance> for () {
+    fn on_liquidity_added(
+        _who: &AccountId,
+        _assets: &[AssetId],
+        _amounts: &[Balance],
+        _shares: Balance,
+        _reserves: &[Balance],
+    ) {
+    }
+
+    fn on_liquidity_removed(
+        _who: &AccountId,
+        _assets: &[AssetId],
+        _amounts: &[Balance],
+        _shares: Balance,
+        _reserves: &[Balance],
+    ) {
+    }
+
+    fn on_liquidity_changed_weight() -> Weight {
+        Weight::zero()
+    }
+}
+
+/// Curve of dynamic trade fee of a pool, which rises after trades with large price impact
+/// and decays back to the fee of the pool.
+pub trait FeeCurve {
+    /// Returns `fee` decayed towards `floor` over `elapsed` blocks.
+    fn decay(fee: Permill, floor: Permill, elapsed: u32) -> Permill;
+
+    /// Returns `fee` raised after a trade which moved price of the pool by `price_impact`.
+    fn raise(fee: Permill, price_impact: Permill) -> Permill;
+}
+
+/// Fee of a pool is constant.
+impl FeeCurve for () {
+    fn decay(_fee: Permill, floor: Permill, _elapsed: u32) -> Permill {
+        floor
+    }
+
+    fn raise(fee: Permill, _price_impact: Permill) -> Permill {
+        fee
+    }
+}
+
+/// Handler of flash swaps, which uses the borrowed assets and repays them to the pool.
+pub trait FlashSwapHandler<AccountId, AssetId, Balance> {
+    /// Called after `amount_out` of `asset_out` is sent to `who` from `pool_account`.
+    /// Before it returns, the pool must be repaid in `asset_in`, `asset_out` or both,
+    /// so that the product of its reserves without fee does not decrease.
+    fn on_flash_swap(
+        who: &AccountId,
+        pool_account: &AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: Balance,
+    ) -> DispatchResult;
+
+    /// Weight of `on_flash_swap`.
+    fn on_flash_swap_weight() -> Weight;
+}
+
+impl<AccountId, AssetId, Balance> FlashSwapHandler<AccountId, AssetId, Balance> for () {
+    fn on_flash_swap(
+        _who: &AccountId,
+        _pool_account: &AccountId,
+        _asset_in: AssetId,
+        _asset_out: AssetId,
+        _amount_out: Balance,
+    ) -> DispatchResult {
+        Ok(())
+    }
+
+    fn on_flash_swap_weight() -> Weight {
+        Weight::zero()
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{AssetId, Balance, PoolId};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{traits::Get, BoundedVec};
+use math::concentrated::{FeeGrowth, Liquidity, SqrtPrice, SwapResult, Tick};
+use math::lbp::LBPWeight;
+use scale_info::TypeInfo;
+use sp_runtime::{FixedU128, Permill};
+
+/// Trade in a single pool, calculated before it is executed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trade<AccountId> {
+    pub pool_id: PoolId,
+    pub pool_account: AccountId,
+    pub asset_in: AssetId,
+    pub asset_out: AssetId,
+    pub amount_in: Balance,
+    pub amount_out: Balance,
+    /// Whole trade fee, paid in `fee_asset`.
+    pub fee: Balance,
+    /// Asset of the fee, which is the asset out of a sell and the asset in of a buy.
+    pub fee_asset: AssetId,
+    /// Part of the fee which goes to the protocol fee receiver instead of the pool.
+    pub protocol_fee: Balance,
+    /// Amount in relative to the reserve of the asset in.
+    pub price_impact: Permill,
+    /// Swap in a concentrated liquidity pool, which changes its state when the trade is executed.
+    pub concentrated_swap: Option<SwapResult>,
+    /// Number of ticks of a concentrated liquidity pool read by the swap.
+    pub ticks: u32,
+}
+
+/// Average price and liquidity of a pool over an oracle window.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct OracleEntry<BlockNumber> {
+    /// Price of the first asset of the pair denominated in the second asset.
+    pub price: FixedU128,
+    /// Liquidity of the pool, as square root of the product of reserves.
+    pub liquidity: Balance,
+    /// Block in which the entry was last updated.
+    pub updated_at: BlockNumber,
+}
+
+/// Dynamic trade fee of a pool.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct FeeEntry<BlockNumber> {
+    /// Fee after the last trade.
+    pub fee: Permill,
+    /// Block of the last trade.
+    pub updated_at: BlockNumber,
+}
+
+/// Sum of weights of both assets of a liquidity bootstrapping pool.
+pub const MAX_WEIGHT: LBPWeight = 100_000_000;
+
+/// Parameters of a liquidity bootstrapping pool.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct LBPParams<AccountId, BlockNumber> {
+    /// Account which provided the liquidity and which alone can withdraw it after the sale.
+    pub owner: AccountId,
+    /// Block in which the sale starts.
+    pub start: BlockNumber,
+    /// Block in which the sale ends.
+    pub end: BlockNumber,
+    /// Weight of the first asset of the pair at the start of the sale.
+    pub initial_weight: LBPWeight,
+    /// Weight of the first asset of the pair at the end of the sale.
+    pub final_weight: LBPWeight,
+}
+
+/// Maximum amplification of a stableswap pool.
+pub const MAX_AMPLIFICATION: Balance = 10_000;
+
+/// Parameters of a stableswap pool.
+///
+/// Amplification changes linearly from `initial_amplification` in block `start`
+/// to `final_amplification` in block `end`.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct StableParams<BlockNumber> {
+    pub initial_amplification: Balance,
+    pub final_amplification: Balance,
+    pub start: BlockNumber,
+    pub end: BlockNumber,
+}
+
+/// Parameters of a concentrated liquidity pool.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct ConcentratedParams {
+    /// Ticks of positions must be multiples of the spacing.
+    pub tick_spacing: Tick,
+}
+
+/// State of a concentrated liquidity pool.
+///
+/// Asset A is the first asset of the pair.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct ConcentratedState {
+    /// Square root of price of asset A denominated in asset B.
+    pub sqrt_price: SqrtPrice,
+    /// Highest tick not above the price.
+    pub tick: Tick,
+    /// Liquidity of positions which contain the price.
+    pub liquidity: Liquidity,
+    /// Fees of asset A per unit of liquidity since the pool was created.
+    pub fee_growth_a: FeeGrowth,
+    /// Fees of asset B per unit of liquidity since the pool was created.
+    pub fee_growth_b: FeeGrowth,
+    /// Number of initialized ticks, at most `MaxTicksPerPool`.
+    pub ticks: u32,
+}
+
+/// Tick of a concentrated liquidity pool which is the end of at least one position.
+#[derive(Encode, Decode, Clone, Copy, Debug, Default, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct TickInfo {
+    /// Liquidity of all positions ending at the tick.
+    pub liquidity_gross: Liquidity,
+    /// Change of liquidity when the price crosses the tick upwards.
+    pub liquidity_net: i128,
+    /// Fee growth of asset A on the other side of the tick than the price.
+    pub fee_growth_outside_a: FeeGrowth,
+    /// Fee growth of asset B on the other side of the tick than the price.
+    pub fee_growth_outside_b: FeeGrowth,
+}
+
+/// Liquidity provided to a concentrated liquidity pool in a range of prices.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Position<AccountId> {
+    pub owner: AccountId,
+    pub pool_id: PoolId,
+    pub tick_lower: Tick,
+    pub tick_upper: Tick,
+    pub liquidity: Liquidity,
+    /// Fee growth of asset A inside the range when fees of the position were last updated.
+    pub fee_growth_inside_a: FeeGrowth,
+    /// Fee growth of asset B inside the range when fees of the position were last updated.
+    pub fee_growth_inside_b: FeeGrowth,
+    /// Fees of asset A earned and not collected yet.
+    pub fees_owed_a: Balance,
+    /// Fees of asset B earned and not collected yet.
+    pub fees_owed_b: Balance,
+}
+
+/// Order selling `asset_in` for `asset_out` once the price of `asset_in` reaches `price`.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct LimitOrder<AccountId> {
+    pub owner: AccountId,
+    pub asset_in: AssetId,
+    pub asset_out: AssetId,
+    /// Amount of `asset_in` not sold yet, held in the account of the owner.
+    pub amount: Balance,
+    /// Minimum price of `asset_in` denominated in `asset_out`, for the whole order and each fill.
+    pub price: FixedU128,
+}
+
+/// Schedule selling `amount` of `asset_in` for `asset_out` every `period` blocks until its
+/// budget is used up.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Schedule<AccountId, BlockNumber> {
+    pub owner: AccountId,
+    pub asset_in: AssetId,
+    pub asset_out: AssetId,
+    /// Amount of `asset_in` sold in each execution.
+    pub amount: Balance,
+    /// Number of blocks between executions.
+    pub period: BlockNumber,
+    /// Budget of `asset_in` not sold yet, held in the account of the owner.
+    pub remaining: Balance,
+    /// Maximum difference between the price of an execution and the oracle price.
+    pub slippage: Permill,
+    /// Block of the next execution.
+    pub next_execution: BlockNumber,
+    /// Number of consecutive executions which failed.
+    pub failures: u32,
+}
+
+/// Pricing of a pool.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum PoolType<AccountId, BlockNumber> {
+    /// Constant product pool.
+    XYK,
+    /// Weighted pool with weights changing linearly during the sale.
+    LBP(LBPParams<AccountId, BlockNumber>),
+    /// Stableswap pool for pegged assets.
+    Stable(StableParams<BlockNumber>),
+    /// Constant product pool with liquidity provided in price ranges.
+    Concentrated(ConcentratedParams),
+}
+
+/// Pool of a sorted set of assets.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxAssets))]
+#[codec(mel_bound(AccountId: MaxEncodedLen, BlockNumber: MaxEncodedLen))]
+pub struct PoolInfo<AccountId, BlockNumber, MaxAssets: Get<u32>> {
+    /// Assets of the pool, sorted.
+    pub assets: BoundedVec<AssetId, MaxAssets>,
+    /// Asset of shares of the pool. Liquidity of concentrated liquidity pools is provided
+    /// in positions instead of shares.
+    pub share_asset: Option<AssetId>,
+    pub pool_type: PoolType<AccountId, BlockNumber>,
+    /// Trade fee of the pool.
+    pub fee: Permill,
+}
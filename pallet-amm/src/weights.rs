@@ -1,39 +1,11 @@
-// This file is part of Basilisk-node.
-
-// Copyright (C) 2020-2022  Intergalactic, Limited (GIB).
-// SPDX-License-Identifier: Apache-2.0
-
-// Licensed under the Apache License, Version 2.0 (the "License");
-// you may not use this file except in compliance with the License.
-// You may obtain a copy of the License at
-//
-// http://www.apache.org/licenses/LICENSE-2.0
-//
-// Unless required by applicable law or agreed to in writing, software
-// distributed under the License is distributed on an "AS IS" BASIS,
-// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
-// See the License for the specific language governing permissions and
-// limitations under the License.
-
-//! Autogenerated weights for amm
+//! Placeholder weights for amm
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 3.0.0
-//! DATE: 2021-03-18, STEPS: [5, ], REPEAT: 20, LOW RANGE: [], HIGH RANGE: []
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
-
-// Executed Command:
-// target/release/hydra-dx
-// benchmark
-// --chain=dev
-// --steps=5
-// --repeat=20
-// --pallet=amm
-// --extrinsic=*
-// --execution=wasm
-// --wasm-execution=compiled
-// --heap-pages=4096
-// --output=weights.rs
-// --template=.maintain/pallet-weight-template.hbs
+//! THESE WEIGHTS ARE NOT BENCHMARK OUTPUT. Times are rounded up, conservative estimates
+//! and reads and writes are counted from the storage each call accesses, listed above
+//! the trades. Token accounts of trades are those of the trader and the pool in both assets
+//! and of the protocol fee receiver. Run the benchmarks in `benchmarking.rs` against
+//! the target runtime and replace this file with the generated one before using it
+//! in production.
 
 #![allow(unused_parens)]
 #![allow(unused_imports)]
@@ -48,68 +20,458 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for amm.
 pub trait WeightInfo {
     fn create_pool() -> Weight;
+    fn create_lbp_pool() -> Weight;
+    fn create_stable_pool() -> Weight;
+    fn update_amplification() -> Weight;
+    fn update_pool_fee() -> Weight;
     fn add_liquidity() -> Weight;
     fn remove_liquidity() -> Weight;
+    fn add_liquidity_single_asset() -> Weight;
+    fn remove_liquidity_single_asset() -> Weight;
     fn sell() -> Weight;
     fn buy() -> Weight;
+    fn flash_swap() -> Weight;
+    fn router_sell(n: u32) -> Weight;
+    fn router_buy(n: u32) -> Weight;
+    fn update_oracles(p: u32, w: u32) -> Weight;
+    fn create_multi_asset_pool(n: u32) -> Weight;
+    fn add_liquidity_multi_asset(n: u32) -> Weight;
+    fn remove_liquidity_multi_asset(n: u32) -> Weight;
+    fn sell_multi_asset(n: u32) -> Weight;
+    fn buy_multi_asset(n: u32) -> Weight;
+    fn create_concentrated_pool() -> Weight;
+    fn mint_position() -> Weight;
+    fn burn_position() -> Weight;
+    fn collect_fees() -> Weight;
+    fn swap_ticks(t: u32) -> Weight;
+    fn place_limit_order() -> Weight;
+    fn cancel_limit_order() -> Weight;
+    fn execute_limit_orders(n: u32) -> Weight;
+    fn schedule_sell() -> Weight;
+    fn cancel_schedule() -> Weight;
+    fn execute_schedules(n: u32) -> Weight;
 }
 
-/// Weights for amm using the hydraDX node and recommended hardware.
+/// Placeholder weights for amm, to be replaced by weights generated on reference hardware.
 pub struct HydraWeight<T>(PhantomData<T>);
 
 impl<T: frame_system::Config> WeightInfo for HydraWeight<T> {
     fn create_pool() -> Weight {
-        Weight::from_ref_time(189_645_000 as u64)
+        Weight::from_ref_time(200_000_000 as u64)
             .saturating_add(T::DbWeight::get().reads(11 as u64))
             .saturating_add(T::DbWeight::get().writes(13 as u64))
     }
+    fn create_lbp_pool() -> Weight {
+        Weight::from_ref_time(200_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(12 as u64))
+            .saturating_add(T::DbWeight::get().writes(12 as u64))
+    }
+    fn create_stable_pool() -> Weight {
+        Weight::from_ref_time(250_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(11 as u64))
+            .saturating_add(T::DbWeight::get().writes(13 as u64))
+    }
+    fn update_amplification() -> Weight {
+        Weight::from_ref_time(30_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(1 as u64))
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+    fn update_pool_fee() -> Weight {
+        Weight::from_ref_time(30_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(1 as u64))
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
     fn add_liquidity() -> Weight {
-        Weight::from_ref_time(171_602_000 as u64)
+        Weight::from_ref_time(200_000_000 as u64)
             .saturating_add(T::DbWeight::get().reads(9 as u64))
             .saturating_add(T::DbWeight::get().writes(8 as u64))
     }
     fn remove_liquidity() -> Weight {
-        Weight::from_ref_time(170_846_000 as u64)
+        Weight::from_ref_time(200_000_000 as u64)
             .saturating_add(T::DbWeight::get().reads(8 as u64))
             .saturating_add(T::DbWeight::get().writes(7 as u64))
     }
+    fn add_liquidity_single_asset() -> Weight {
+        Weight::from_ref_time(200_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(11 as u64))
+            .saturating_add(T::DbWeight::get().writes(8 as u64))
+    }
+    fn remove_liquidity_single_asset() -> Weight {
+        Weight::from_ref_time(200_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(10 as u64))
+            .saturating_add(T::DbWeight::get().writes(7 as u64))
+    }
+    // Storage: XYK PairPools (r:1 w:0)
+    // Storage: XYK Pools (r:1 w:0)
+    // Storage: XYK DynamicFees (r:1 w:1)
+    // Storage: Tokens Accounts (r:5 w:5)
     fn sell() -> Weight {
-        Weight::from_ref_time(122_125_000 as u64)
-            .saturating_add(T::DbWeight::get().reads(5 as u64))
-            .saturating_add(T::DbWeight::get().writes(4 as u64))
+        Weight::from_ref_time(150_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(8 as u64))
+            .saturating_add(T::DbWeight::get().writes(6 as u64))
     }
+    // Storage: XYK PairPools (r:1 w:0)
+    // Storage: XYK Pools (r:1 w:0)
+    // Storage: XYK DynamicFees (r:1 w:1)
+    // Storage: Tokens Accounts (r:5 w:5)
     fn buy() -> Weight {
-        Weight::from_ref_time(121_289_000 as u64)
+        Weight::from_ref_time(150_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(8 as u64))
+            .saturating_add(T::DbWeight::get().writes(6 as u64))
+    }
+    // Storage: XYK PairPools (r:1 w:0)
+    // Storage: XYK Pools (r:1 w:0)
+    // Storage: XYK DynamicFees (r:1 w:1)
+    // Storage: Tokens TotalIssuance (r:1 w:0)
+    // Storage: Tokens Accounts (r:5 w:5)
+    fn flash_swap() -> Weight {
+        Weight::from_ref_time(150_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(9 as u64))
+            .saturating_add(T::DbWeight::get().writes(6 as u64))
+    }
+    // Storage: XYK PairPools (r:1 w:0), per hop
+    // Storage: XYK Pools (r:1 w:0), per hop
+    // Storage: XYK DynamicFees (r:1 w:1), per hop
+    // Storage: Tokens Accounts (r:5 w:5), per hop
+    fn router_sell(n: u32) -> Weight {
+        Weight::from_ref_time(20_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(150_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads((8 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes((6 as u64).saturating_mul(n as u64)))
+    }
+    // Storage: XYK PairPools (r:1 w:0), per hop
+    // Storage: XYK Pools (r:1 w:0), per hop
+    // Storage: XYK DynamicFees (r:1 w:1), per hop
+    // Storage: Tokens Accounts (r:5 w:5), per hop
+    fn router_buy(n: u32) -> Weight {
+        Weight::from_ref_time(20_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(150_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads((8 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes((6 as u64).saturating_mul(n as u64)))
+    }
+    fn update_oracles(p: u32, w: u32) -> Weight {
+        Weight::from_ref_time(10_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(30_000_000 as u64).saturating_mul(p as u64))
+            .saturating_add(
+                Weight::from_ref_time(10_000_000 as u64)
+                    .saturating_mul((p as u64).saturating_mul(w as u64)),
+            )
+            .saturating_add(T::DbWeight::get().reads((6 as u64).saturating_mul(p as u64)))
+            .saturating_add(T::DbWeight::get().reads((p as u64).saturating_mul(w as u64)))
+            .saturating_add(T::DbWeight::get().writes((p as u64).saturating_mul(w as u64)))
+    }
+    fn create_multi_asset_pool(n: u32) -> Weight {
+        Weight::from_ref_time(100_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(50_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads(7 as u64))
+            .saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes(7 as u64))
+            .saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(n as u64)))
+    }
+    fn add_liquidity_multi_asset(n: u32) -> Weight {
+        Weight::from_ref_time(70_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(50_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads(4 as u64))
+            .saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes(2 as u64))
+            .saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(n as u64)))
+    }
+    fn remove_liquidity_multi_asset(n: u32) -> Weight {
+        Weight::from_ref_time(60_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(50_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads(4 as u64))
+            .saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes(2 as u64))
+            .saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(n as u64)))
+    }
+    fn sell_multi_asset(n: u32) -> Weight {
+        Weight::from_ref_time(90_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(40_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads(6 as u64))
+            .saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes(6 as u64))
+    }
+    fn buy_multi_asset(n: u32) -> Weight {
+        Weight::from_ref_time(90_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(40_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads(6 as u64))
+            .saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes(6 as u64))
+    }
+    fn create_concentrated_pool() -> Weight {
+        Weight::from_ref_time(70_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(5 as u64))
+            .saturating_add(T::DbWeight::get().writes(6 as u64))
+    }
+    fn mint_position() -> Weight {
+        Weight::from_ref_time(150_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(10 as u64))
+            .saturating_add(T::DbWeight::get().writes(9 as u64))
+    }
+    fn burn_position() -> Weight {
+        Weight::from_ref_time(200_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(10 as u64))
+            .saturating_add(T::DbWeight::get().writes(9 as u64))
+    }
+    fn collect_fees() -> Weight {
+        Weight::from_ref_time(100_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(8 as u64))
+            .saturating_add(T::DbWeight::get().writes(6 as u64))
+    }
+    // Storage: XYK Ticks (r:t w:t)
+    fn swap_ticks(t: u32) -> Weight {
+        Weight::from_ref_time(0 as u64)
+            .saturating_add(Weight::from_ref_time(20_000_000 as u64).saturating_mul(t as u64))
+            .saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(t as u64)))
+            .saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(t as u64)))
+    }
+    fn place_limit_order() -> Weight {
+        Weight::from_ref_time(50_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(4 as u64))
+            .saturating_add(T::DbWeight::get().writes(3 as u64))
+    }
+    fn cancel_limit_order() -> Weight {
+        Weight::from_ref_time(50_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(2 as u64))
+            .saturating_add(T::DbWeight::get().writes(2 as u64))
+    }
+    // Every order is sold after `FILL_ATTEMPTS` calculations of its trade, which read
+    // the same storage, so that only the calculations add to the time of a fill.
+    fn execute_limit_orders(n: u32) -> Weight {
+        Weight::from_ref_time(10_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(300_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads(1 as u64))
+            .saturating_add(T::DbWeight::get().reads((10 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+            .saturating_add(T::DbWeight::get().writes((7 as u64).saturating_mul(n as u64)))
+    }
+    fn schedule_sell() -> Weight {
+        Weight::from_ref_time(60_000_000 as u64)
             .saturating_add(T::DbWeight::get().reads(5 as u64))
             .saturating_add(T::DbWeight::get().writes(4 as u64))
     }
+    fn cancel_schedule() -> Weight {
+        Weight::from_ref_time(50_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(3 as u64))
+            .saturating_add(T::DbWeight::get().writes(3 as u64))
+    }
+    fn execute_schedules(n: u32) -> Weight {
+        Weight::from_ref_time(10_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(150_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads(1 as u64))
+            .saturating_add(T::DbWeight::get().reads((12 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+            .saturating_add(T::DbWeight::get().writes((8 as u64).saturating_mul(n as u64)))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
     fn create_pool() -> Weight {
-        Weight::from_ref_time(189_645_000 as u64)
+        Weight::from_ref_time(200_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(11 as u64))
+            .saturating_add(RocksDbWeight::get().writes(13 as u64))
+    }
+    fn create_lbp_pool() -> Weight {
+        Weight::from_ref_time(200_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(12 as u64))
+            .saturating_add(RocksDbWeight::get().writes(12 as u64))
+    }
+    fn create_stable_pool() -> Weight {
+        Weight::from_ref_time(250_000_000 as u64)
             .saturating_add(RocksDbWeight::get().reads(11 as u64))
             .saturating_add(RocksDbWeight::get().writes(13 as u64))
     }
+    fn update_amplification() -> Weight {
+        Weight::from_ref_time(30_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(1 as u64))
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+    fn update_pool_fee() -> Weight {
+        Weight::from_ref_time(30_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(1 as u64))
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
     fn add_liquidity() -> Weight {
-        Weight::from_ref_time(171_602_000 as u64)
+        Weight::from_ref_time(200_000_000 as u64)
             .saturating_add(RocksDbWeight::get().reads(9 as u64))
             .saturating_add(RocksDbWeight::get().writes(8 as u64))
     }
     fn remove_liquidity() -> Weight {
-        Weight::from_ref_time(170_846_000 as u64)
+        Weight::from_ref_time(200_000_000 as u64)
             .saturating_add(RocksDbWeight::get().reads(8 as u64))
             .saturating_add(RocksDbWeight::get().writes(7 as u64))
     }
+    fn add_liquidity_single_asset() -> Weight {
+        Weight::from_ref_time(200_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(11 as u64))
+            .saturating_add(RocksDbWeight::get().writes(8 as u64))
+    }
+    fn remove_liquidity_single_asset() -> Weight {
+        Weight::from_ref_time(200_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(10 as u64))
+            .saturating_add(RocksDbWeight::get().writes(7 as u64))
+    }
+    // Storage: XYK PairPools (r:1 w:0)
+    // Storage: XYK Pools (r:1 w:0)
+    // Storage: XYK DynamicFees (r:1 w:1)
+    // Storage: Tokens Accounts (r:5 w:5)
     fn sell() -> Weight {
-        Weight::from_ref_time(122_125_000 as u64)
-            .saturating_add(RocksDbWeight::get().reads(5 as u64))
-            .saturating_add(RocksDbWeight::get().writes(4 as u64))
+        Weight::from_ref_time(150_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(8 as u64))
+            .saturating_add(RocksDbWeight::get().writes(6 as u64))
     }
+    // Storage: XYK PairPools (r:1 w:0)
+    // Storage: XYK Pools (r:1 w:0)
+    // Storage: XYK DynamicFees (r:1 w:1)
+    // Storage: Tokens Accounts (r:5 w:5)
     fn buy() -> Weight {
-        Weight::from_ref_time(121_289_000 as u64)
+        Weight::from_ref_time(150_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(8 as u64))
+            .saturating_add(RocksDbWeight::get().writes(6 as u64))
+    }
+    // Storage: XYK PairPools (r:1 w:0)
+    // Storage: XYK Pools (r:1 w:0)
+    // Storage: XYK DynamicFees (r:1 w:1)
+    // Storage: Tokens TotalIssuance (r:1 w:0)
+    // Storage: Tokens Accounts (r:5 w:5)
+    fn flash_swap() -> Weight {
+        Weight::from_ref_time(150_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(9 as u64))
+            .saturating_add(RocksDbWeight::get().writes(6 as u64))
+    }
+    // Storage: XYK PairPools (r:1 w:0), per hop
+    // Storage: XYK Pools (r:1 w:0), per hop
+    // Storage: XYK DynamicFees (r:1 w:1), per hop
+    // Storage: Tokens Accounts (r:5 w:5), per hop
+    fn router_sell(n: u32) -> Weight {
+        Weight::from_ref_time(20_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(150_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads((8 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes((6 as u64).saturating_mul(n as u64)))
+    }
+    // Storage: XYK PairPools (r:1 w:0), per hop
+    // Storage: XYK Pools (r:1 w:0), per hop
+    // Storage: XYK DynamicFees (r:1 w:1), per hop
+    // Storage: Tokens Accounts (r:5 w:5), per hop
+    fn router_buy(n: u32) -> Weight {
+        Weight::from_ref_time(20_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(150_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads((8 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes((6 as u64).saturating_mul(n as u64)))
+    }
+    fn update_oracles(p: u32, w: u32) -> Weight {
+        Weight::from_ref_time(10_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(30_000_000 as u64).saturating_mul(p as u64))
+            .saturating_add(
+                Weight::from_ref_time(10_000_000 as u64)
+                    .saturating_mul((p as u64).saturating_mul(w as u64)),
+            )
+            .saturating_add(RocksDbWeight::get().reads((6 as u64).saturating_mul(p as u64)))
+            .saturating_add(RocksDbWeight::get().reads((p as u64).saturating_mul(w as u64)))
+            .saturating_add(RocksDbWeight::get().writes((p as u64).saturating_mul(w as u64)))
+    }
+    fn create_multi_asset_pool(n: u32) -> Weight {
+        Weight::from_ref_time(100_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(50_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads(7 as u64))
+            .saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes(7 as u64))
+            .saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(n as u64)))
+    }
+    fn add_liquidity_multi_asset(n: u32) -> Weight {
+        Weight::from_ref_time(70_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(50_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads(4 as u64))
+            .saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes(2 as u64))
+            .saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(n as u64)))
+    }
+    fn remove_liquidity_multi_asset(n: u32) -> Weight {
+        Weight::from_ref_time(60_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(50_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads(4 as u64))
+            .saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes(2 as u64))
+            .saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(n as u64)))
+    }
+    fn sell_multi_asset(n: u32) -> Weight {
+        Weight::from_ref_time(90_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(40_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads(6 as u64))
+            .saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes(6 as u64))
+    }
+    fn buy_multi_asset(n: u32) -> Weight {
+        Weight::from_ref_time(90_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(40_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads(6 as u64))
+            .saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes(6 as u64))
+    }
+    fn create_concentrated_pool() -> Weight {
+        Weight::from_ref_time(70_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(5 as u64))
+            .saturating_add(RocksDbWeight::get().writes(6 as u64))
+    }
+    fn mint_position() -> Weight {
+        Weight::from_ref_time(150_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(10 as u64))
+            .saturating_add(RocksDbWeight::get().writes(9 as u64))
+    }
+    fn burn_position() -> Weight {
+        Weight::from_ref_time(200_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(10 as u64))
+            .saturating_add(RocksDbWeight::get().writes(9 as u64))
+    }
+    fn collect_fees() -> Weight {
+        Weight::from_ref_time(100_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(8 as u64))
+            .saturating_add(RocksDbWeight::get().writes(6 as u64))
+    }
+    // Storage: XYK Ticks (r:t w:t)
+    fn swap_ticks(t: u32) -> Weight {
+        Weight::from_ref_time(0 as u64)
+            .saturating_add(Weight::from_ref_time(20_000_000 as u64).saturating_mul(t as u64))
+            .saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(t as u64)))
+            .saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(t as u64)))
+    }
+    fn place_limit_order() -> Weight {
+        Weight::from_ref_time(50_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(4 as u64))
+            .saturating_add(RocksDbWeight::get().writes(3 as u64))
+    }
+    fn cancel_limit_order() -> Weight {
+        Weight::from_ref_time(50_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(2 as u64))
+            .saturating_add(RocksDbWeight::get().writes(2 as u64))
+    }
+    // Every order is sold after `FILL_ATTEMPTS` calculations of its trade, which read
+    // the same storage, so that only the calculations add to the time of a fill.
+    fn execute_limit_orders(n: u32) -> Weight {
+        Weight::from_ref_time(10_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(300_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads(1 as u64))
+            .saturating_add(RocksDbWeight::get().reads((10 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+            .saturating_add(RocksDbWeight::get().writes((7 as u64).saturating_mul(n as u64)))
+    }
+    fn schedule_sell() -> Weight {
+        Weight::from_ref_time(60_000_000 as u64)
             .saturating_add(RocksDbWeight::get().reads(5 as u64))
             .saturating_add(RocksDbWeight::get().writes(4 as u64))
     }
+    fn cancel_schedule() -> Weight {
+        Weight::from_ref_time(50_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(3 as u64))
+            .saturating_add(RocksDbWeight::get().writes(3 as u64))
+    }
+    fn execute_schedules(n: u32) -> Weight {
+        Weight::from_ref_time(10_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(150_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads(1 as u64))
+            .saturating_add(RocksDbWeight::get().reads((12 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+            .saturating_add(RocksDbWeight::get().writes((8 as u64).saturating_mul(n as u64)))
+    }
 }